@@ -0,0 +1,323 @@
+use crate::api::models::{ResponseApiResponse, ResponseRequest, ResponseStatus};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Bumped when the fingerprint or entry format changes, orphaning old entries
+const FINGERPRINT_VERSION: &str = "v2";
+
+const STATS_FILE: &str = "stats.json";
+
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: u64,
+    response: ResponseApiResponse,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Counters {
+    hits: u64,
+    misses: u64,
+}
+
+/// Entries on disk and lifetime hit counts
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// The server and account a request goes to. Responses are only served back
+/// to the same scope; the API key itself is kept as a hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheScope {
+    base_url: String,
+    key_hash: String,
+}
+
+impl CacheScope {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            key_hash: hex_sha256(api_key.as_bytes()),
+        }
+    }
+}
+
+/// Disk-backed cache of completed responses, keyed by a hash of the request.
+/// Each entry is one JSON file; expired entries are dropped on read and the
+/// oldest entries are evicted once the directory grows past its size limit.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+    force: bool,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            ttl: DEFAULT_TTL,
+            max_bytes: DEFAULT_MAX_BYTES,
+            force: false,
+        }
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Also cache sampled requests (temperature other than 0)
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// SHA-256 of the scope and the request serialized with sorted keys
+    pub fn fingerprint(scope: &CacheScope, request: &ResponseRequest) -> String {
+        let value = serde_json::to_value(request).unwrap_or_default();
+        let mut canonical = format!(
+            "{}\n{}\n{}\n",
+            FINGERPRINT_VERSION, scope.base_url, scope.key_hash
+        );
+        write_canonical(&value, &mut canonical);
+
+        hex_sha256(canonical.as_bytes())
+    }
+
+    /// Streaming and background requests are never cached; sampled requests
+    /// only when forced, since a rerun is expected to give a different answer
+    pub fn accepts(&self, request: &ResponseRequest) -> bool {
+        if request.stream == Some(true) || request.background == Some(true) {
+            return false;
+        }
+        self.force || request.temperature == Some(0.0)
+    }
+
+    /// The cached response for `request` in `scope`, if present and not
+    /// expired
+    pub fn get(
+        &self,
+        scope: &CacheScope,
+        request: &ResponseRequest,
+    ) -> Option<ResponseApiResponse> {
+        let path = self.entry_path(&Self::fingerprint(scope, request));
+        let entry = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CacheEntry>(&contents).ok());
+
+        let hit = match entry {
+            Some(entry) if !self.is_expired(entry.created_at) => Some(entry.response),
+            Some(_) => {
+                let _ = fs::remove_file(&path);
+                None
+            }
+            None => None,
+        };
+        self.count(hit.is_some());
+
+        hit.map(|mut response| {
+            response.cached = true;
+            response
+        })
+    }
+
+    /// Store a completed response; anything else is not worth replaying
+    pub fn put(
+        &self,
+        scope: &CacheScope,
+        request: &ResponseRequest,
+        response: &ResponseApiResponse,
+    ) -> io::Result<()> {
+        if response.status != Some(ResponseStatus::Completed) {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+
+        let fingerprint = Self::fingerprint(scope, request);
+        let entry = CacheEntry {
+            created_at: now(),
+            response: ResponseApiResponse {
+                cached: false,
+                cost: None,
+                ..serde_json::from_value(serde_json::to_value(response)?)?
+            },
+        };
+
+        // Write then rename so readers never see a partial entry
+        let temp = self.dir.join(format!("{}.tmp", fingerprint));
+        fs::write(&temp, serde_json::to_vec(&entry)?)?;
+        fs::rename(&temp, self.entry_path(&fingerprint))?;
+
+        self.evict()
+    }
+
+    pub fn stats(&self) -> io::Result<CacheStats> {
+        let counters = self.counters();
+        let mut stats = CacheStats {
+            hits: counters.hits,
+            misses: counters.misses,
+            ..Default::default()
+        };
+        for (path, bytes, _) in self.entries()? {
+            stats.entries += 1;
+            stats.bytes += bytes;
+            let created_at = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<CacheEntry>(&contents).ok())
+                .map(|entry| entry.created_at);
+            if created_at.is_none_or(|created_at| self.is_expired(created_at)) {
+                stats.expired += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Remove every entry and reset the counters; returns the entries removed
+    pub fn clear(&self) -> io::Result<usize> {
+        let entries = self.entries()?;
+        for (path, _, _) in &entries {
+            fs::remove_file(path)?;
+        }
+        match fs::remove_file(self.dir.join(STATS_FILE)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        Ok(entries.len())
+    }
+
+    fn entry_path(&self, fingerprint: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", fingerprint))
+    }
+
+    fn is_expired(&self, created_at: u64) -> bool {
+        now().saturating_sub(created_at) > self.ttl.as_secs()
+    }
+
+    // Entry files with their size and modification time; a missing
+    // directory is an empty cache
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let entry = entry?;
+            let path = entry.path();
+            let is_entry = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_name().is_some_and(|name| name != STATS_FILE);
+            if is_entry {
+                let metadata = entry.metadata()?;
+                entries.push((path, metadata.len(), metadata.modified()?));
+            }
+        }
+        Ok(entries)
+    }
+
+    // Delete the oldest entries until the cache fits in `max_bytes`
+    fn evict(&self) -> io::Result<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, bytes, _)| bytes).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        for (path, bytes, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(path)?;
+            total -= bytes;
+        }
+        Ok(())
+    }
+
+    fn counters(&self) -> Counters {
+        fs::read_to_string(self.dir.join(STATS_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    // Counting is best effort; a read-only cache still serves hits
+    fn count(&self, hit: bool) {
+        let mut counters = self.counters();
+        if hit {
+            counters.hits += 1;
+        } else {
+            counters.misses += 1;
+        }
+        if let Ok(contents) = serde_json::to_vec(&counters) {
+            let _ = fs::create_dir_all(&self.dir);
+            let _ = fs::write(self.dir.join(STATS_FILE), contents);
+        }
+    }
+}
+
+// JSON with object keys sorted at every level, independent of serde_json's
+// map ordering
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(value, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn hex_sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,354 @@
+use crate::api::errors::CassetteError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+const REDACTED: &str = "[REDACTED]";
+
+// Request headers that carry credentials and never reach a cassette file
+const SECRET_HEADERS: &[&str] = &["authorization", "openai-organization", "openai-project"];
+
+/// Whether a cassette captures live traffic or serves it back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+impl FromStr for CassetteMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            _ => Err(format!(
+                "unknown cassette mode '{}', expected record or replay",
+                s
+            )),
+        }
+    }
+}
+
+/// How a replayed request is matched against the recorded ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// The next recorded interaction, with the same method, URL and body
+    #[default]
+    Strict,
+    /// Any unused interaction with the same method and URL
+    Loose,
+}
+
+impl FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "loose" => Ok(Self::Loose),
+            _ => Err(format!(
+                "unknown cassette matching '{}', expected strict or loose",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Value,
+}
+
+/// The raw response body, so SSE streams are kept event by event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Loaded on first use, so a missing file surfaces as a request error
+    interactions: Option<Vec<Interaction>>,
+    used: Vec<bool>,
+}
+
+/// A JSON file of HTTP interactions. Record mode performs each request and
+/// appends it with credentials redacted; replay mode answers requests from the
+/// file without touching the network.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    matching: MatchMode,
+    // Lowercase names of further request headers to redact
+    redacted_headers: Vec<String>,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    pub fn new(path: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        Self {
+            path: path.into(),
+            mode,
+            matching: MatchMode::default(),
+            redacted_headers: Vec::new(),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, CassetteMode::Record)
+    }
+
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, CassetteMode::Replay)
+    }
+
+    pub fn matching(mut self, matching: MatchMode) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Also redact these request headers, e.g. custom auth headers, on top of
+    /// `authorization` and the OpenAI organization and project headers
+    pub fn redact_headers<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.redacted_headers.extend(
+            names
+                .into_iter()
+                .map(|name| name.as_ref().trim().to_ascii_lowercase())
+                .filter(|name| !name.is_empty()),
+        );
+        self
+    }
+
+    /// Cassette named by `OPENAI_API_CASSETTE`. It replays when the file
+    /// exists and records otherwise, unless `OPENAI_API_CASSETTE_MODE` says
+    /// which; `OPENAI_API_CASSETTE_MATCH` picks strict (default) or loose, and
+    /// `OPENAI_API_CASSETTE_REDACT` lists more headers to redact, by comma.
+    pub fn from_env() -> Result<Option<Self>, CassetteError> {
+        let Some(path) = env::var_os("OPENAI_API_CASSETTE").filter(|path| !path.is_empty()) else {
+            return Ok(None);
+        };
+        let path = PathBuf::from(path);
+
+        let mode = match env::var("OPENAI_API_CASSETTE_MODE") {
+            Ok(mode) => mode.parse().map_err(CassetteError::InvalidSetting)?,
+            Err(_) if path.exists() => CassetteMode::Replay,
+            Err(_) => CassetteMode::Record,
+        };
+        let matching = match env::var("OPENAI_API_CASSETTE_MATCH") {
+            Ok(matching) => matching.parse().map_err(CassetteError::InvalidSetting)?,
+            Err(_) => MatchMode::default(),
+        };
+        let redacted = env::var("OPENAI_API_CASSETTE_REDACT").unwrap_or_default();
+        Ok(Some(
+            Self::new(path, mode)
+                .matching(matching)
+                .redact_headers(redacted.split(',')),
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Recorded interactions, in order
+    pub fn interactions(&self) -> Result<Vec<Interaction>, CassetteError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(self.loaded(&mut state)?.clone())
+    }
+
+    /// Response recorded for `request`, each interaction served at most once
+    pub fn find(&self, request: &RecordedRequest) -> Result<RecordedResponse, CassetteError> {
+        let mut state = self.state.lock().unwrap();
+        let matching = self.matching;
+        self.loaded(&mut state)?;
+        let State { interactions, used } = &mut *state;
+        let interactions = interactions.as_ref().unwrap();
+
+        let same_route = |interaction: &Interaction| {
+            interaction.request.method == request.method && interaction.request.url == request.url
+        };
+        let index = match matching {
+            MatchMode::Strict => used
+                .iter()
+                .position(|used| !used)
+                .filter(|&i| same_route(&interactions[i]))
+                .filter(|&i| interactions[i].request.body == request.body),
+            MatchMode::Loose => {
+                (0..interactions.len()).find(|&i| !used[i] && same_route(&interactions[i]))
+            }
+        };
+
+        let Some(index) = index else {
+            return Err(CassetteError::NoMatch {
+                path: self.path.display().to_string(),
+                method: request.method.clone(),
+                url: request.url.clone(),
+            });
+        };
+        used[index] = true;
+        Ok(interactions[index].response.clone())
+    }
+
+    /// Append an interaction, with the configured headers redacted, and
+    /// rewrite the file
+    pub fn save(&self, mut interaction: Interaction) -> Result<(), CassetteError> {
+        for (name, value) in interaction.request.headers.iter_mut() {
+            if self.redacted_headers.contains(name) {
+                *value = REDACTED.to_string();
+            }
+        }
+        let mut state = self.state.lock().unwrap();
+        let interactions = self.loaded(&mut state)?;
+        interactions.push(interaction);
+
+        let file = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        let json =
+            serde_json::to_string_pretty(&file).map_err(|source| self.parse_error(source))?;
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&self.path, json + "\n")
+        };
+        write().map_err(|source| self.io_error(source))
+    }
+
+    // A recording starts empty; a replay needs the file
+    fn loaded<'a>(&self, state: &'a mut State) -> Result<&'a mut Vec<Interaction>, CassetteError> {
+        if state.interactions.is_none() {
+            let interactions = match self.mode {
+                CassetteMode::Record => Vec::new(),
+                CassetteMode::Replay => {
+                    let json =
+                        fs::read_to_string(&self.path).map_err(|source| self.io_error(source))?;
+                    let file: CassetteFile =
+                        serde_json::from_str(&json).map_err(|source| self.parse_error(source))?;
+                    file.interactions
+                }
+            };
+            state.used = vec![false; interactions.len()];
+            state.interactions = Some(interactions);
+        }
+        Ok(state.interactions.as_mut().unwrap())
+    }
+
+    fn io_error(&self, source: std::io::Error) -> CassetteError {
+        CassetteError::Io {
+            path: self.path.display().to_string(),
+            source,
+        }
+    }
+
+    fn parse_error(&self, source: serde_json::Error) -> CassetteError {
+        CassetteError::Parse {
+            path: self.path.display().to_string(),
+            source,
+        }
+    }
+}
+
+impl RecordedRequest {
+    /// A request as it goes on the wire, with credentials redacted,
+    /// including the header values of remote MCP tools in the body
+    pub fn new(method: &str, url: &str, headers: &HeaderMap, mut body: Value) -> Self {
+        let tools = body.get_mut("tools").and_then(Value::as_array_mut);
+        for tool in tools.into_iter().flatten() {
+            if tool["type"] != "mcp" {
+                continue;
+            }
+            let headers = tool.get_mut("headers").and_then(Value::as_object_mut);
+            for value in headers.into_iter().flat_map(|headers| headers.values_mut()) {
+                *value = Value::from(REDACTED);
+            }
+        }
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: redact(headers_map(headers)),
+            body,
+        }
+    }
+}
+
+impl RecordedResponse {
+    pub fn new(status: StatusCode, headers: &HeaderMap, body: String) -> Self {
+        let mut headers = headers_map(headers);
+        headers.remove("set-cookie");
+        Self {
+            status: status.as_u16(),
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    pub fn header_map(&self) -> HeaderMap {
+        self.headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn headers_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn redact(mut headers: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    for (name, value) in headers.iter_mut() {
+        if SECRET_HEADERS.contains(&name.as_str()) {
+            *value = REDACTED.to_string();
+        }
+    }
+    headers
+}
@@ -1,18 +1,149 @@
-use crate::api::errors::OpenAIError;
-use crate::api::models::{ResponseApiResponse, ResponseRequest, ResponseRequestBuilder};
-use reqwest::Client;
+use crate::api::cache::{CacheScope, ResponseCache};
+use crate::api::cassette::{
+    Cassette, CassetteMode, Interaction, RecordedRequest, RecordedResponse,
+};
+use crate::api::errors::{OpenAIError, TransportError, ValidationError};
+#[cfg(feature = "metrics")]
+use crate::api::metrics::MetricsRegistry;
+use crate::api::middleware::{Flow, Middleware};
+use crate::api::models::{
+    InputItem, InputType, McpApprovalRequest, ResponseApiResponse, ResponseRequest,
+    ResponseRequestBuilder,
+};
+use crate::api::rate_limit::{RateLimitPermit, RateLimiter};
+use crate::api::streaming::{self, ResponseStream, StreamEvent};
+use crate::api::telemetry::{self, TelemetryOptions};
+use crate::api::transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport};
+use futures_util::stream::{self, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+const CONTINUATION_PROMPT: &str =
+    "Continue exactly where your previous answer stopped, without repeating anything.";
 
 pub struct OpenAIClient {
-    client: Client,
+    transport: Arc<dyn Transport>,
     api_key: String,
+    base_url: String,
+    cache: Option<ResponseCache>,
+    cassette: Option<Cassette>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    telemetry: TelemetryOptions,
+    max_retries: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<MetricsRegistry>>,
 }
 
+// Backoff before retry `attempt` when the API did not say how long to wait
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(20);
+
 impl OpenAIClient {
     pub fn new(api_key: String) -> Self {
         Self {
-            client: Client::new(),
+            transport: Arc::new(ReqwestTransport::new()),
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cache: None,
+            cassette: None,
+            middleware: Vec::new(),
+            telemetry: TelemetryOptions::default(),
+            max_retries: 0,
+            rate_limiter: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Client for `OPENAI_API_KEY` that sends to `OPENAI_BASE_URL` when set,
+    /// and records or replays through the cassette in `OPENAI_API_CASSETTE`,
+    /// if set. Fails when the key is missing or a cassette setting is unknown.
+    #[allow(clippy::result_large_err)]
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or(ValidationError::MissingApiKey)?;
+        let mut client = Self::new(api_key);
+        if let Some(base_url) = std::env::var("OPENAI_BASE_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+        {
+            client = client.with_base_url(base_url);
         }
+        client.cassette = Cassette::from_env()?;
+        Ok(client)
+    }
+
+    /// Send to another Responses API server, e.g. `http://localhost:8080/v1`
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send requests through `transport` instead of the default reqwest client
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Run `middleware` around every request, after any added before it
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Choose what the `tracing` spans of each request include
+    pub fn with_telemetry(mut self, telemetry: TelemetryOptions) -> Self {
+        self.telemetry = telemetry;
+        self
+    }
+
+    /// Send a request again, up to `max_retries` times, after a rate limit,
+    /// server error, timeout or connection failure. Waits for `retry-after`
+    /// when the API gives it, else backs off exponentially; a `retry-after`
+    /// above 20 seconds is returned as an error instead.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Wait for `limiter` before sending each request, and correct it from
+    /// the rate limit headers of each reply
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Record request counts, latencies, tokens, costs and retries in `metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Serve repeated requests from `cache` and store new responses in it
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Record traffic to, or replay it from, `cassette` instead of the network
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
     }
 
     /// Simple method for basic text responses (backward compatibility)
@@ -37,37 +168,444 @@ impl OpenAIClient {
         &self,
         builder: ResponseRequestBuilder,
     ) -> Result<ResponseApiResponse, OpenAIError> {
-        let request = builder.build();
+        let request = builder.try_build()?;
         self.send_request(request).await
     }
 
-    /// Send a complete request with all parameters
+    /// Send a complete request with all parameters, through the middleware,
+    /// inside an `openai.response` tracing span
     pub async fn send_request(
         &self,
         request: ResponseRequest,
     ) -> Result<ResponseApiResponse, OpenAIError> {
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/responses")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        let status = response.status();
-        let response_text = response.text().await?;
+        let span = telemetry::request_span(&request, self.telemetry);
+        #[cfg(feature = "metrics")]
+        let model = request.model.clone().unwrap_or_default();
+        let started = Instant::now();
+        let result = self.run_middleware(request).instrument(span.clone()).await;
+        match &result {
+            Ok(response) => {
+                telemetry::record_response(&span, response, started.elapsed(), self.telemetry)
+            }
+            Err(error) => telemetry::record_error(&span, error, started.elapsed()),
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            match &result {
+                Ok(response) => metrics.record_response(&model, response, started.elapsed()),
+                Err(error) => metrics.record_error(&model, error, started.elapsed()),
+            }
+        }
+        result
+    }
 
-        if !status.is_success() {
-            return Err(OpenAIError::parse_api_error(&response_text, status));
+    async fn run_middleware(
+        &self,
+        mut request: ResponseRequest,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        let middleware = &self.middleware;
+        let mut headers = HeaderMap::new();
+        let response = match before_request(middleware, &mut request, &mut headers).await {
+            Ok(Flow::Continue) => self.dispatch(&request, headers).await,
+            Ok(Flow::Respond(response)) => Ok(*response),
+            Err(error) => return on_error(middleware, &request, error).await,
+        };
+
+        let response = match response {
+            Ok(mut response) => after_response(middleware, &request, &mut response)
+                .await
+                .map(|()| response),
+            Err(error) => Err(error),
+        };
+        match response {
+            Ok(response) => Ok(response),
+            Err(error) => on_error(middleware, &request, error).await,
         }
+    }
 
-        let response_data: ResponseApiResponse = serde_json::from_str(&response_text)
-            .map_err(|e| OpenAIError::JsonParseError(e.to_string()))?;
+    /// Send `request` with `stream` set and return its server-sent events as
+    /// they arrive. Middleware runs as for `send_request`: `before_request`
+    /// before sending, `after_response` on the final response event and
+    /// `on_error` on a failure before or during the stream. A response from
+    /// `Flow::Respond` or `on_error` arrives as a single final event. Like
+    /// `send_request` it runs inside an `openai.response` span, completed when
+    /// the final event or an error arrives, which is also when its metrics
+    /// are recorded.
+    pub async fn stream_request(
+        &self,
+        mut request: ResponseRequest,
+    ) -> Result<ResponseStream, OpenAIError> {
+        request.stream = Some(true);
+        let outcome = StreamOutcome {
+            span: telemetry::request_span(&request, self.telemetry),
+            started: Instant::now(),
+            telemetry: self.telemetry,
+            #[cfg(feature = "metrics")]
+            metrics: self
+                .metrics
+                .clone()
+                .map(|metrics| (metrics, request.model.clone().unwrap_or_default())),
+        };
+        let middleware = self.middleware.clone();
+        let opened = async {
+            let mut headers = HeaderMap::new();
+            let events = match before_request(&middleware, &mut request, &mut headers).await {
+                Ok(Flow::Continue) => self.open_stream(&request, headers).await,
+                Ok(Flow::Respond(response)) => Ok(finished(&response)),
+                Err(error) => Err(error),
+            };
+            match events {
+                Ok(events) => Ok(events),
+                Err(error) => on_error(&middleware, &request, error)
+                    .await
+                    .map(|response| finished(&response)),
+            }
+        }
+        .instrument(outcome.span.clone())
+        .await;
+        let events = match opened {
+            Ok(events) => events,
+            Err(error) => {
+                outcome.record(Err(&error));
+                return Err(error);
+            }
+        };
+
+        let request = Arc::new(request);
+        let span = outcome.span.clone();
+        let mut outcome = Some(outcome);
+        let events = events
+            .then(move |event| {
+                let middleware = middleware.clone();
+                let request = request.clone();
+                async move { finish_event(&middleware, &request, event).await }
+            })
+            .inspect(move |event| {
+                let result = match event {
+                    Ok(event) if event.is_final() => event.response().map(Ok),
+                    Ok(_) => None,
+                    Err(error) => Some(Err(error)),
+                };
+                let Some(result) = result else { return };
+                if let Some(outcome) = outcome.take() {
+                    outcome.record(result.as_ref().map_err(|error| *error));
+                }
+            });
+        Ok(in_span(events.boxed(), span))
+    }
+
+    // Answer from the cache or the API
+    async fn dispatch(
+        &self,
+        request: &ResponseRequest,
+        headers: HeaderMap,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        let cache = self.cache.as_ref().filter(|cache| cache.accepts(request));
+        let scope = CacheScope::new(&self.base_url, &self.api_key);
+        if let Some(response) = cache.and_then(|cache| cache.get(&scope, request)) {
+            return Ok(response);
+        }
+
+        telemetry::log_body(self.telemetry, "request", request);
+        // Streamed replies are read to their final event
+        let response_data = if request.stream == Some(true) {
+            streaming::collect_response(self.open_stream(request, headers).await?).await?
+        } else {
+            let body = serde_json::to_value(request)
+                .map_err(|e| OpenAIError::JsonParseError(e.to_string()))?;
+            let url = format!("{}/responses", self.base_url);
+            self.retrying(request, |permit| {
+                let call = self.call(Method::POST, &url, Some(body.clone()), headers.clone());
+                async move {
+                    let result = call.await;
+                    drop(permit);
+                    result
+                }
+            })
+            .await?
+        };
+        telemetry::log_body(self.telemetry, "response", &response_data);
+
+        // A cache that cannot be written only costs a later miss
+        if let Some(cache) = cache {
+            let _ = cache.put(&scope, request, &response_data);
+        }
 
         Ok(response_data)
     }
 
+    // Send a streaming request and decode its events, holding the rate
+    // limiter's in-flight permit until the stream ends
+    async fn open_stream(
+        &self,
+        request: &ResponseRequest,
+        headers: HeaderMap,
+    ) -> Result<ResponseStream, OpenAIError> {
+        let body = serde_json::to_value(request)
+            .map_err(|e| OpenAIError::JsonParseError(e.to_string()))?;
+        let url = format!("{}/responses", self.base_url);
+        self.retrying(request, |permit| {
+            let started = Instant::now();
+            #[cfg(feature = "metrics")]
+            let mut first_token = self.metrics.clone().zip(request.model.clone());
+            let exchange = self.exchange(Method::POST, &url, Some(body.clone()), headers.clone());
+            async move {
+                let response = exchange.await?;
+                if !response.status.is_success() {
+                    let (status, headers) = (response.status, response.headers.clone());
+                    let text = response.text().await?;
+                    return Err(OpenAIError::from_response(status, &headers, &text));
+                }
+                let events = streaming::decode(response.body).inspect(move |event| {
+                    let _held = &permit;
+                    #[cfg(feature = "metrics")]
+                    if let Ok(event) = event {
+                        if event.text_delta().is_some() {
+                            if let Some((metrics, model)) = first_token.take() {
+                                metrics.record_first_token(&model, started.elapsed());
+                            }
+                        }
+                    }
+                    #[cfg(not(feature = "metrics"))]
+                    let _ = (event, started);
+                });
+                Ok(events.boxed())
+            }
+        })
+        .await
+    }
+
+    // Run `attempt` until it succeeds, fails for good or runs out of retries,
+    // waiting for the rate limiter before each try
+    async fn retrying<T, F, Fut>(
+        &self,
+        request: &ResponseRequest,
+        mut attempt: F,
+    ) -> Result<T, OpenAIError>
+    where
+        F: FnMut(Option<RateLimitPermit>) -> Fut,
+        Fut: Future<Output = Result<T, OpenAIError>>,
+    {
+        let mut retries = 0;
+        loop {
+            let permit = match &self.rate_limiter {
+                Some(limiter) => Some(limiter.acquire(&self.api_key, request).await),
+                None => None,
+            };
+            let result = attempt(permit).await;
+            let delay = match &result {
+                Err(error) if error.is_retryable() && retries < self.max_retries => {
+                    retry_delay(error, retries + 1)
+                }
+                _ => None,
+            };
+            match delay {
+                Some(delay) => {
+                    retries += 1;
+                    Span::current().record("retries", retries);
+                    #[cfg(feature = "metrics")]
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_retry(request.model.as_deref().unwrap_or_default());
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+                None => return result,
+            }
+        }
+    }
+
+    /// Fetch a stored response, e.g. to poll a background one
+    pub async fn retrieve_response(&self, id: &str) -> Result<ResponseApiResponse, OpenAIError> {
+        let url = format!("{}/responses/{}", self.base_url, id);
+        self.call_by_id(Method::GET, &url).await
+    }
+
+    /// Cancel a background response that has not finished yet
+    pub async fn cancel_response(&self, id: &str) -> Result<ResponseApiResponse, OpenAIError> {
+        let url = format!("{}/responses/{}/cancel", self.base_url, id);
+        self.call_by_id(Method::POST, &url).await
+    }
+
+    /// Delete a stored response
+    pub async fn delete_response(&self, id: &str) -> Result<(), OpenAIError> {
+        let url = format!("{}/responses/{}", self.base_url, id);
+        self.call_by_id::<serde_json::Value>(Method::DELETE, &url)
+            .await
+            .map(|_| ())
+    }
+
+    // Call a stored response's endpoint once the rate limiter allows it
+    async fn call_by_id<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+    ) -> Result<T, OpenAIError> {
+        let _permit = match &self.rate_limiter {
+            Some(limiter) => Some(limiter.acquire_call(&self.api_key).await),
+            None => None,
+        };
+        self.call(method, url, None, HeaderMap::new()).await
+    }
+
+    // Send a request and parse a successful reply, or map the API error
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<serde_json::Value>,
+        extra_headers: HeaderMap,
+    ) -> Result<T, OpenAIError> {
+        let (status, headers, text) = self.send(method, url, body, extra_headers).await?;
+        if !status.is_success() {
+            return Err(OpenAIError::from_response(status, &headers, &text));
+        }
+        serde_json::from_str(&text).map_err(|e| OpenAIError::JsonParseError(e.to_string()))
+    }
+
+    /// Send a request and return the raw response, through the cassette if any
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<serde_json::Value>,
+        extra_headers: HeaderMap,
+    ) -> Result<(StatusCode, HeaderMap, String), OpenAIError> {
+        let response = self.exchange(method, url, body, extra_headers).await?;
+        let (status, headers) = (response.status, response.headers.clone());
+        Ok((status, headers, response.text().await?))
+    }
+
+    // Send a request with its body still streaming, unless a cassette has to
+    // record or replay it whole
+    async fn exchange(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<serde_json::Value>,
+        extra_headers: HeaderMap,
+    ) -> Result<HttpResponse, OpenAIError> {
+        let mut headers = HeaderMap::new();
+        let bearer = HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+            .map_err(|_| TransportError::other("API key is not a valid header value"))?;
+        headers.insert(AUTHORIZATION, bearer);
+        if body.is_some() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        for middleware in &self.middleware {
+            middleware.add_headers(&mut headers).await?;
+        }
+        headers.extend(extra_headers);
+        let http_request = HttpRequest {
+            method: method.clone(),
+            url: url.to_string(),
+            headers,
+            body: body.as_ref().map(|body| body.to_string().into()),
+        };
+        let recorded = RecordedRequest::new(
+            method.as_str(),
+            url,
+            &http_request.headers,
+            body.unwrap_or_default(),
+        );
+
+        if let Some(cassette) = self.cassette.as_ref() {
+            if cassette.mode() == CassetteMode::Replay {
+                let response = cassette.find(&recorded)?;
+                let headers = response.header_map();
+                telemetry::record_http(response.status(), &headers);
+                return Ok(HttpResponse::new(response.status(), headers, response.body));
+            }
+        }
+
+        let started = Instant::now();
+        let response = self.transport.send(http_request).await?;
+        // The transport answers once the status and headers are in
+        let first_byte = started.elapsed();
+        let status = response.status;
+        let headers = response.headers.clone();
+        telemetry::record_http(status, &headers);
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.update(&self.api_key, recorded.body["model"].as_str(), &headers);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(model)) = (&self.metrics, recorded.body["model"].as_str()) {
+            if status.is_success() {
+                metrics.record_first_byte(model, first_byte);
+            }
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = first_byte;
+
+        if let Some(cassette) = self.cassette.as_ref() {
+            let text = response.text().await?;
+            cassette.save(Interaction {
+                request: recorded,
+                response: RecordedResponse::new(status, &headers, text.clone()),
+            })?;
+            return Ok(HttpResponse::new(status, headers, text));
+        }
+        Ok(response)
+    }
+
+    /// Answer pending MCP approval requests through `approve` until the model
+    /// returns a response that needs no further approval, sending at most
+    /// `max_turns` follow-up requests
+    pub async fn resolve_mcp_approvals<F>(
+        &self,
+        request: &ResponseRequest,
+        mut response: ResponseApiResponse,
+        mut approve: F,
+        max_turns: u32,
+    ) -> Result<ResponseApiResponse, OpenAIError>
+    where
+        F: FnMut(&McpApprovalRequest) -> bool,
+    {
+        let mut turns = 0;
+        loop {
+            let answers: Vec<InputItem> = response
+                .mcp_approval_requests()
+                .map(|pending| InputItem::mcp_approval_response(pending, approve(pending)))
+                .collect();
+            if answers.is_empty() {
+                return Ok(response);
+            }
+            if turns == max_turns {
+                return Err(OpenAIError::TooManyTurns(max_turns));
+            }
+            turns += 1;
+
+            // Tools are not inherited through previous_response_id, so resend them
+            let mut follow_up = request.clone();
+            follow_up.input = Some(InputType::Items(answers));
+            follow_up.previous_response_id = Some(response.id.clone());
+            response = self.send_request(follow_up).await?;
+        }
+    }
+
+    /// Ask the model to continue a response cut off by `max_output_tokens`,
+    /// up to `max_continuations` times, merging the parts into one response.
+    /// Requires stored responses, since each step chains on the previous one.
+    pub async fn continue_truncated(
+        &self,
+        request: &ResponseRequest,
+        mut response: ResponseApiResponse,
+        max_continuations: u32,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        for _ in 0..max_continuations {
+            if !response.is_truncated() {
+                break;
+            }
+
+            let mut follow_up = request.clone();
+            follow_up.input = Some(InputType::Text(CONTINUATION_PROMPT.to_string()));
+            follow_up.previous_response_id = Some(response.id.clone());
+            let continuation = self.send_request(follow_up).await?;
+            response.extend_with(continuation);
+        }
+        Ok(response)
+    }
+
     /// Create a request builder for advanced usage
     pub fn request_builder(&self) -> ResponseRequestBuilder {
         ResponseRequestBuilder::new()
@@ -92,7 +630,8 @@ impl OpenAIClient {
         self.send_request(request).await
     }
 
-    /// Convenience method for streaming responses
+    /// Convenience method for streaming responses, read to the final
+    /// response; use `stream_request` for the events as they arrive
     pub async fn get_streaming_response(
         &self,
         model: &str,
@@ -128,3 +667,127 @@ impl OpenAIClient {
         self.send_request(request).await
     }
 }
+
+async fn before_request(
+    middleware: &[Arc<dyn Middleware>],
+    request: &mut ResponseRequest,
+    headers: &mut HeaderMap,
+) -> Result<Flow, OpenAIError> {
+    for middleware in middleware {
+        if let Flow::Respond(response) = middleware.before_request(request, headers).await? {
+            return Ok(Flow::Respond(response));
+        }
+    }
+    Ok(Flow::Continue)
+}
+
+async fn after_response(
+    middleware: &[Arc<dyn Middleware>],
+    request: &ResponseRequest,
+    response: &mut ResponseApiResponse,
+) -> Result<(), OpenAIError> {
+    for middleware in middleware {
+        middleware.after_response(request, response).await?;
+    }
+    Ok(())
+}
+
+async fn on_error(
+    middleware: &[Arc<dyn Middleware>],
+    request: &ResponseRequest,
+    mut error: OpenAIError,
+) -> Result<ResponseApiResponse, OpenAIError> {
+    for middleware in middleware {
+        error = match middleware.on_error(request, error).await {
+            Ok(response) => return Ok(response),
+            Err(error) => error,
+        };
+    }
+    Err(error)
+}
+
+// Run the response hooks on the final event of a stream, and the error hooks
+// on a failure, which a recovered response replaces
+async fn finish_event(
+    middleware: &[Arc<dyn Middleware>],
+    request: &ResponseRequest,
+    event: Result<StreamEvent, OpenAIError>,
+) -> Result<StreamEvent, OpenAIError> {
+    let result = match event {
+        Ok(event) => match (event.error(), event.response()) {
+            (Some(error), _) => Err(error),
+            (None, Some(mut response)) if event.is_final() => {
+                after_response(middleware, request, &mut response)
+                    .await
+                    .map(|()| StreamEvent::finished(&response))
+            }
+            (None, _) => return Ok(event),
+        },
+        Err(error) => Err(error),
+    };
+    match result {
+        Ok(event) => Ok(event),
+        Err(error) => on_error(middleware, request, error)
+            .await
+            .map(|response| StreamEvent::finished(&response)),
+    }
+}
+
+// Records a streamed request in its span and metrics once the final event or
+// an error arrives
+struct StreamOutcome {
+    span: Span,
+    started: Instant,
+    telemetry: TelemetryOptions,
+    #[cfg(feature = "metrics")]
+    metrics: Option<(Arc<MetricsRegistry>, String)>,
+}
+
+impl StreamOutcome {
+    fn record(self, result: Result<&ResponseApiResponse, &OpenAIError>) {
+        let latency = self.started.elapsed();
+        match result {
+            Ok(response) => {
+                telemetry::record_response(&self.span, response, latency, self.telemetry)
+            }
+            Err(error) => telemetry::record_error(&self.span, error, latency),
+        }
+        #[cfg(feature = "metrics")]
+        if let Some((metrics, model)) = &self.metrics {
+            match result {
+                Ok(response) => metrics.record_response(model, response, latency),
+                Err(error) => metrics.record_error(model, error, latency),
+            }
+        }
+    }
+}
+
+// Poll `events` inside `span`, so what they log belongs to the request
+fn in_span(mut events: ResponseStream, span: Span) -> ResponseStream {
+    stream::poll_fn(move |cx| {
+        let _entered = span.enter();
+        events.poll_next_unpin(cx)
+    })
+    .boxed()
+}
+
+// A stream of just the final event for `response`
+fn finished(response: &ResponseApiResponse) -> ResponseStream {
+    stream::iter([Ok(StreamEvent::finished(response))]).boxed()
+}
+
+// How long to wait before retry `attempt`, or `None` to give up when the
+// server asks for a longer wait than `RETRY_MAX_DELAY`
+fn retry_delay(error: &OpenAIError, attempt: u32) -> Option<Duration> {
+    match error {
+        OpenAIError::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } => Some(*retry_after).filter(|delay| *delay <= RETRY_MAX_DELAY),
+        _ => Some(
+            RETRY_BASE_DELAY
+                .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+                .min(RETRY_MAX_DELAY),
+        ),
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+// String-valued API enums. Values the crate does not know parse into `Other`
+// instead of failing, so new server values never break a request or response.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)+
+                    other => $name::Other(other.to_string()),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(s.into())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok(value.as_str().into())
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// Processing tier for the request
+    pub enum ServiceTier {
+        Auto => "auto",
+        Default => "default",
+        Flex => "flex",
+        Scale => "scale",
+        Priority => "priority",
+    }
+}
+
+string_enum! {
+    /// What to do when the input exceeds the model's context window
+    #[derive(Default)]
+    pub enum Truncation {
+        Auto => "auto",
+        #[default]
+        Disabled => "disabled",
+    }
+}
+
+string_enum! {
+    /// Extra output data requested through `include`
+    pub enum IncludeField {
+        CodeInterpreterOutputs => "code_interpreter_call.outputs",
+        ComputerCallImageUrl => "computer_call_output.output.image_url",
+        FileSearchResults => "file_search_call.results",
+        InputImageUrl => "message.input_image.image_url",
+        OutputTextLogprobs => "message.output_text.logprobs",
+        ReasoningEncryptedContent => "reasoning.encrypted_content",
+        WebSearchSources => "web_search_call.action.sources",
+    }
+}
+
+string_enum! {
+    /// Lifecycle state of a response
+    pub enum ResponseStatus {
+        Completed => "completed",
+        Failed => "failed",
+        InProgress => "in_progress",
+        Cancelled => "cancelled",
+        Queued => "queued",
+        Incomplete => "incomplete",
+    }
+}
+
+string_enum! {
+    /// Lifecycle state of an output item such as a message
+    pub enum ItemStatus {
+        InProgress => "in_progress",
+        Completed => "completed",
+        Incomplete => "incomplete",
+    }
+}
+
+string_enum! {
+    /// Author of a message
+    pub enum Role {
+        User => "user",
+        Assistant => "assistant",
+        System => "system",
+        Developer => "developer",
+    }
+}
+
+string_enum! {
+    /// Resolution at which the model looks at an image input
+    #[derive(Default)]
+    pub enum ImageDetail {
+        #[default]
+        Auto => "auto",
+        Low => "low",
+        High => "high",
+    }
+}
+
+string_enum! {
+    /// Shape of the text output: plain text or structured JSON
+    pub enum TextFormatType {
+        Text => "text",
+        JsonSchema => "json_schema",
+        JsonObject => "json_object",
+    }
+}
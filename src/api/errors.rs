@@ -1,27 +1,239 @@
+use reqwest::header::HeaderMap;
 use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum OpenAIError {
-    #[error("API Error: {message} (Type: {error_type}, Code: {code})")]
-    ApiError {
-        message: String,
-        error_type: String,
-        code: String,
-    },
-    #[error("HTTP Error {status}: {message}")]
-    HttpError {
-        status: reqwest::StatusCode,
-        message: String,
+    #[error("Authentication failed: {0}")]
+    Authentication(ApiErrorContext),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(ApiErrorContext),
+    #[error("Not found: {0}")]
+    NotFound(ApiErrorContext),
+    #[error("Rate limited: {context}")]
+    RateLimited {
+        context: ApiErrorContext,
+        retry_after: Option<Duration>,
     },
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(ApiErrorContext),
+    #[error("Context length exceeded: {0}")]
+    ContextLengthExceeded(ApiErrorContext),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(ApiErrorContext),
+    #[error("Blocked by content filter: {0}")]
+    ContentFilter(ApiErrorContext),
+    #[error("Server error: {0}")]
+    ServerError(ApiErrorContext),
+    #[error("API Error: {0}")]
+    ApiError(ApiErrorContext),
     #[error("JSON Parse Error: {0}")]
     JsonParseError(String),
-    #[error("Network Error: {0}")]
-    NetworkError(#[from] reqwest::Error),
+    #[error(transparent)]
+    Transport(#[from] TransportError),
     #[error("No output messages returned from OpenAI")]
     NoOutputMessages,
     #[error("Empty response content")]
     EmptyResponse,
+    #[error("No final response after {0} follow-up requests")]
+    TooManyTurns(u32),
+    #[error("Invalid tool choice: {0}")]
+    InvalidToolChoice(#[from] ToolChoiceError),
+    #[error("Invalid request parameters: {0}")]
+    Validation(#[from] ValidationError),
+    #[error("Cassette error: {0}")]
+    Cassette(#[from] CassetteError),
+}
+
+/// How a request failed below HTTP, whatever transport sent it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// No answer in time
+    Timeout,
+    /// The connection could not be made, or broke before an answer
+    Connection,
+    /// Anything else, e.g. a header that cannot be sent
+    Other,
+}
+
+/// A failure to get an HTTP answer at all. Custom transports pick the kind,
+/// which decides retries and the CLI exit code.
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    pub fn timeout(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Timeout, source)
+    }
+
+    pub fn connection(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Connection, source)
+    }
+
+    pub fn other(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Other, source)
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            TransportErrorKind::Timeout => write!(f, "Request timed out: {}", self.source),
+            TransportErrorKind::Connection => write!(f, "Network Error: {}", self.source),
+            TransportErrorKind::Other => write!(f, "Transport Error: {}", self.source),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(error: reqwest::Error) -> Self {
+        let kind = if error.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if error.is_connect() || error.is_request() {
+            TransportErrorKind::Connection
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, error)
+    }
+}
+
+// Everything the API told us about a failed request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiErrorContext {
+    pub status: reqwest::StatusCode,
+    pub message: String,
+    pub error_type: Option<String>,
+    pub code: Option<String>,
+    pub param: Option<String>,
+    pub request_id: Option<String>, // `x-request-id`, quote it when contacting support
+}
+
+impl fmt::Display for ApiErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(param) = &self.param {
+            write!(f, " (param: {})", param)?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, " (code: {})", code)?;
+        }
+        write!(f, " [HTTP {}", self.status.as_u16())?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request {}", request_id)?;
+        }
+        write!(f, "]")
+    }
+}
+
+// Problems found in a request before it is sent
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    #[error("{field} must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        field: &'static str,
+        min: String,
+        max: String,
+        value: String,
+    },
+    #[error("metadata must be a JSON object of string values")]
+    MetadataNotObject,
+    #[error("metadata can have at most {max} keys, got {count}")]
+    TooManyMetadataKeys { max: usize, count: usize },
+    #[error("metadata key '{key}' is longer than {max} characters")]
+    MetadataKeyTooLong { key: String, max: usize },
+    #[error("metadata value for '{key}' must be a string")]
+    MetadataValueNotString { key: String },
+    #[error("metadata value for '{key}' is longer than {max} characters")]
+    MetadataValueTooLong { key: String, max: usize },
+    #[error("{0}")]
+    Conflict(&'static str),
+    #[error(
+        "about {tokens} tokens of input and output exceed the {window}-token context window of {model}"
+    )]
+    ContextWindowExceeded {
+        model: String,
+        tokens: usize,
+        window: usize,
+    },
+    #[error("worst-case cost {estimate} exceeds the spend limit of {limit}")]
+    CostLimitExceeded { estimate: String, limit: String },
+    #[error("worst-case cost on {0} has no bound without max_output_tokens, as its context window is unknown")]
+    UnboundedCost(String),
+    #[error("no price is known for {0}; add it under [pricing.models] in the config file")]
+    UnknownPrice(String),
+    #[error("{period} budget of {limit} is used up ({spent} spent)")]
+    BudgetExceeded {
+        period: &'static str,
+        spent: String,
+        limit: String,
+    },
+    #[error("model '{0}' is not allowed by policy")]
+    ModelNotAllowed(String),
+    #[error("invalid HTTP header '{0}'")]
+    InvalidHeader(String),
+    #[error("OPENAI_API_KEY is not set")]
+    MissingApiKey,
+    #[error(transparent)]
+    ToolChoice(#[from] ToolChoiceError),
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ToolChoiceError {
+    #[error("tool_choice 'required' needs at least one tool")]
+    NoTools,
+    #[error("allowed_tools must list at least one tool")]
+    EmptyAllowedTools,
+    #[error("'{0}' is not in the request's tools")]
+    UnknownTool(String),
+}
+
+// Problems recording or replaying HTTP cassettes
+#[derive(Error, Debug)]
+pub enum CassetteError {
+    #[error("{path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("{path}: {source}")]
+    Parse {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("no recorded interaction in {path} matches {method} {url}")]
+    NoMatch {
+        path: String,
+        method: String,
+        url: String,
+    },
+    #[error("{0}")]
+    InvalidSetting(String),
 }
 
 // Error handling structures
@@ -43,24 +255,114 @@ pub struct ApiError {
 
 impl OpenAIError {
     pub fn parse_api_error(response_text: &str, status: reqwest::StatusCode) -> Self {
-        // Try to parse as API error first
-        if let Ok(api_error) = serde_json::from_str::<ApiError>(response_text) {
-            OpenAIError::ApiError {
+        Self::from_response(status, &HeaderMap::new(), response_text)
+    }
+
+    /// Classify a failed HTTP response by status and error code
+    pub fn from_response(status: reqwest::StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        let context = match serde_json::from_str::<ApiError>(body) {
+            Ok(api_error) => ApiErrorContext {
+                status,
                 message: api_error.error.message,
-                error_type: api_error
-                    .error
-                    .error_type
-                    .unwrap_or_else(|| "unknown".to_string()),
-                code: api_error
-                    .error
-                    .code
-                    .unwrap_or_else(|| "unknown".to_string()),
-            }
-        } else {
-            OpenAIError::HttpError {
+                error_type: api_error.error.error_type,
+                code: api_error.error.code,
+                param: api_error.error.param,
+                request_id: header_str(headers, "x-request-id"),
+            },
+            // Proxies and gateways answer with plain text or HTML
+            Err(_) => ApiErrorContext {
                 status,
-                message: response_text.to_string(),
+                message: match body.trim() {
+                    "" => status
+                        .canonical_reason()
+                        .unwrap_or("Unknown error")
+                        .to_string(),
+                    text => text.to_string(),
+                },
+                error_type: None,
+                code: None,
+                param: None,
+                request_id: header_str(headers, "x-request-id"),
+            },
+        };
+
+        let code = context.code.as_deref().or(context.error_type.as_deref());
+        match (status.as_u16(), code) {
+            (_, Some("insufficient_quota")) => OpenAIError::QuotaExceeded(context),
+            (_, Some("context_length_exceeded")) => OpenAIError::ContextLengthExceeded(context),
+            (_, Some("content_filter" | "content_policy_violation")) => {
+                OpenAIError::ContentFilter(context)
             }
+            (401, _) => OpenAIError::Authentication(context),
+            (403, _) => OpenAIError::PermissionDenied(context),
+            (404, _) => OpenAIError::NotFound(context),
+            (429, _) => OpenAIError::RateLimited {
+                context,
+                retry_after: retry_after(headers),
+            },
+            (400 | 413 | 422, _) => OpenAIError::InvalidRequest(context),
+            (500..=599, _) => OpenAIError::ServerError(context),
+            _ => OpenAIError::ApiError(context),
+        }
+    }
+
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OpenAIError::RateLimited { .. } | OpenAIError::ServerError(_) => true,
+            OpenAIError::Transport(e) => e.kind() != TransportErrorKind::Other,
+            _ => false,
         }
     }
+
+    /// API details of the failure, if the server answered
+    pub fn context(&self) -> Option<&ApiErrorContext> {
+        match self {
+            OpenAIError::Authentication(context)
+            | OpenAIError::PermissionDenied(context)
+            | OpenAIError::NotFound(context)
+            | OpenAIError::RateLimited { context, .. }
+            | OpenAIError::QuotaExceeded(context)
+            | OpenAIError::ContextLengthExceeded(context)
+            | OpenAIError::InvalidRequest(context)
+            | OpenAIError::ContentFilter(context)
+            | OpenAIError::ServerError(context)
+            | OpenAIError::ApiError(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.context()?.request_id.as_deref()
+    }
+}
+
+impl From<reqwest::Error> for OpenAIError {
+    fn from(error: reqwest::Error) -> Self {
+        OpenAIError::Transport(error.into())
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(str::to_string)
+}
+
+// Longest `retry-after` taken from a server; anything above is clamped to it
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+// `retry-after-ms` is more precise; `retry-after` is in seconds
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds = |name| {
+        header_str(headers, name)
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    };
+    seconds("retry-after-ms")
+        .map(|ms| ms / 1000.0)
+        .or_else(|| seconds("retry-after"))
+        .map(|seconds| {
+            Duration::try_from_secs_f64(seconds)
+                .unwrap_or(MAX_RETRY_AFTER)
+                .min(MAX_RETRY_AFTER)
+        })
 }
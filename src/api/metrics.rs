@@ -0,0 +1,311 @@
+use crate::api::errors::{OpenAIError, TransportErrorKind};
+use crate::api::models::ResponseApiResponse;
+use crate::api::pricing::PricingTable;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds in seconds of the latency histogram buckets
+pub const LATENCY_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+// Name, help text and type of every metric, in exposition order
+const METRICS: &[(&str, &str, &str)] = &[
+    (
+        "openai_requests_total",
+        "Requests by model and response status (error when none came back)",
+        "counter",
+    ),
+    (
+        "openai_request_errors_total",
+        "Failed requests by model and error class",
+        "counter",
+    ),
+    (
+        "openai_request_duration_seconds",
+        "Time from sending a request to its final response",
+        "histogram",
+    ),
+    (
+        "openai_time_to_first_byte_seconds",
+        "Time from sending a request to the status and headers of its reply",
+        "histogram",
+    ),
+    (
+        "openai_time_to_first_token_seconds",
+        "Time from sending a streamed request to its first text delta",
+        "histogram",
+    ),
+    (
+        "openai_tokens_total",
+        "Tokens used by model and type (input, cached, output, reasoning)",
+        "counter",
+    ),
+    (
+        "openai_cost_usd_total",
+        "Estimated cost in USD by model",
+        "counter",
+    ),
+    (
+        "openai_retries_total",
+        "Requests sent again after a retryable error",
+        "counter",
+    ),
+];
+
+// Label pairs, sorted by name so equal sets share a series
+type Labels = Vec<(String, String)>;
+
+/// Counts and sums of one histogram series
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Histogram {
+    /// Observations at or below each of `LATENCY_BUCKETS`, cumulative
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        for (count, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Series {
+    counters: BTreeMap<(&'static str, Labels), f64>,
+    histograms: BTreeMap<(&'static str, Labels), Histogram>,
+}
+
+/// In-memory counters and histograms for the requests of the clients it is
+/// attached to, rendered in the Prometheus text format for a `/metrics`
+/// endpoint. Share one registry between clients through an `Arc`.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    series: Mutex<Series>,
+    pricing: PricingTable,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Price tokens with `pricing` instead of the built-in list prices
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Current value of a counter, 0 if never incremented
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> f64 {
+        let series = self.series.lock().unwrap();
+        let labels = labels_of(labels);
+        series
+            .counters
+            .iter()
+            .find(|((metric, series_labels), _)| *metric == name && *series_labels == labels)
+            .map_or(0.0, |(_, value)| *value)
+    }
+
+    /// Current state of a histogram series, if anything was observed
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> Option<Histogram> {
+        let series = self.series.lock().unwrap();
+        let labels = labels_of(labels);
+        series
+            .histograms
+            .iter()
+            .find(|((metric, series_labels), _)| *metric == name && *series_labels == labels)
+            .map(|(_, histogram)| histogram.clone())
+    }
+
+    /// Every series in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap();
+        let mut out = String::new();
+        for (name, help, kind) in METRICS {
+            let counters: Vec<_> = series
+                .counters
+                .iter()
+                .filter(|((metric, _), _)| metric == name)
+                .collect();
+            let histograms: Vec<_> = series
+                .histograms
+                .iter()
+                .filter(|((metric, _), _)| metric == name)
+                .collect();
+            if counters.is_empty() && histograms.is_empty() {
+                continue;
+            }
+
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for ((_, labels), value) in counters {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+            }
+            for ((_, labels), histogram) in histograms {
+                for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                    let le = Some(bound.to_string());
+                    let labels = format_labels(labels, le);
+                    let _ = writeln!(out, "{}_bucket{} {}", name, labels, count);
+                }
+                let labels_inf = format_labels(labels, Some("+Inf".to_string()));
+                let _ = writeln!(out, "{}_bucket{} {}", name, labels_inf, histogram.count);
+                let labels = format_labels(labels, None);
+                let _ = writeln!(out, "{}_sum{} {}", name, labels, histogram.sum);
+                let _ = writeln!(out, "{}_count{} {}", name, labels, histogram.count);
+            }
+        }
+        out
+    }
+
+    pub(crate) fn record_response(
+        &self,
+        model: &str,
+        response: &ResponseApiResponse,
+        latency: Duration,
+    ) {
+        let status = response
+            .status
+            .as_ref()
+            .map_or("unknown", |status| status.as_str());
+        self.increment(
+            "openai_requests_total",
+            &[("model", model), ("status", status)],
+            1.0,
+        );
+        self.observe("openai_request_duration_seconds", model, latency);
+
+        // Cached responses cost nothing
+        if response.cached {
+            return;
+        }
+        let usage = &response.usage;
+        let cached = usage
+            .input_tokens_details
+            .as_ref()
+            .and_then(|details| details.cached_tokens)
+            .unwrap_or(0);
+        let reasoning = usage
+            .output_tokens_details
+            .as_ref()
+            .and_then(|details| details.reasoning_tokens)
+            .unwrap_or(0);
+        for (kind, tokens) in [
+            ("input", usage.input_tokens),
+            ("cached", cached),
+            ("output", usage.output_tokens),
+            ("reasoning", reasoning),
+        ] {
+            let labels = [("model", model), ("type", kind)];
+            self.increment("openai_tokens_total", &labels, tokens as f64);
+        }
+        if let Some(cost) = self.pricing.cost(response) {
+            self.increment("openai_cost_usd_total", &[("model", model)], cost.total);
+        }
+    }
+
+    pub(crate) fn record_error(&self, model: &str, error: &OpenAIError, latency: Duration) {
+        self.increment(
+            "openai_requests_total",
+            &[("model", model), ("status", "error")],
+            1.0,
+        );
+        self.increment(
+            "openai_request_errors_total",
+            &[("model", model), ("error_class", error_class(error))],
+            1.0,
+        );
+        self.observe("openai_request_duration_seconds", model, latency);
+    }
+
+    pub(crate) fn record_first_byte(&self, model: &str, latency: Duration) {
+        self.observe("openai_time_to_first_byte_seconds", model, latency);
+    }
+
+    pub(crate) fn record_first_token(&self, model: &str, latency: Duration) {
+        self.observe("openai_time_to_first_token_seconds", model, latency);
+    }
+
+    pub(crate) fn record_retry(&self, model: &str) {
+        self.increment("openai_retries_total", &[("model", model)], 1.0);
+    }
+
+    fn increment(&self, name: &'static str, labels: &[(&str, &str)], by: f64) {
+        let mut series = self.series.lock().unwrap();
+        *series
+            .counters
+            .entry((name, labels_of(labels)))
+            .or_default() += by;
+    }
+
+    fn observe(&self, name: &'static str, model: &str, latency: Duration) {
+        let mut series = self.series.lock().unwrap();
+        series
+            .histograms
+            .entry((name, labels_of(&[("model", model)])))
+            .or_default()
+            .observe(latency.as_secs_f64());
+    }
+}
+
+/// Short, stable name of the kind of failure, used as a metric label
+pub fn error_class(error: &OpenAIError) -> &'static str {
+    match error {
+        OpenAIError::Authentication(_) => "authentication",
+        OpenAIError::PermissionDenied(_) => "permission_denied",
+        OpenAIError::NotFound(_) => "not_found",
+        OpenAIError::RateLimited { .. } => "rate_limited",
+        OpenAIError::QuotaExceeded(_) => "quota_exceeded",
+        OpenAIError::ContextLengthExceeded(_) => "context_length_exceeded",
+        OpenAIError::InvalidRequest(_)
+        | OpenAIError::InvalidToolChoice(_)
+        | OpenAIError::Validation(_) => "invalid_request",
+        OpenAIError::ContentFilter(_) => "content_filter",
+        OpenAIError::ServerError(_) => "server_error",
+        OpenAIError::Transport(e) if e.kind() == TransportErrorKind::Timeout => "timeout",
+        OpenAIError::Transport(_) => "network",
+        OpenAIError::JsonParseError(_) => "parse",
+        _ => "other",
+    }
+}
+
+fn labels_of(labels: &[(&str, &str)]) -> Labels {
+    let mut labels: Labels = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn format_labels(labels: &Labels, le: Option<String>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
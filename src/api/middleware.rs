@@ -0,0 +1,225 @@
+use crate::api::errors::{OpenAIError, ValidationError};
+use crate::api::models::{ResponseApiResponse, ResponseRequest};
+use crate::api::pricing::{format_usd, PricingTable};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::sync::{Arc, Mutex};
+
+/// What `before_request` decided
+#[derive(Debug)]
+pub enum Flow {
+    /// Go on to the next middleware, then the API
+    Continue,
+    /// Skip the API and answer with this response
+    Respond(Box<ResponseApiResponse>),
+}
+
+/// Hooks around every `send_request`, for policies such as extra headers,
+/// redaction, logging or model restrictions. Middleware runs in the order it
+/// was added; every hook has a pass-through default.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Rewrite the request or add HTTP headers before it is sent. Returning
+    /// an error or `Flow::Respond` stops the chain; the error goes to the
+    /// `on_error` hooks and the response to the `after_response` hooks.
+    async fn before_request(
+        &self,
+        _request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        Ok(Flow::Continue)
+    }
+
+    /// Inspect or rewrite a successful response; an error stops the chain
+    /// and goes to the `on_error` hooks
+    async fn after_response(
+        &self,
+        _request: &ResponseRequest,
+        _response: &mut ResponseApiResponse,
+    ) -> Result<(), OpenAIError> {
+        Ok(())
+    }
+
+    /// See a failed call; return the error, possibly replaced, to pass it on,
+    /// or a response to recover and stop the chain
+    async fn on_error(
+        &self,
+        _request: &ResponseRequest,
+        error: OpenAIError,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        Err(error)
+    }
+
+    /// Add HTTP headers to every call, including `retrieve_response`,
+    /// `cancel_response` and `delete_response`, which have no request body
+    /// for `before_request`; an error fails the call
+    async fn add_headers(&self, _headers: &mut HeaderMap) -> Result<(), OpenAIError> {
+        Ok(())
+    }
+}
+
+// Shared middleware, e.g. to read a `SpendLimit` after the client used it
+#[async_trait]
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    async fn before_request(
+        &self,
+        request: &mut ResponseRequest,
+        headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        (**self).before_request(request, headers).await
+    }
+
+    async fn after_response(
+        &self,
+        request: &ResponseRequest,
+        response: &mut ResponseApiResponse,
+    ) -> Result<(), OpenAIError> {
+        (**self).after_response(request, response).await
+    }
+
+    async fn on_error(
+        &self,
+        request: &ResponseRequest,
+        error: OpenAIError,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        (**self).on_error(request, error).await
+    }
+
+    async fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), OpenAIError> {
+        (**self).add_headers(headers).await
+    }
+}
+
+/// Adds fixed headers to every HTTP call
+#[derive(Debug, Clone, Default)]
+pub struct ExtraHeaders {
+    headers: HeaderMap,
+}
+
+impl ExtraHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header; names and values that are not valid HTTP are rejected
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self, ValidationError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ValidationError::InvalidHeader(name.to_string()))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| ValidationError::InvalidHeader(name.to_string()))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+}
+
+#[async_trait]
+impl Middleware for ExtraHeaders {
+    async fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), OpenAIError> {
+        headers.extend(self.headers.clone());
+        Ok(())
+    }
+}
+
+/// Rejects requests for models that are not listed. Entries match exactly,
+/// or by prefix when they end in `*`, e.g. `gpt-4.1*`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelAllowlist {
+    models: Vec<String>,
+}
+
+impl ModelAllowlist {
+    pub fn new(models: Vec<String>) -> Self {
+        Self { models }
+    }
+
+    pub fn allows(&self, model: &str) -> bool {
+        self.models
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => model.starts_with(prefix),
+                None => model == allowed,
+            })
+    }
+}
+
+#[async_trait]
+impl Middleware for ModelAllowlist {
+    async fn before_request(
+        &self,
+        request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        let model = request.model.as_deref().unwrap_or_default();
+        if self.allows(model) {
+            Ok(Flow::Continue)
+        } else {
+            Err(ValidationError::ModelNotAllowed(model.to_string()).into())
+        }
+    }
+}
+
+/// Caps what all requests through a client may cost together: each request
+/// is refused when its worst-case cost, added to what earlier responses cost,
+/// would exceed `limit` USD. Follow-up requests such as continuations, MCP
+/// approvals and tool results count against the same limit.
+#[derive(Debug)]
+pub struct SpendLimit {
+    pricing: PricingTable,
+    limit: f64,
+    spent: Mutex<f64>,
+}
+
+impl SpendLimit {
+    pub fn new(pricing: PricingTable, limit: f64) -> Self {
+        Self {
+            pricing,
+            limit,
+            spent: Mutex::new(0.0),
+        }
+    }
+
+    /// USD spent on responses so far; cached responses cost nothing
+    pub fn spent(&self) -> f64 {
+        *self.spent.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Middleware for SpendLimit {
+    async fn before_request(
+        &self,
+        request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        let model = request.model.clone().unwrap_or_default();
+        if self.pricing.price(&model).is_none() {
+            return Err(ValidationError::UnknownPrice(model).into());
+        }
+        let estimate = self
+            .pricing
+            .worst_case_cost(request)
+            .ok_or_else(|| ValidationError::UnboundedCost(model.clone()))?
+            + self.spent();
+
+        if estimate > self.limit {
+            return Err(ValidationError::CostLimitExceeded {
+                estimate: format_usd(estimate),
+                limit: format_usd(self.limit),
+            }
+            .into());
+        }
+        Ok(Flow::Continue)
+    }
+
+    async fn after_response(
+        &self,
+        _request: &ResponseRequest,
+        response: &mut ResponseApiResponse,
+    ) -> Result<(), OpenAIError> {
+        if !response.cached {
+            let cost = self.pricing.cost(response).map_or(0.0, |cost| cost.total);
+            *self.spent.lock().unwrap() += cost;
+        }
+        Ok(())
+    }
+}
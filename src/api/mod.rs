@@ -1,3 +1,15 @@
+pub mod cache;
+pub mod cassette;
 pub mod client;
+pub mod enums;
 pub mod errors;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
 pub mod models;
+pub mod pricing;
+pub mod rate_limit;
+pub mod streaming;
+pub mod telemetry;
+pub mod tools;
+pub mod transport;
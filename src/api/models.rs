@@ -1,4 +1,15 @@
+pub use crate::api::enums::{
+    ImageDetail, IncludeField, ItemStatus, ResponseStatus, Role, ServiceTier, TextFormatType,
+    Truncation,
+};
+use crate::api::errors::{ToolChoiceError, ValidationError};
+use crate::api::pricing::Cost;
+use crate::api::tools::ToolDefinition;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
 
 // Content types for response output
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -9,8 +20,99 @@ pub enum ContentType {
     #[serde(rename = "output_text")]
     OutputText {
         text: String,
-        annotations: Vec<serde_json::Value>, // Flexible for various annotation types
+        #[serde(default)]
+        annotations: Vec<Annotation>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        logprobs: Vec<TokenLogprob>,
     },
+    #[serde(rename = "refusal")]
+    Refusal { refusal: String },
+    // Content types this crate does not model yet, kept as returned
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+// Log probability of one output token, returned when
+// `message.output_text.logprobs` is included
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+// One of the most likely alternatives at a token position
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl TokenLogprob {
+    /// Probability of this token, between 0 and 1
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+impl TopLogprob {
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+// Annotations attached to output text; indices are character offsets into the text
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Annotation {
+    #[serde(rename = "url_citation")]
+    UrlCitation {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        start_index: usize,
+        end_index: usize,
+    },
+    #[serde(rename = "file_citation")]
+    FileCitation {
+        file_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+        index: usize,
+    },
+    #[serde(rename = "container_file_citation")]
+    ContainerFileCitation {
+        container_id: String,
+        file_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+        start_index: usize,
+        end_index: usize,
+    },
+    #[serde(rename = "file_path")]
+    FilePath { file_id: String, index: usize },
+    // Annotation types this crate does not model yet, kept as returned
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+impl Annotation {
+    /// Character offset where a citation marker belongs, if any
+    pub fn marker_index(&self) -> Option<usize> {
+        match self {
+            Annotation::UrlCitation { end_index, .. }
+            | Annotation::ContainerFileCitation { end_index, .. } => Some(*end_index),
+            Annotation::FileCitation { index, .. } | Annotation::FilePath { index, .. } => {
+                Some(*index)
+            }
+            Annotation::Other(_) => None,
+        }
+    }
 }
 
 // Input types for the Responses API
@@ -19,36 +121,422 @@ pub enum ContentType {
 pub enum InputType {
     Text(String),
     Array(Vec<serde_json::Value>), // For complex inputs like images, files, etc.
+    Items(Vec<InputItem>),
 }
 
-// Prompt template reference
-#[derive(Serialize, Debug, Clone)]
+// Typed input items sent back to the model
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum InputItem {
+    #[serde(rename = "mcp_approval_response")]
+    McpApprovalResponse {
+        approval_request_id: String,
+        approve: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    #[serde(rename = "function_call_output")]
+    FunctionCallOutput { call_id: String, output: String },
+    #[serde(rename = "reasoning")]
+    Reasoning(ReasoningItem),
+}
+
+impl InputItem {
+    /// Answer an MCP approval request
+    pub fn mcp_approval_response(request: &McpApprovalRequest, approve: bool) -> Self {
+        InputItem::McpApprovalResponse {
+            approval_request_id: request.id.clone(),
+            approve,
+            reason: None,
+        }
+    }
+
+    /// Return the result of a function call to the model
+    pub fn function_call_output(call: &FunctionCall, output: String) -> Self {
+        InputItem::FunctionCallOutput {
+            call_id: call.call_id.clone(),
+            output,
+        }
+    }
+}
+
+// Reference to a reusable prompt stored with the API, pinned to a version
+// when one is given (the latest version otherwise)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PromptTemplate {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub template_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub variables: Option<serde_json::Value>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "null_as_default",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub variables: BTreeMap<String, PromptVariable>,
+}
+
+impl PromptTemplate {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            version: None,
+            variables: BTreeMap::new(),
+        }
+    }
+
+    pub fn version(mut self, version: String) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn variable(mut self, name: String, value: PromptVariable) -> Self {
+        self.variables.insert(name, value);
+        self
+    }
+}
+
+// Value substituted for a prompt variable: plain text or a typed input
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PromptVariable {
+    Text(String),
+    Input(PromptInput),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum PromptInput {
+    #[serde(rename = "input_text")]
+    Text { text: String },
+    #[serde(rename = "input_image")]
+    Image {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        image_url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+        #[serde(default)]
+        detail: ImageDetail,
+    },
+    #[serde(rename = "input_file")]
+    File {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+    },
+    // Input types this crate does not model yet, kept as returned
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+// Uploaded files are referenced by ID; anything else is treated as a URL
+fn is_file_id(value: &str) -> bool {
+    value.starts_with("file-")
+}
+
+impl PromptVariable {
+    /// An image given by URL or uploaded file ID
+    pub fn image(source: &str) -> Self {
+        let (image_url, file_id) = if is_file_id(source) {
+            (None, Some(source.to_string()))
+        } else {
+            (Some(source.to_string()), None)
+        };
+        PromptVariable::Input(PromptInput::Image {
+            image_url,
+            file_id,
+            detail: ImageDetail::default(),
+        })
+    }
+
+    /// A file given by URL or uploaded file ID
+    pub fn file(source: &str) -> Self {
+        let (file_url, file_id) = if is_file_id(source) {
+            (None, Some(source.to_string()))
+        } else {
+            (Some(source.to_string()), None)
+        };
+        PromptVariable::Input(PromptInput::File { file_url, file_id })
+    }
+}
+
+// CLI form: `image:URL|FILE_ID`, `file:URL|FILE_ID`, or text (`text:` forces
+// text for values that start with one of the other prefixes)
+impl FromStr for PromptVariable {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if let Some(source) = s.strip_prefix("image:") {
+            PromptVariable::image(source)
+        } else if let Some(source) = s.strip_prefix("file:") {
+            PromptVariable::file(source)
+        } else {
+            PromptVariable::Text(s.strip_prefix("text:").unwrap_or(s).to_string())
+        })
+    }
 }
 
 // Reasoning configuration for o-series models
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 pub struct ReasoningConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub effort: Option<String>,
+    pub effort: Option<ReasoningEffort>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub encrypted_content: Option<bool>,
+    pub summary: Option<ReasoningSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningSummary {
+    Auto,
+    Concise,
+    Detailed,
 }
 
 // Tool choice configuration
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum ToolChoice {
-    String(String),            // "auto", "none", "required"
-    Object(serde_json::Value), // Specific tool selection
+    Mode(ToolChoiceMode),       // "none", "auto", "required"
+    AllowedTools(AllowedTools), // Restrict the model to a subset of tools
+    Tool(ToolReference),        // Force a specific function or hosted tool
+    Other(serde_json::Value),   // Shapes returned by the API that this crate does not model yet
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoiceMode {
+    None,
+    Auto,
+    Required,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedToolsMode {
+    Auto,
+    Required,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename = "allowed_tools")]
+pub struct AllowedTools {
+    pub mode: AllowedToolsMode,
+    pub tools: Vec<ToolReference>,
+}
+
+// Reference to a single tool from the request's `tools` list
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ToolReference {
+    #[serde(rename = "function")]
+    Function { name: String },
+    #[serde(rename = "mcp")]
+    Mcp {
+        server_label: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    #[serde(rename = "web_search_preview", alias = "web_search")]
+    WebSearch,
+    #[serde(rename = "file_search")]
+    FileSearch,
+    #[serde(rename = "code_interpreter")]
+    CodeInterpreter,
+    #[serde(rename = "image_generation")]
+    ImageGeneration,
+    #[serde(rename = "computer_use_preview")]
+    ComputerUse,
+}
+
+impl ToolChoice {
+    pub fn none() -> Self {
+        ToolChoice::Mode(ToolChoiceMode::None)
+    }
+
+    pub fn auto() -> Self {
+        ToolChoice::Mode(ToolChoiceMode::Auto)
+    }
+
+    pub fn required() -> Self {
+        ToolChoice::Mode(ToolChoiceMode::Required)
+    }
+
+    /// Force a call to the named function
+    pub fn function(name: String) -> Self {
+        ToolChoice::Tool(ToolReference::Function { name })
+    }
+
+    /// Restrict the model to the listed tools
+    pub fn allowed_tools(mode: AllowedToolsMode, tools: Vec<ToolReference>) -> Self {
+        ToolChoice::AllowedTools(AllowedTools { mode, tools })
+    }
+
+    /// Check that every tool this choice refers to is present in `tools`
+    pub fn validate(&self, tools: &[ToolDefinition]) -> Result<(), ToolChoiceError> {
+        match self {
+            ToolChoice::Mode(ToolChoiceMode::Required) if tools.is_empty() => {
+                Err(ToolChoiceError::NoTools)
+            }
+            ToolChoice::Mode(_) | ToolChoice::Other(_) => Ok(()),
+            ToolChoice::Tool(reference) => reference.ensure_in(tools),
+            ToolChoice::AllowedTools(allowed) => {
+                if allowed.tools.is_empty() {
+                    return Err(ToolChoiceError::EmptyAllowedTools);
+                }
+                allowed
+                    .tools
+                    .iter()
+                    .try_for_each(|reference| reference.ensure_in(tools))
+            }
+        }
+    }
+}
+
+impl ToolReference {
+    /// Whether `tool` is the tool this reference points at
+    pub fn matches(&self, tool: &ToolDefinition) -> bool {
+        match (self, tool) {
+            (ToolReference::Function { name }, ToolDefinition::Function(function)) => {
+                function.name == *name
+            }
+            (ToolReference::Mcp { server_label, .. }, ToolDefinition::Mcp(mcp)) => {
+                mcp.server_label == *server_label
+            }
+            (ToolReference::WebSearch, ToolDefinition::WebSearch(_))
+            | (ToolReference::FileSearch, ToolDefinition::FileSearch(_))
+            | (ToolReference::CodeInterpreter, ToolDefinition::CodeInterpreter(_))
+            | (ToolReference::ImageGeneration, ToolDefinition::ImageGeneration(_))
+            | (ToolReference::ComputerUse, ToolDefinition::ComputerUse(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn ensure_in(&self, tools: &[ToolDefinition]) -> Result<(), ToolChoiceError> {
+        if tools.iter().any(|tool| self.matches(tool)) {
+            Ok(())
+        } else {
+            Err(ToolChoiceError::UnknownTool(self.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ToolReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolReference::Function { name } => write!(f, "function={}", name),
+            ToolReference::Mcp { server_label, .. } => write!(f, "mcp={}", server_label),
+            ToolReference::WebSearch => write!(f, "web_search"),
+            ToolReference::FileSearch => write!(f, "file_search"),
+            ToolReference::CodeInterpreter => write!(f, "code_interpreter"),
+            ToolReference::ImageGeneration => write!(f, "image_generation"),
+            ToolReference::ComputerUse => write!(f, "computer_use"),
+        }
+    }
+}
+
+/// Parses the CLI form: `function=NAME`, `mcp=LABEL` or a hosted tool name
+impl FromStr for ToolReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some(("function", name)) if !name.is_empty() => Ok(ToolReference::Function {
+                name: name.to_string(),
+            }),
+            Some(("mcp", label)) if !label.is_empty() => Ok(ToolReference::Mcp {
+                server_label: label.to_string(),
+                name: None,
+            }),
+            Some(_) => Err(format!("Invalid tool reference '{}'", s)),
+            None => match s {
+                "web_search" | "web_search_preview" => Ok(ToolReference::WebSearch),
+                "file_search" => Ok(ToolReference::FileSearch),
+                "code_interpreter" => Ok(ToolReference::CodeInterpreter),
+                "image_generation" => Ok(ToolReference::ImageGeneration),
+                "computer_use" | "computer_use_preview" => Ok(ToolReference::ComputerUse),
+                _ => Err(format!(
+                    "Unknown tool '{}'; expected function=NAME, mcp=LABEL, web_search, \
+                     file_search, code_interpreter, image_generation or computer_use",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Formats in the CLI form accepted by `FromStr`; unmodelled shapes print as JSON
+impl fmt::Display for ToolChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolChoice::Mode(ToolChoiceMode::None) => write!(f, "none"),
+            ToolChoice::Mode(ToolChoiceMode::Auto) => write!(f, "auto"),
+            ToolChoice::Mode(ToolChoiceMode::Required) => write!(f, "required"),
+            ToolChoice::AllowedTools(allowed) => {
+                let mode = match allowed.mode {
+                    AllowedToolsMode::Auto => "auto",
+                    AllowedToolsMode::Required => "required",
+                };
+                let tools: Vec<String> = allowed.tools.iter().map(ToString::to_string).collect();
+                write!(f, "allowed_tools:{}:{}", mode, tools.join(","))
+            }
+            ToolChoice::Tool(reference) => write!(f, "{}", reference),
+            ToolChoice::Other(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Parses the CLI form: `none`, `auto`, `required`, a tool reference, or
+/// `allowed_tools:MODE:REF[,REF...]`
+impl FromStr for ToolChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => return Ok(ToolChoice::none()),
+            "auto" => return Ok(ToolChoice::auto()),
+            "required" => return Ok(ToolChoice::required()),
+            _ => {}
+        }
+
+        if let Some(rest) = s.strip_prefix("allowed_tools:") {
+            let (mode, list) = rest.split_once(':').ok_or_else(|| {
+                "allowed_tools must be given as allowed_tools:MODE:REF[,REF...]".to_string()
+            })?;
+            let mode = match mode {
+                "auto" => AllowedToolsMode::Auto,
+                "required" => AllowedToolsMode::Required,
+                _ => {
+                    return Err(format!(
+                        "allowed_tools mode must be 'auto' or 'required', got '{}'",
+                        mode
+                    ))
+                }
+            };
+            let tools = list
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(ToolChoice::allowed_tools(mode, tools));
+        }
+
+        s.parse().map(ToolChoice::Tool)
+    }
 }
 
 // Complete request structure for the Responses API
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Debug, Default, Clone)]
 pub struct ResponseRequest {
     // Core parameters
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -80,9 +568,9 @@ pub struct ResponseRequest {
 
     // Advanced configuration
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub service_tier: Option<String>, // "auto", "default", "flex", "priority"
+    pub service_tier: Option<ServiceTier>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub truncation: Option<String>, // "auto", "disabled"
+    pub truncation: Option<Truncation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
 
@@ -92,7 +580,7 @@ pub struct ResponseRequest {
 
     // Tools and functions
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tools: Option<Vec<serde_json::Value>>,
+    pub tools: Option<Vec<ToolDefinition>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -106,7 +594,7 @@ pub struct ResponseRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<TextConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub include: Option<Vec<String>>, // Additional output data to include
+    pub include: Option<Vec<IncludeField>>, // Additional output data to include
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>, // Up to 16 key-value pairs
 }
@@ -145,6 +633,97 @@ impl ResponseRequest {
             metadata: None,
         }
     }
+
+    /// Check the tool choice against the tools declared on this request
+    pub fn validate_tool_choice(&self) -> Result<(), ToolChoiceError> {
+        match &self.tool_choice {
+            Some(choice) => choice.validate(self.tools.as_deref().unwrap_or_default()),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the parameters against the limits documented for the API
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(temperature) = self.temperature {
+            check_range("temperature", temperature, 0.0, 2.0)?;
+        }
+        if let Some(top_p) = self.top_p {
+            check_range("top_p", top_p, 0.0, 1.0)?;
+        }
+        if let Some(top_logprobs) = self.top_logprobs {
+            check_range("top_logprobs", top_logprobs, 0, MAX_TOP_LOGPROBS)?;
+        }
+        if let Some(tokens) = self.max_output_tokens {
+            check_range("max_output_tokens", tokens, MIN_MAX_OUTPUT_TOKENS, u32::MAX)?;
+        }
+
+        if let Some(metadata) = &self.metadata {
+            validate_metadata(metadata)?;
+        }
+
+        if self.background == Some(true) && self.store == Some(false) {
+            return Err(ValidationError::Conflict(
+                "background responses must be stored; remove store: false",
+            ));
+        }
+
+        self.validate_tool_choice()?;
+        Ok(())
+    }
+}
+
+// Limits enforced by the API
+pub const MAX_TOP_LOGPROBS: u32 = 20;
+pub const MIN_MAX_OUTPUT_TOKENS: u32 = 16;
+pub const MAX_METADATA_KEYS: usize = 16;
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+pub const MAX_METADATA_VALUE_LEN: usize = 512;
+
+fn check_range<T>(field: &'static str, value: T, min: T, max: T) -> Result<(), ValidationError>
+where
+    T: PartialOrd + fmt::Display,
+{
+    if value >= min && value <= max {
+        Ok(())
+    } else {
+        Err(ValidationError::OutOfRange {
+            field,
+            min: min.to_string(),
+            max: max.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn validate_metadata(metadata: &serde_json::Value) -> Result<(), ValidationError> {
+    let map = metadata
+        .as_object()
+        .ok_or(ValidationError::MetadataNotObject)?;
+    if map.len() > MAX_METADATA_KEYS {
+        return Err(ValidationError::TooManyMetadataKeys {
+            max: MAX_METADATA_KEYS,
+            count: map.len(),
+        });
+    }
+
+    for (key, value) in map {
+        if key.chars().count() > MAX_METADATA_KEY_LEN {
+            return Err(ValidationError::MetadataKeyTooLong {
+                key: key.clone(),
+                max: MAX_METADATA_KEY_LEN,
+            });
+        }
+        let value = value
+            .as_str()
+            .ok_or_else(|| ValidationError::MetadataValueNotString { key: key.clone() })?;
+        if value.chars().count() > MAX_METADATA_VALUE_LEN {
+            return Err(ValidationError::MetadataValueTooLong {
+                key: key.clone(),
+                max: MAX_METADATA_VALUE_LEN,
+            });
+        }
+    }
+    Ok(())
 }
 
 /// Builder pattern for constructing ResponseRequest with fluent API
@@ -200,6 +779,11 @@ impl ResponseRequestBuilder {
         self
     }
 
+    pub fn input_items(mut self, items: Vec<InputItem>) -> Self {
+        self.request.input = Some(InputType::Items(items));
+        self
+    }
+
     pub fn instructions(mut self, instructions: String) -> Self {
         self.request.instructions = Some(instructions);
         self
@@ -221,9 +805,11 @@ impl ResponseRequestBuilder {
         self
     }
 
+    /// Also requests `message.output_text.logprobs`, without which the
+    /// response carries no log probabilities
     pub fn top_logprobs(mut self, logprobs: u32) -> Self {
         self.request.top_logprobs = Some(logprobs);
-        self
+        self.add_include(IncludeField::OutputTextLogprobs)
     }
 
     // Behavior configuration
@@ -248,12 +834,12 @@ impl ResponseRequestBuilder {
     }
 
     // Advanced configuration
-    pub fn service_tier(mut self, tier: String) -> Self {
+    pub fn service_tier(mut self, tier: ServiceTier) -> Self {
         self.request.service_tier = Some(tier);
         self
     }
 
-    pub fn truncation(mut self, truncation: String) -> Self {
+    pub fn truncation(mut self, truncation: Truncation) -> Self {
         self.request.truncation = Some(truncation);
         self
     }
@@ -270,18 +856,21 @@ impl ResponseRequestBuilder {
     }
 
     // Tools and functions
-    pub fn tools(mut self, tools: Vec<serde_json::Value>) -> Self {
+    pub fn tools(mut self, tools: Vec<ToolDefinition>) -> Self {
         self.request.tools = Some(tools);
         self
     }
 
-    pub fn tool_choice_string(mut self, choice: String) -> Self {
-        self.request.tool_choice = Some(ToolChoice::String(choice));
+    pub fn tool(mut self, tool: impl Into<ToolDefinition>) -> Self {
+        self.request
+            .tools
+            .get_or_insert_with(Vec::new)
+            .push(tool.into());
         self
     }
 
-    pub fn tool_choice_object(mut self, choice: serde_json::Value) -> Self {
-        self.request.tool_choice = Some(ToolChoice::Object(choice));
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.request.tool_choice = Some(choice);
         self
     }
 
@@ -301,16 +890,40 @@ impl ResponseRequestBuilder {
         self
     }
 
+    pub fn reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.request
+            .reasoning
+            .get_or_insert_with(Default::default)
+            .effort = Some(effort);
+        self
+    }
+
+    pub fn reasoning_summary(mut self, summary: ReasoningSummary) -> Self {
+        self.request
+            .reasoning
+            .get_or_insert_with(Default::default)
+            .summary = Some(summary);
+        self
+    }
+
     pub fn text_config(mut self, text: TextConfig) -> Self {
         self.request.text = Some(text);
         self
     }
 
-    pub fn include(mut self, include: Vec<String>) -> Self {
+    pub fn include(mut self, include: Vec<IncludeField>) -> Self {
         self.request.include = Some(include);
         self
     }
 
+    pub fn add_include(mut self, field: IncludeField) -> Self {
+        let include = self.request.include.get_or_insert_with(Vec::new);
+        if !include.contains(&field) {
+            include.push(field);
+        }
+        self
+    }
+
     pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
         self.request.metadata = Some(metadata);
         self
@@ -319,18 +932,28 @@ impl ResponseRequestBuilder {
     pub fn build(self) -> ResponseRequest {
         self.request
     }
+
+    /// Build the request after checking it with [`ResponseRequest::validate`]
+    pub fn try_build(self) -> Result<ResponseRequest, ValidationError> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
 }
 
 // Text format configuration
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TextFormat {
     #[serde(rename = "type")]
-    pub format_type: String,
+    pub format_type: TextFormatType,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, // e.g. json_schema name and schema
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TextConfig {
     pub format: TextFormat,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 // Reasoning information
@@ -342,17 +965,120 @@ pub struct Reasoning {
     pub summary: Option<String>,
 }
 
+// Output items returned in a response's `output` array
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum OutputItem {
+    #[serde(rename = "message")]
+    Message(OutputMessage),
+    #[serde(rename = "reasoning")]
+    Reasoning(ReasoningItem),
+    #[serde(rename = "function_call")]
+    FunctionCall(FunctionCall),
+    #[serde(rename = "mcp_list_tools")]
+    McpListTools(McpListTools),
+    #[serde(rename = "mcp_call")]
+    McpCall(McpCall),
+    #[serde(rename = "mcp_approval_request")]
+    McpApprovalRequest(McpApprovalRequest),
+    // Hosted tool calls and newer item types, kept as returned
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
 // Output message structure for the new API
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OutputMessage {
-    #[serde(rename = "type")]
-    pub message_type: String, // "message"
     pub id: String,
-    pub status: String, // "completed"
-    pub role: String,   // "assistant"
+    pub status: ItemStatus,
+    pub role: Role,
     pub content: Vec<ContentType>,
 }
 
+// Reasoning performed by the model before answering
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReasoningItem {
+    pub id: String,
+    #[serde(default)]
+    pub summary: Vec<ReasoningSummaryPart>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ReasoningSummaryPart {
+    #[serde(rename = "summary_text")]
+    SummaryText { text: String },
+}
+
+impl ReasoningItem {
+    /// Summary parts as plain text
+    pub fn summary_texts(&self) -> impl Iterator<Item = &str> {
+        self.summary.iter().map(|part| match part {
+            ReasoningSummaryPart::SummaryText { text } => text.as_str(),
+        })
+    }
+}
+
+// A call the model made to a function tool
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub call_id: String,
+    pub name: String,
+    pub arguments: String, // JSON-encoded arguments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+// Tools imported from a remote MCP server
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpListTools {
+    pub id: String,
+    pub server_label: String,
+    pub tools: Vec<McpToolInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<serde_json::Value>,
+}
+
+// A call the model made to a remote MCP tool
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpCall {
+    pub id: String,
+    pub server_label: String,
+    pub name: String,
+    pub arguments: String, // JSON-encoded arguments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_request_id: Option<String>,
+}
+
+// A remote MCP tool call waiting for the caller's approval
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpApprovalRequest {
+    pub id: String,
+    pub server_label: String,
+    pub name: String,
+    pub arguments: String, // JSON-encoded arguments
+}
+
 // Detailed usage statistics
 #[derive(Serialize, Deserialize, Debug)]
 pub struct InputTokensDetails {
@@ -366,10 +1092,28 @@ pub struct OutputTokensDetails {
     pub reasoning_tokens: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Why a response stopped before completing
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IncompleteDetails {
+    pub reason: IncompleteReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncompleteReason {
+    MaxOutputTokens,
+    ContentFilter,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Usage {
+    #[serde(default)]
     pub input_tokens: u32,
+    #[serde(default)]
     pub output_tokens: u32,
+    #[serde(default)]
     pub total_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_tokens_details: Option<InputTokensDetails>,
@@ -377,38 +1121,206 @@ pub struct Usage {
     pub output_tokens_details: Option<OutputTokensDetails>,
 }
 
-// Complete response structure matching the new OpenAI Responses API format
+impl Usage {
+    /// Add the usage of another call, detail counts included
+    pub fn add(&mut self, other: Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+
+        let cached = |usage: &Usage| {
+            usage
+                .input_tokens_details
+                .as_ref()
+                .and_then(|details| details.cached_tokens)
+        };
+        if let Some(cached_tokens) = add_counts(cached(self), cached(&other)) {
+            self.input_tokens_details = Some(InputTokensDetails {
+                cached_tokens: Some(cached_tokens),
+            });
+        }
+        let reasoning = |usage: &Usage| {
+            usage
+                .output_tokens_details
+                .as_ref()
+                .and_then(|details| details.reasoning_tokens)
+        };
+        if let Some(reasoning_tokens) = add_counts(reasoning(self), reasoning(&other)) {
+            self.output_tokens_details = Some(OutputTokensDetails {
+                reasoning_tokens: Some(reasoning_tokens),
+            });
+        }
+    }
+}
+
+// Sum of two optional counts, `None` only if both are missing
+fn add_counts(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+    }
+}
+
+// Complete response structure matching the new OpenAI Responses API format.
+// Only `id` is required: fields may be null or missing depending on the model
+// and response status, and fields this crate does not know are kept in `extra`.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResponseApiResponse {
     pub id: String,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub object: String, // "response"
+    #[serde(default, deserialize_with = "null_as_default")]
     pub created_at: u64,
-    pub status: String, // "completed"
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ResponseStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub incomplete_details: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub instructions: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incomplete_details: Option<IncompleteDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<serde_json::Value>, // string, or input items for prompt templates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_output_tokens: Option<u32>,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub model: String,
-    pub output: Vec<OutputMessage>,
-    pub parallel_tool_calls: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub output: Vec<OutputItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub previous_response_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<PromptTemplate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<Reasoning>,
-    pub store: bool,
-    pub temperature: f32,
-    pub text: TextConfig,
-    pub tool_choice: String, // "auto"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTier>, // tier that actually processed the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub tools: Vec<serde_json::Value>,
-    pub top_p: f32,
-    pub truncation: String, // "disabled"
-    pub usage: Usage,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub usage: Usage, // null while a background response is still running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+    // Estimated by this crate from its pricing table; never sent by the API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<Cost>,
+    // Set by this crate when the response was served from the local cache
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+// Treat an explicit `null` like a missing field
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl ResponseApiResponse {
+    /// Whether the response stopped early; see `incomplete_details` for why
+    pub fn is_incomplete(&self) -> bool {
+        self.status == Some(ResponseStatus::Incomplete)
+    }
+
+    pub fn incomplete_reason(&self) -> Option<IncompleteReason> {
+        self.incomplete_details
+            .as_ref()
+            .map(|details| details.reason)
+    }
+
+    /// Whether the answer was cut off by `max_output_tokens`
+    pub fn is_truncated(&self) -> bool {
+        self.is_incomplete() && self.incomplete_reason() == Some(IncompleteReason::MaxOutputTokens)
+    }
+
+    /// Refusal messages the model returned instead of an answer
+    pub fn refusals(&self) -> impl Iterator<Item = &str> {
+        self.messages()
+            .flat_map(|message| &message.content)
+            .filter_map(|content| match content {
+                ContentType::Refusal { refusal } => Some(refusal.as_str()),
+                _ => None,
+            })
+    }
+
+    pub fn is_refusal(&self) -> bool {
+        self.refusals().next().is_some()
+    }
+
+    /// Append a continuation of this response: its output follows ours and
+    /// its status, usage and identifiers replace or add to ours
+    pub fn extend_with(&mut self, continuation: ResponseApiResponse) {
+        self.usage.add(continuation.usage);
+        self.output.extend(continuation.output);
+        self.id = continuation.id;
+        self.status = continuation.status;
+        self.incomplete_details = continuation.incomplete_details;
+    }
+
+    /// Assistant messages in the output, skipping tool items
+    pub fn messages(&self) -> impl Iterator<Item = &OutputMessage> {
+        self.output.iter().filter_map(|item| match item {
+            OutputItem::Message(message) => Some(message),
+            _ => None,
+        })
+    }
+
+    /// Reasoning items, in output order
+    pub fn reasoning_items(&self) -> impl Iterator<Item = &ReasoningItem> {
+        self.output.iter().filter_map(|item| match item {
+            OutputItem::Reasoning(reasoning) => Some(reasoning),
+            _ => None,
+        })
+    }
+
+    /// Reasoning items to send back as input on the next turn; with
+    /// `reasoning.encrypted_content` included this chains turns without `store`
+    pub fn reasoning_input_items(&self) -> Vec<InputItem> {
+        self.reasoning_items()
+            .cloned()
+            .map(InputItem::Reasoning)
+            .collect()
+    }
+
+    /// Function calls the caller is expected to run and answer
+    pub fn function_calls(&self) -> impl Iterator<Item = &FunctionCall> {
+        self.output.iter().filter_map(|item| match item {
+            OutputItem::FunctionCall(call) => Some(call),
+            _ => None,
+        })
+    }
+
+    /// Remote MCP calls the model made while producing this response
+    pub fn mcp_calls(&self) -> impl Iterator<Item = &McpCall> {
+        self.output.iter().filter_map(|item| match item {
+            OutputItem::McpCall(call) => Some(call),
+            _ => None,
+        })
+    }
+
+    /// MCP tool calls that need approval before the model can continue
+    pub fn mcp_approval_requests(&self) -> impl Iterator<Item = &McpApprovalRequest> {
+        self.output.iter().filter_map(|item| match item {
+            OutputItem::McpApprovalRequest(request) => Some(request),
+            _ => None,
+        })
+    }
 }
@@ -0,0 +1,201 @@
+use crate::api::models::{ResponseApiResponse, ResponseRequest, ServiceTier, Usage};
+use crate::utils::tokens::{context_window, count_request_tokens};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+const TOKENS_PER_UNIT: f64 = 1_000_000.0;
+
+// Standard-tier list prices in USD per million tokens as (model prefix,
+// input, cached input, output); the longest matching prefix wins
+const DEFAULT_PRICES: &[(&str, f64, Option<f64>, f64)] = &[
+    ("gpt-5-nano", 0.05, Some(0.005), 0.40),
+    ("gpt-5-mini", 0.25, Some(0.025), 2.00),
+    ("gpt-5", 1.25, Some(0.125), 10.00),
+    ("gpt-4.1-nano", 0.10, Some(0.025), 0.40),
+    ("gpt-4.1-mini", 0.40, Some(0.10), 1.60),
+    ("gpt-4.1", 2.00, Some(0.50), 8.00),
+    ("gpt-4o-mini", 0.15, Some(0.075), 0.60),
+    ("gpt-4o", 2.50, Some(1.25), 10.00),
+    ("gpt-4-turbo", 10.00, None, 30.00),
+    ("gpt-4", 30.00, None, 60.00),
+    ("gpt-3.5-turbo", 0.50, None, 1.50),
+    ("o1-mini", 1.10, Some(0.55), 4.40),
+    ("o1", 15.00, Some(7.50), 60.00),
+    ("o3-mini", 1.10, Some(0.55), 4.40),
+    ("o3", 2.00, Some(0.50), 8.00),
+    ("o4-mini", 1.10, Some(0.275), 4.40),
+];
+
+// Price multipliers for service tiers other than the standard one
+const FLEX_MULTIPLIER: f64 = 0.5;
+const PRIORITY_MULTIPLIER: f64 = 1.75;
+
+/// Rates for one model in USD per million tokens
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    /// Rate for cached input tokens; the input rate when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+    pub output: f64,
+    /// Rate for reasoning tokens; the output rate when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<f64>,
+}
+
+impl ModelPrice {
+    fn cached_input_rate(&self) -> f64 {
+        self.cached_input.unwrap_or(self.input)
+    }
+
+    fn reasoning_rate(&self) -> f64 {
+        self.reasoning.unwrap_or(self.output)
+    }
+}
+
+/// Estimated cost of a response in USD, split by token kind
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Cost {
+    pub input: f64,
+    pub cached_input: f64,
+    pub output: f64,
+    pub reasoning: f64,
+    pub total: f64,
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_usd(self.total))
+    }
+}
+
+// Whether `model` is `family` or one of its variants, e.g. a dated snapshot
+pub(crate) fn in_family(model: &str, family: &str) -> bool {
+    model
+        .strip_prefix(family)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+/// Dollar amount with enough decimals for sub-cent costs
+pub fn format_usd(amount: f64) -> String {
+    format!("${:.6}", amount)
+}
+
+/// Built-in list prices with overrides from the `[pricing]` config table
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PricingTable {
+    /// Rates keyed by model name or family; the longest matching key wins
+    pub models: BTreeMap<String, ModelPrice>,
+    /// Multipliers keyed by service tier, e.g. `flex = 0.5`
+    pub service_tiers: BTreeMap<String, f64>,
+}
+
+impl PricingTable {
+    /// Rates for `model` under the longest matching key, configured or
+    /// built in, with configured overrides winning keys of equal length. A
+    /// key matches the model itself and its `-` suffixed variants, so `gpt-4`
+    /// prices `gpt-4-0613` but not `gpt-4.5-preview`.
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        let configured = self
+            .models
+            .iter()
+            .map(|(prefix, price)| (prefix.as_str(), *price, true));
+        let built_in = DEFAULT_PRICES
+            .iter()
+            .map(|&(prefix, input, cached_input, output)| {
+                let price = ModelPrice {
+                    input,
+                    cached_input,
+                    output,
+                    reasoning: None,
+                };
+                (prefix, price, false)
+            });
+        built_in
+            .chain(configured)
+            .filter(|(prefix, ..)| in_family(model, prefix))
+            .max_by_key(|&(prefix, _, configured)| (prefix.len(), configured))
+            .map(|(_, price, _)| price)
+    }
+
+    /// Price multiplier of the tier that processed (or will process) a request
+    pub fn tier_multiplier(&self, tier: Option<&ServiceTier>) -> f64 {
+        let Some(tier) = tier else {
+            return 1.0;
+        };
+        if let Some(multiplier) = self.service_tiers.get(tier.as_str()) {
+            return *multiplier;
+        }
+        match tier {
+            ServiceTier::Flex => FLEX_MULTIPLIER,
+            ServiceTier::Priority => PRIORITY_MULTIPLIER,
+            _ => 1.0,
+        }
+    }
+
+    /// Cost of `usage` on `model`, or `None` when the model has no known price
+    pub fn usage_cost(
+        &self,
+        model: &str,
+        tier: Option<&ServiceTier>,
+        usage: &Usage,
+    ) -> Option<Cost> {
+        let price = self.price(model)?;
+        let multiplier = self.tier_multiplier(tier) / TOKENS_PER_UNIT;
+
+        let cached = usage
+            .input_tokens_details
+            .as_ref()
+            .and_then(|details| details.cached_tokens)
+            .unwrap_or_default()
+            .min(usage.input_tokens);
+        let reasoning = usage
+            .output_tokens_details
+            .as_ref()
+            .and_then(|details| details.reasoning_tokens)
+            .unwrap_or_default()
+            .min(usage.output_tokens);
+
+        let input = f64::from(usage.input_tokens - cached) * price.input * multiplier;
+        let cached_input = f64::from(cached) * price.cached_input_rate() * multiplier;
+        let output = f64::from(usage.output_tokens - reasoning) * price.output * multiplier;
+        let reasoning = f64::from(reasoning) * price.reasoning_rate() * multiplier;
+
+        Some(Cost {
+            input,
+            cached_input,
+            output,
+            reasoning,
+            total: input + cached_input + output + reasoning,
+        })
+    }
+
+    /// Cost of a finished response, priced at the tier that processed it
+    pub fn cost(&self, response: &ResponseApiResponse) -> Option<Cost> {
+        self.usage_cost(
+            &response.model,
+            response.service_tier.as_ref(),
+            &response.usage,
+        )
+    }
+
+    /// Upper bound on what `request` can cost: its estimated input with no
+    /// cache hits, plus `max_output_tokens` (or the rest of the context
+    /// window) at the higher of the output and reasoning rates. `None` when
+    /// the model has no known price or the output is unbounded.
+    pub fn worst_case_cost(&self, request: &ResponseRequest) -> Option<f64> {
+        let model = request.model.as_deref()?;
+        let price = self.price(model)?;
+        let input_tokens = count_request_tokens(request);
+        let output_tokens = match request.max_output_tokens {
+            Some(tokens) => tokens as usize,
+            None => context_window(model)?.saturating_sub(input_tokens),
+        };
+
+        let multiplier = self.tier_multiplier(request.service_tier.as_ref()) / TOKENS_PER_UNIT;
+        let output_rate = price.output.max(price.reasoning_rate());
+        Some((input_tokens as f64 * price.input + output_tokens as f64 * output_rate) * multiplier)
+    }
+}
@@ -0,0 +1,276 @@
+use crate::api::models::ResponseRequest;
+use crate::utils::tokens::count_request_tokens;
+use reqwest::header::HeaderMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// What shares one set of limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// All requests made with the same API key
+    #[default]
+    Key,
+    /// Requests for the same model with the same API key, matching how the
+    /// API enforces its limits
+    Model,
+}
+
+/// Client-side limits on requests per minute, tokens per minute and requests
+/// in flight, so that many concurrent callers of one client wait their turn
+/// instead of running into 429s. Waiting requests are served in arrival
+/// order. Share one limiter between clients through an `Arc`.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    requests_per_minute: Option<u32>,
+    tokens_per_minute: Option<u32>,
+    max_in_flight: Option<usize>,
+    scope: RateLimitScope,
+    buckets: Mutex<HashMap<BucketKey, Arc<Bucket>>>,
+}
+
+// Hash of the API key, so the key itself is not kept, and model if per model
+type BucketKey = (u64, Option<String>);
+
+#[derive(Debug)]
+struct Bucket {
+    // Held while waiting for budget, so requests take it in arrival order
+    queue: tokio::sync::Mutex<()>,
+    budget: Mutex<Budget>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+#[derive(Debug)]
+struct Budget {
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+}
+
+// Holds up to a minute's allowance and refills continuously
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            capacity: per_minute as f64,
+            available: per_minute as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.updated = now;
+    }
+
+    // Time until `amount` is available; more than the capacity waits for a
+    // full bucket
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.capacity)
+        }
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.available -= amount.min(self.capacity);
+    }
+}
+
+/// A place in the in-flight limit, released when dropped
+#[derive(Debug)]
+pub struct RateLimitPermit {
+    _in_flight: Option<OwnedSemaphorePermit>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start at most `limit` requests per minute; 0 means no limit
+    pub fn requests_per_minute(mut self, limit: u32) -> Self {
+        self.requests_per_minute = Some(limit).filter(|limit| *limit > 0);
+        self
+    }
+
+    /// Spend at most `limit` tokens per minute, counting each request's
+    /// estimated input tokens plus its `max_output_tokens`; 0 means no limit
+    pub fn tokens_per_minute(mut self, limit: u32) -> Self {
+        self.tokens_per_minute = Some(limit).filter(|limit| *limit > 0);
+        self
+    }
+
+    /// Keep at most `limit` requests waiting on the API at once; 0 means no
+    /// limit
+    pub fn max_in_flight(mut self, limit: usize) -> Self {
+        self.max_in_flight = Some(limit).filter(|limit| *limit > 0);
+        self
+    }
+
+    pub fn scope(mut self, scope: RateLimitScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Estimated tokens a request counts against the tokens-per-minute limit
+    pub fn estimated_tokens(request: &ResponseRequest) -> u64 {
+        count_request_tokens(request) as u64 + request.max_output_tokens.unwrap_or_default() as u64
+    }
+
+    /// Wait until `request` may be sent with `api_key`
+    pub async fn acquire(&self, api_key: &str, request: &ResponseRequest) -> RateLimitPermit {
+        let tokens = Self::estimated_tokens(request) as f64;
+        self.wait(api_key, request.model.as_deref(), tokens).await
+    }
+
+    /// Wait until a call without a request body, such as polling with
+    /// `retrieve_response`, may be sent; it counts as a request but spends no
+    /// tokens. Its model is not known, so in `Model` scope it shares a bucket
+    /// with other such calls.
+    pub async fn acquire_call(&self, api_key: &str) -> RateLimitPermit {
+        self.wait(api_key, None, 0.0).await
+    }
+
+    async fn wait(&self, api_key: &str, model: Option<&str>, tokens: f64) -> RateLimitPermit {
+        let bucket = self.bucket(api_key, model);
+        let in_flight = match &bucket.in_flight {
+            // The semaphore is never closed
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        let _turn = bucket.queue.lock().await;
+        loop {
+            let wait = {
+                let mut budget = bucket.budget.lock().unwrap();
+                let now = Instant::now();
+                let wait_for = |bucket: Option<&mut TokenBucket>, amount| {
+                    bucket.map_or(Duration::ZERO, |bucket| {
+                        bucket.refill(now);
+                        bucket.wait_for(amount)
+                    })
+                };
+                let wait = wait_for(budget.requests.as_mut(), 1.0)
+                    .max(wait_for(budget.tokens.as_mut(), tokens));
+                if wait.is_zero() {
+                    budget
+                        .requests
+                        .iter_mut()
+                        .for_each(|bucket| bucket.take(1.0));
+                    budget
+                        .tokens
+                        .iter_mut()
+                        .for_each(|bucket| bucket.take(tokens));
+                }
+                wait
+            };
+            if wait.is_zero() {
+                break;
+            }
+            tracing::debug!(wait_ms = wait.as_millis() as u64, "waiting for rate limit");
+            tokio::time::sleep(wait).await;
+        }
+
+        RateLimitPermit {
+            _in_flight: in_flight,
+        }
+    }
+
+    /// Adopt the remaining allowance the API reported in its
+    /// `x-ratelimit-remaining-requests` and `-tokens` headers. Only in `Model`
+    /// scope: the API reports per model, so in `Key` scope one model's
+    /// allowance would wrongly replace that of every other.
+    pub fn update(&self, api_key: &str, model: Option<&str>, headers: &HeaderMap) {
+        if self.scope != RateLimitScope::Model {
+            return;
+        }
+        let remaining = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+        };
+        let requests = remaining("x-ratelimit-remaining-requests");
+        let tokens = remaining("x-ratelimit-remaining-tokens");
+        if requests.is_none() && tokens.is_none() {
+            return;
+        }
+
+        let bucket = self.bucket(api_key, model);
+        let mut budget = bucket.budget.lock().unwrap();
+        let budget = &mut *budget;
+        let now = Instant::now();
+        for (bucket, remaining) in [
+            (&mut budget.requests, requests),
+            (&mut budget.tokens, tokens),
+        ] {
+            if let (Some(bucket), Some(remaining)) = (bucket, remaining) {
+                bucket.refill(now);
+                bucket.available = remaining.min(bucket.capacity);
+            }
+        }
+    }
+
+    /// Requests that could start now without waiting, if limited
+    pub fn available_requests(&self, api_key: &str, model: Option<&str>) -> Option<f64> {
+        self.available(api_key, model, |budget| budget.requests.as_mut())
+    }
+
+    /// Tokens that could be spent now without waiting, if limited
+    pub fn available_tokens(&self, api_key: &str, model: Option<&str>) -> Option<f64> {
+        self.available(api_key, model, |budget| budget.tokens.as_mut())
+    }
+
+    fn available(
+        &self,
+        api_key: &str,
+        model: Option<&str>,
+        pick: impl FnOnce(&mut Budget) -> Option<&mut TokenBucket>,
+    ) -> Option<f64> {
+        let bucket = self.bucket(api_key, model);
+        let mut budget = bucket.budget.lock().unwrap();
+        let bucket = pick(&mut budget)?;
+        bucket.refill(Instant::now());
+        Some(bucket.available)
+    }
+
+    fn bucket(&self, api_key: &str, model: Option<&str>) -> Arc<Bucket> {
+        let mut hasher = DefaultHasher::new();
+        api_key.hash(&mut hasher);
+        let model = match self.scope {
+            RateLimitScope::Key => None,
+            RateLimitScope::Model => Some(model.unwrap_or_default().to_string()),
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets
+            .entry((hasher.finish(), model))
+            .or_insert_with(|| {
+                Arc::new(Bucket {
+                    queue: tokio::sync::Mutex::new(()),
+                    budget: Mutex::new(Budget {
+                        requests: self.requests_per_minute.map(TokenBucket::new),
+                        tokens: self.tokens_per_minute.map(TokenBucket::new),
+                    }),
+                    in_flight: self
+                        .max_in_flight
+                        .map(|limit| Arc::new(Semaphore::new(limit))),
+                })
+            })
+            .clone()
+    }
+}
@@ -0,0 +1,183 @@
+use crate::api::errors::OpenAIError;
+use crate::api::models::{ResponseApiResponse, ResponseStatus};
+use crate::api::transport::ByteStream;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+
+/// Events of a streamed response as they arrive
+pub type ResponseStream = BoxStream<'static, Result<StreamEvent, OpenAIError>>;
+
+/// One server-sent event of a streamed response, such as
+/// `response.output_text.delta` or `response.completed`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEvent {
+    /// From the `event:` line, else the `type` of the data
+    pub event: String,
+    /// The JSON data, or a string when the data is not JSON
+    pub data: Value,
+}
+
+impl StreamEvent {
+    pub fn new(event: impl Into<String>, data: Value) -> Self {
+        Self {
+            event: event.into(),
+            data,
+        }
+    }
+
+    /// The final event for `response`, e.g. to answer a stream without the API
+    pub fn finished(response: &ResponseApiResponse) -> Self {
+        let event = match response.status {
+            Some(ResponseStatus::Incomplete) => "response.incomplete",
+            Some(ResponseStatus::Failed) => "response.failed",
+            _ => "response.completed",
+        };
+        Self::new(event, json!({ "type": event, "response": response }))
+    }
+
+    /// Text added by a `response.output_text.delta` event
+    pub fn text_delta(&self) -> Option<&str> {
+        match self.event.as_str() {
+            "response.output_text.delta" => self.data["delta"].as_str(),
+            _ => None,
+        }
+    }
+
+    /// Whether the event ends the stream with the final response
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self.event.as_str(),
+            "response.completed" | "response.incomplete" | "response.failed"
+        )
+    }
+
+    /// The response snapshot carried by `response.*` lifecycle events
+    pub fn response(&self) -> Option<ResponseApiResponse> {
+        serde_json::from_value(self.data.get("response")?.clone()).ok()
+    }
+
+    /// The failure reported by an `error` event
+    pub fn error(&self) -> Option<OpenAIError> {
+        if self.event != "error" {
+            return None;
+        }
+        let body = json!({ "error": self.data }).to_string();
+        Some(OpenAIError::from_response(
+            StatusCode::OK,
+            &HeaderMap::new(),
+            &body,
+        ))
+    }
+}
+
+/// Splits a `text/event-stream` body into events as its chunks arrive
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events completed by `chunk`
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<StreamEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some((end, separator)) = block_end(&self.buffer) {
+            let block: Vec<u8> = self.buffer.drain(..end + separator).take(end).collect();
+            events.extend(parse_block(&String::from_utf8_lossy(&block)));
+        }
+        events
+    }
+
+    /// The last event, when the body did not end with a blank line
+    pub fn finish(&mut self) -> Option<StreamEvent> {
+        let block = std::mem::take(&mut self.buffer);
+        parse_block(&String::from_utf8_lossy(&block))
+    }
+}
+
+/// Decode a streamed body into its events
+pub fn decode(body: ByteStream) -> ResponseStream {
+    let state = (body, SseDecoder::new(), VecDeque::new(), false);
+    stream::unfold(
+        state,
+        |(mut body, mut decoder, mut ready, mut done)| async move {
+            loop {
+                if let Some(event) = ready.pop_front() {
+                    return Some((Ok(event), (body, decoder, ready, done)));
+                }
+                if done {
+                    return None;
+                }
+                match body.next().await {
+                    Some(Ok(chunk)) => ready.extend(decoder.push(&chunk)),
+                    Some(Err(error)) => return Some((Err(error), (body, decoder, ready, true))),
+                    None => {
+                        ready.extend(decoder.finish());
+                        done = true;
+                    }
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+/// Read a stream to its final event and return that response
+pub async fn collect_response(
+    mut events: ResponseStream,
+) -> Result<ResponseApiResponse, OpenAIError> {
+    while let Some(event) = events.next().await {
+        let event = event?;
+        if let Some(error) = event.error() {
+            return Err(error);
+        }
+        if event.is_final() {
+            return event.response().ok_or_else(|| {
+                OpenAIError::JsonParseError(format!("{} event without a response", event.event))
+            });
+        }
+    }
+    Err(OpenAIError::EmptyResponse)
+}
+
+// Offset and length of the blank line ending the first event
+fn block_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    (0..buffer.len()).find_map(|i| {
+        [&b"\r\n\r\n"[..], b"\n\n", b"\r\r"]
+            .iter()
+            .find(|separator| buffer[i..].starts_with(separator))
+            .map(|separator| (i, separator.len()))
+    })
+}
+
+fn parse_block(block: &str) -> Option<StreamEvent> {
+    let mut event = None;
+    let mut data: Vec<&str> = Vec::new();
+    for line in block.split(['\r', '\n']) {
+        // Lines starting with `:` are comments, e.g. keep-alives
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = Some(value.to_string()),
+            "data" => data.push(value),
+            _ => {}
+        }
+    }
+    let data = data.join("\n");
+    if data.is_empty() || data == "[DONE]" {
+        return None;
+    }
+
+    let data = serde_json::from_str(&data).unwrap_or(Value::String(data));
+    let event = event
+        .or_else(|| data["type"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "message".to_string());
+    Some(StreamEvent::new(event, data))
+}
@@ -0,0 +1,129 @@
+use crate::api::errors::OpenAIError;
+use crate::api::models::{ResponseApiResponse, ResponseRequest};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::time::Duration;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// What the client's tracing spans carry beyond model, IDs, status, latency,
+/// token usage and retries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TelemetryOptions {
+    /// Log request and response bodies at debug level; they may hold prompts
+    /// and personal data, so this is off by default
+    pub log_bodies: bool,
+    /// Also set the OpenTelemetry GenAI semantic convention attributes
+    /// (`gen_ai.*`)
+    pub genai_attributes: bool,
+}
+
+impl TelemetryOptions {
+    pub fn log_bodies(mut self, log_bodies: bool) -> Self {
+        self.log_bodies = log_bodies;
+        self
+    }
+
+    pub fn genai_attributes(mut self, genai_attributes: bool) -> Self {
+        self.genai_attributes = genai_attributes;
+        self
+    }
+}
+
+/// Span for one `send_request` call; fields are filled in as they are known
+pub(crate) fn request_span(request: &ResponseRequest, options: TelemetryOptions) -> Span {
+    let model = request.model.as_deref().unwrap_or_default();
+    let span = tracing::info_span!(
+        "openai.response",
+        model,
+        request_id = Empty,
+        response_id = Empty,
+        status = Empty,
+        http_status = Empty,
+        latency_ms = Empty,
+        input_tokens = Empty,
+        output_tokens = Empty,
+        cached = Empty,
+        retries = 0u32,
+        error = Empty,
+        gen_ai.operation.name = Empty,
+        gen_ai.system = Empty,
+        gen_ai.request.model = Empty,
+        gen_ai.request.temperature = Empty,
+        gen_ai.request.top_p = Empty,
+        gen_ai.request.max_tokens = Empty,
+        gen_ai.response.id = Empty,
+        gen_ai.response.model = Empty,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+    );
+    if options.genai_attributes {
+        span.record("gen_ai.operation.name", "chat");
+        span.record("gen_ai.system", "openai");
+        span.record("gen_ai.request.model", model);
+        if let Some(temperature) = request.temperature {
+            span.record("gen_ai.request.temperature", temperature as f64);
+        }
+        if let Some(top_p) = request.top_p {
+            span.record("gen_ai.request.top_p", top_p as f64);
+        }
+        if let Some(max_tokens) = request.max_output_tokens {
+            span.record("gen_ai.request.max_tokens", max_tokens);
+        }
+    }
+    span
+}
+
+/// Record what the HTTP reply said on the current request span
+pub(crate) fn record_http(status: StatusCode, headers: &HeaderMap) {
+    let span = Span::current();
+    span.record("http_status", status.as_u16());
+    if let Some(request_id) = headers
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+    {
+        span.record("request_id", request_id);
+    }
+}
+
+pub(crate) fn record_response(
+    span: &Span,
+    response: &ResponseApiResponse,
+    latency: Duration,
+    options: TelemetryOptions,
+) {
+    span.record("latency_ms", latency.as_millis() as u64);
+    span.record("response_id", response.id.as_str());
+    if let Some(status) = &response.status {
+        span.record("status", status.to_string());
+    }
+    span.record("input_tokens", response.usage.input_tokens);
+    span.record("output_tokens", response.usage.output_tokens);
+    if response.cached {
+        span.record("cached", true);
+    }
+    if options.genai_attributes {
+        span.record("gen_ai.response.id", response.id.as_str());
+        span.record("gen_ai.response.model", response.model.as_str());
+        span.record("gen_ai.usage.input_tokens", response.usage.input_tokens);
+        span.record("gen_ai.usage.output_tokens", response.usage.output_tokens);
+    }
+    tracing::info!(parent: span, "response received");
+}
+
+pub(crate) fn record_error(span: &Span, error: &OpenAIError, latency: Duration) {
+    span.record("latency_ms", latency.as_millis() as u64);
+    span.record("error", error.to_string());
+    if let Some(request_id) = error.request_id() {
+        span.record("request_id", request_id);
+    }
+    tracing::warn!(parent: span, error = %error, "request failed");
+}
+
+/// Log a body at debug level, when enabled
+pub(crate) fn log_body(options: TelemetryOptions, kind: &str, body: &impl serde::Serialize) {
+    if options.log_bodies {
+        let body = serde_json::to_string(body).unwrap_or_default();
+        tracing::debug!(kind, body, "body");
+    }
+}
@@ -0,0 +1,494 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Tool definitions for the `tools` request parameter
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ToolDefinition {
+    #[serde(rename = "function")]
+    Function(FunctionTool),
+    #[serde(rename = "web_search_preview", alias = "web_search")]
+    WebSearch(WebSearchTool),
+    #[serde(rename = "file_search")]
+    FileSearch(FileSearchTool),
+    #[serde(rename = "code_interpreter")]
+    CodeInterpreter(CodeInterpreterTool),
+    #[serde(rename = "image_generation")]
+    ImageGeneration(ImageGenerationTool),
+    #[serde(rename = "computer_use_preview")]
+    ComputerUse(ComputerUseTool),
+    #[serde(rename = "mcp")]
+    Mcp(McpTool),
+    // Tool types this crate does not model yet, sent as given
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+impl ToolDefinition {
+    /// Custom function tool with a JSON schema for its parameters
+    pub fn function(name: String, parameters: serde_json::Value) -> Self {
+        ToolDefinition::Function(FunctionTool::new(name, parameters))
+    }
+
+    /// Hosted web search with default settings
+    pub fn web_search() -> Self {
+        ToolDefinition::WebSearch(WebSearchTool::new())
+    }
+
+    /// Hosted file search over the given vector stores
+    pub fn file_search(vector_store_ids: Vec<String>) -> Self {
+        ToolDefinition::FileSearch(FileSearchTool::new(vector_store_ids))
+    }
+
+    /// Hosted code interpreter running in an automatically created container
+    pub fn code_interpreter() -> Self {
+        ToolDefinition::CodeInterpreter(CodeInterpreterTool::new())
+    }
+
+    /// Hosted image generation with default settings
+    pub fn image_generation() -> Self {
+        ToolDefinition::ImageGeneration(ImageGenerationTool::new())
+    }
+
+    /// Computer use with the given display size and environment
+    pub fn computer_use(
+        display_width: u32,
+        display_height: u32,
+        environment: ComputerEnvironment,
+    ) -> Self {
+        ToolDefinition::ComputerUse(ComputerUseTool::new(
+            display_width,
+            display_height,
+            environment,
+        ))
+    }
+
+    /// Remote MCP server reachable by the API
+    pub fn mcp(server_label: String, server_url: String) -> Self {
+        ToolDefinition::Mcp(McpTool::new(server_label, server_url))
+    }
+
+    /// The `type` tag this tool serializes with
+    pub fn tool_type(&self) -> &str {
+        match self {
+            ToolDefinition::Function(_) => "function",
+            ToolDefinition::WebSearch(_) => "web_search_preview",
+            ToolDefinition::FileSearch(_) => "file_search",
+            ToolDefinition::CodeInterpreter(_) => "code_interpreter",
+            ToolDefinition::ImageGeneration(_) => "image_generation",
+            ToolDefinition::ComputerUse(_) => "computer_use_preview",
+            ToolDefinition::Mcp(_) => "mcp",
+            ToolDefinition::Other(tool) => tool["type"].as_str().unwrap_or_default(),
+        }
+    }
+}
+
+// Function tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FunctionTool {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub parameters: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+impl FunctionTool {
+    pub fn new(name: String, parameters: serde_json::Value) -> Self {
+        Self {
+            name,
+            description: None,
+            parameters,
+            strict: None,
+        }
+    }
+
+    pub fn description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = Some(strict);
+        self
+    }
+}
+
+// Web search tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct WebSearchTool {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_location: Option<UserLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_context_size: Option<SearchContextSize>,
+}
+
+impl WebSearchTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user_location(mut self, location: UserLocation) -> Self {
+        self.user_location = Some(location);
+        self
+    }
+
+    pub fn search_context_size(mut self, size: SearchContextSize) -> Self {
+        self.search_context_size = Some(size);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchContextSize {
+    Low,
+    Medium,
+    High,
+}
+
+// Approximate user location used to localize web search results
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UserLocation {
+    #[serde(rename = "type")]
+    pub location_type: String, // "approximate"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>, // ISO 3166-1 alpha-2 code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>, // IANA timezone
+}
+
+impl UserLocation {
+    pub fn approximate() -> Self {
+        Self {
+            location_type: "approximate".to_string(),
+            city: None,
+            country: None,
+            region: None,
+            timezone: None,
+        }
+    }
+
+    pub fn city(mut self, city: String) -> Self {
+        self.city = Some(city);
+        self
+    }
+
+    pub fn country(mut self, country: String) -> Self {
+        self.country = Some(country);
+        self
+    }
+
+    pub fn region(mut self, region: String) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: String) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+}
+
+// File search tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileSearchTool {
+    pub vector_store_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_results: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranking_options: Option<serde_json::Value>,
+}
+
+impl FileSearchTool {
+    pub fn new(vector_store_ids: Vec<String>) -> Self {
+        Self {
+            vector_store_ids,
+            max_num_results: None,
+            filters: None,
+            ranking_options: None,
+        }
+    }
+
+    pub fn max_num_results(mut self, max: u32) -> Self {
+        self.max_num_results = Some(max);
+        self
+    }
+
+    pub fn filters(mut self, filters: serde_json::Value) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    pub fn ranking_options(mut self, options: serde_json::Value) -> Self {
+        self.ranking_options = Some(options);
+        self
+    }
+}
+
+// Code interpreter tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CodeInterpreterTool {
+    pub container: CodeInterpreterContainer,
+}
+
+impl CodeInterpreterTool {
+    pub fn new() -> Self {
+        Self {
+            container: CodeInterpreterContainer::auto(),
+        }
+    }
+
+    /// Run in an existing container instead of an automatic one
+    pub fn container_id(mut self, id: String) -> Self {
+        self.container = CodeInterpreterContainer::Id(id);
+        self
+    }
+
+    /// Files to upload into the automatic container
+    pub fn file_ids(mut self, file_ids: Vec<String>) -> Self {
+        self.container = CodeInterpreterContainer::Auto(AutoContainer {
+            container_type: "auto".to_string(),
+            file_ids: Some(file_ids),
+        });
+        self
+    }
+}
+
+impl Default for CodeInterpreterTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum CodeInterpreterContainer {
+    Id(String),          // Existing container ID
+    Auto(AutoContainer), // Container created for the request
+}
+
+impl CodeInterpreterContainer {
+    pub fn auto() -> Self {
+        CodeInterpreterContainer::Auto(AutoContainer {
+            container_type: "auto".to_string(),
+            file_ids: None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AutoContainer {
+    #[serde(rename = "type")]
+    pub container_type: String, // "auto"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_ids: Option<Vec<String>>,
+}
+
+// Image generation tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ImageGenerationTool {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>, // "1024x1024", "1024x1536", "1536x1024", "auto"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<ImageQuality>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<ImageOutputFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<ImageBackground>,
+}
+
+impl ImageGenerationTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn size(mut self, size: String) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn quality(mut self, quality: ImageQuality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn output_format(mut self, format: ImageOutputFormat) -> Self {
+        self.output_format = Some(format);
+        self
+    }
+
+    pub fn background(mut self, background: ImageBackground) -> Self {
+        self.background = Some(background);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageQuality {
+    Low,
+    Medium,
+    High,
+    Auto,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageBackground {
+    Transparent,
+    Opaque,
+    Auto,
+}
+
+// Computer use tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComputerUseTool {
+    pub display_width: u32,
+    pub display_height: u32,
+    pub environment: ComputerEnvironment,
+}
+
+impl ComputerUseTool {
+    pub fn new(display_width: u32, display_height: u32, environment: ComputerEnvironment) -> Self {
+        Self {
+            display_width,
+            display_height,
+            environment,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComputerEnvironment {
+    Browser,
+    Mac,
+    Windows,
+    Ubuntu,
+    Linux,
+}
+
+// Remote MCP server tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct McpTool {
+    pub server_label: String,
+    pub server_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<McpApproval>,
+}
+
+impl McpTool {
+    pub fn new(server_label: String, server_url: String) -> Self {
+        Self {
+            server_label,
+            server_url,
+            allowed_tools: None,
+            headers: None,
+            require_approval: None,
+        }
+    }
+
+    pub fn allowed_tools(mut self, tools: Vec<String>) -> Self {
+        self.allowed_tools = Some(tools);
+        self
+    }
+
+    pub fn header(mut self, name: String, value: String) -> Self {
+        self.headers
+            .get_or_insert_with(HashMap::new)
+            .insert(name, value);
+        self
+    }
+
+    pub fn require_approval(mut self, approval: McpApproval) -> Self {
+        self.require_approval = Some(approval);
+        self
+    }
+}
+
+// Approval policy for remote MCP tool calls
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum McpApproval {
+    Mode(McpApprovalMode),
+    Filter {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        always: Option<McpToolFilter>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        never: Option<McpToolFilter>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpApprovalMode {
+    Always,
+    Never,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct McpToolFilter {
+    pub tool_names: Vec<String>,
+}
+
+impl From<FunctionTool> for ToolDefinition {
+    fn from(tool: FunctionTool) -> Self {
+        ToolDefinition::Function(tool)
+    }
+}
+
+impl From<WebSearchTool> for ToolDefinition {
+    fn from(tool: WebSearchTool) -> Self {
+        ToolDefinition::WebSearch(tool)
+    }
+}
+
+impl From<FileSearchTool> for ToolDefinition {
+    fn from(tool: FileSearchTool) -> Self {
+        ToolDefinition::FileSearch(tool)
+    }
+}
+
+impl From<CodeInterpreterTool> for ToolDefinition {
+    fn from(tool: CodeInterpreterTool) -> Self {
+        ToolDefinition::CodeInterpreter(tool)
+    }
+}
+
+impl From<ImageGenerationTool> for ToolDefinition {
+    fn from(tool: ImageGenerationTool) -> Self {
+        ToolDefinition::ImageGeneration(tool)
+    }
+}
+
+impl From<ComputerUseTool> for ToolDefinition {
+    fn from(tool: ComputerUseTool) -> Self {
+        ToolDefinition::ComputerUse(tool)
+    }
+}
+
+impl From<McpTool> for ToolDefinition {
+    fn from(tool: McpTool) -> Self {
+        ToolDefinition::Mcp(tool)
+    }
+}
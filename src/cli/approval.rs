@@ -0,0 +1,33 @@
+use crate::api::models::McpApprovalRequest;
+use std::io::{self, Write};
+
+/// Ask on the terminal whether a remote MCP tool call may run
+pub fn prompt_mcp_approval(request: &McpApprovalRequest) -> bool {
+    println!("\n🔐 Approval required for MCP tool call:");
+    println!("  Server: {}", request.server_label);
+    println!("  Tool: {}", request.name);
+    println!("  Arguments: {}", format_arguments(&request.arguments));
+    print!("Allow this call? [y/N]: ");
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    is_approval(&answer)
+}
+
+/// Only an explicit yes approves; anything else, including an empty line, denies
+pub fn is_approval(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Pretty-print JSON-encoded arguments, falling back to the raw string
+pub fn format_arguments(arguments: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(arguments)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| arguments.to_string())
+}
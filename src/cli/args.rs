@@ -1,6 +1,19 @@
+use crate::api::models::{
+    IncludeField, PromptTemplate, PromptVariable, ReasoningEffort, ReasoningSummary,
+    ResponseRequest, ServiceTier, ToolChoice, Truncation,
+};
+use crate::api::tools::{McpTool, SearchContextSize, ToolDefinition, WebSearchTool};
 use anyhow::{anyhow, Result};
-use clap::Parser;
+use crate::config::file::{CacheConfig, HistoryConfig};
+use crate::config::metadata::parse_key_value;
+use crate::usage::ledger::DEFAULT_PROFILE;
+use crate::usage::report::{parse_day, GroupBy};
+use crate::utils::tokens::Encoding;
+use clap::{Parser, Subcommand};
+use std::env;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DisplayMode {
@@ -9,12 +22,72 @@ pub enum DisplayMode {
     Plain,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+/// How `--cache` uses the local response cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CacheMode {
+    /// Cache deterministic requests (temperature 0)
+    On,
+    /// Also cache sampled requests
+    Force,
+}
+
+/// What to do when a request is estimated not to fit the model's context window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ContextCheck {
+    #[default]
+    Warn,
+    Fail,
+    Off,
+}
+
+/// Minimum level of the diagnostic logs written by --log-level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
 /// OpenAI CLI tool for interacting with the Responses API
-#[derive(Parser)]
+#[derive(Parser, Default)]
 #[command(name = "openai-cli")]
 #[command(about = "A command-line tool to interact with OpenAI's Responses API")]
 #[command(version = "1.0")]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// The question or prompt to send to OpenAI
     #[arg(help = "The question or prompt to send to OpenAI")]
     pub prompt: Option<String>,
@@ -59,9 +132,10 @@ pub struct Args {
     #[arg(long)]
     pub background: bool,
 
-    /// Service tier (auto, default, flex, priority)
-    #[arg(long)]
-    pub service_tier: Option<String>,
+    /// Service tier: auto, default, flex, scale or priority; tiers newer
+    /// than this tool are passed through as given
+    #[arg(long, value_name = "TIER", value_parser = ServiceTier::from_str)]
+    pub service_tier: Option<ServiceTier>,
 
     /// User identifier for tracking
     #[arg(long)]
@@ -79,17 +153,277 @@ pub struct Args {
     #[arg(long)]
     pub top_logprobs: Option<u32>,
 
-    /// Truncation strategy (auto, disabled)
-    #[arg(long, default_value = "disabled")]
-    pub truncation: String,
+    /// Color output tokens by confidence and show their top alternatives
+    #[arg(long)]
+    pub show_logprobs: bool,
+
+    /// Truncation strategy: auto or disabled; strategies newer than this
+    /// tool are passed through as given
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        value_parser = Truncation::from_str,
+        default_value_t = Truncation::Disabled
+    )]
+    pub truncation: Truncation,
 
     /// Whether to store the response
     #[arg(long)]
     pub store: Option<bool>,
 
+    /// Automatically ask the model to continue answers cut off by max_output_tokens
+    #[arg(long)]
+    pub continue_on_truncation: bool,
+
     /// Whether to allow parallel tool calls
     #[arg(long)]
     pub parallel_tool_calls: Option<bool>,
+
+    /// Enable the hosted web search tool
+    #[arg(long)]
+    pub web_search: bool,
+
+    /// Amount of web search context to retrieve (low, medium, high)
+    #[arg(long, value_enum, requires = "web_search")]
+    pub search_context_size: Option<SearchContextSize>,
+
+    /// Enable file search over a vector store (repeatable)
+    #[arg(long = "file-search", value_name = "VECTOR_STORE_ID")]
+    pub file_search: Vec<String>,
+
+    /// Enable the hosted code interpreter tool
+    #[arg(long)]
+    pub code_interpreter: bool,
+
+    /// Enable the hosted image generation tool
+    #[arg(long)]
+    pub image_generation: bool,
+
+    /// Attach a remote MCP server as LABEL=URL (repeatable)
+    #[arg(long = "mcp", value_name = "LABEL=URL")]
+    pub mcp_servers: Vec<String>,
+
+    /// Reasoning effort for reasoning models (minimal, low, medium, high)
+    #[arg(long, value_enum)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+
+    /// Reasoning summary detail for reasoning models (auto, concise, detailed)
+    #[arg(long, value_enum)]
+    pub reasoning_summary: Option<ReasoningSummary>,
+
+    /// Expand the model's reasoning summaries in markdown output
+    #[arg(long)]
+    pub show_thinking: bool,
+
+    /// Additional output data to include, e.g. reasoning.encrypted_content (repeatable);
+    /// fields newer than this tool are passed through as given
+    #[arg(long, value_name = "FIELD", value_parser = IncludeField::from_str)]
+    pub include: Vec<IncludeField>,
+
+    /// Launch a local stdio MCP server from the config file and expose its
+    /// tools as functions (repeatable)
+    #[arg(long = "local-mcp", value_name = "NAME")]
+    pub local_mcp: Vec<String>,
+
+    /// Most follow-up requests to send while answering MCP approvals, and
+    /// again while answering local MCP tool calls, before giving up
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub max_turns: u32,
+
+    /// Tool choice: none, auto, required, function=NAME, mcp=LABEL, a hosted
+    /// tool name, or allowed_tools:MODE:REF[,REF...]
+    #[arg(long, value_name = "CHOICE")]
+    pub tool_choice: Option<ToolChoice>,
+
+    /// Metadata to attach to the response (repeatable); values may use
+    /// {hostname}, {git_branch} and {session}
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub metadata: Vec<(String, String)>,
+
+    /// Record this exchange in the local history, even if it is not enabled
+    /// in the config file
+    #[arg(long, conflicts_with = "no_history")]
+    pub history: bool,
+
+    /// Do not record this exchange in the local history
+    #[arg(long)]
+    pub no_history: bool,
+
+    /// ID of a reusable prompt stored with the API; the prompt argument
+    /// becomes optional extra input
+    #[arg(long, value_name = "ID")]
+    pub prompt_id: Option<String>,
+
+    /// Version of the stored prompt (latest when omitted)
+    #[arg(long, value_name = "VERSION", requires = "prompt_id")]
+    pub prompt_version: Option<String>,
+
+    /// Stored prompt variable (repeatable); VALUE may be image:URL|FILE_ID,
+    /// file:URL|FILE_ID or text
+    #[arg(
+        long = "var",
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value,
+        requires = "prompt_id"
+    )]
+    pub vars: Vec<(String, String)>,
+
+    /// Check the estimated input plus max_output_tokens against the model's
+    /// context window before sending (warn, fail, off)
+    #[arg(long, value_enum, default_value_t = ContextCheck::Warn)]
+    pub context_check: ContextCheck,
+
+    /// Refuse to send the request when its worst-case cost in USD exceeds this
+    #[arg(long, value_name = "USD")]
+    pub max_cost: Option<f64>,
+
+    /// Serve repeated requests from the local cache; --cache=force also
+    /// caches requests with a non-zero temperature
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "on"
+    )]
+    pub cache: Option<CacheMode>,
+
+    /// Bypass the local response cache even if the config file enables it
+    #[arg(long, conflicts_with = "cache")]
+    pub no_cache: bool,
+
+    /// Profile recorded with this call in the usage ledger (default:
+    /// $OPENAI_CLI_PROFILE, else "default")
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Write diagnostic logs at this level or above (default: $RUST_LOG, else off)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+
+    /// Format of the diagnostic logs
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "text")]
+    pub log_format: LogFormat,
+
+    /// Append diagnostic logs to this file instead of stderr
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Include request and response bodies in debug logs
+    #[arg(long)]
+    pub log_bodies: bool,
+
+    /// Add OpenTelemetry GenAI attributes (gen_ai.*) to request spans
+    #[arg(long)]
+    pub log_genai: bool,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// List saved exchanges from the local history
+    History(HistoryArgs),
+    /// Browse and render prompts from the local prompt library
+    Prompts(PromptsArgs),
+    /// Count the tokens of text read from stdin, offline
+    Tokens(TokensArgs),
+    /// Report spending recorded in the local usage ledger
+    Usage(UsageArgs),
+    /// Inspect or empty the local response cache
+    Cache(CacheArgs),
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum CacheCommand {
+    /// Show cached entries, size and hit rate
+    Stats,
+    /// Delete every cached response
+    Clear,
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct UsageArgs {
+    #[command(subcommand)]
+    pub command: UsageCommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum UsageCommand {
+    /// Summarize recorded calls, tokens and cost
+    Report(ReportArgs),
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct ReportArgs {
+    /// Group by day, month, model, profile or metadata:KEY
+    #[arg(long, default_value_t = GroupBy::Day)]
+    pub by: GroupBy,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+    pub format: ReportFormat,
+
+    /// First UTC day to include (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_day)]
+    pub since: Option<String>,
+
+    /// Last UTC day to include (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_day)]
+    pub until: Option<String>,
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct TokensArgs {
+    /// Model whose encoding and context window to use
+    #[arg(short, long, default_value = "gpt-4o-mini")]
+    pub model: String,
+
+    /// Count with this encoding instead of the model's
+    #[arg(long, value_enum)]
+    pub encoding: Option<Encoding>,
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct HistoryArgs {
+    /// Only show entries whose metadata has this value (repeatable, all must match)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub metadata: Vec<(String, String)>,
+
+    /// Number of most recent entries to show
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output entries as JSON lines
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug, Clone, PartialEq)]
+pub struct PromptsArgs {
+    #[command(subcommand)]
+    pub command: PromptsCommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum PromptsCommand {
+    /// List the prompts in the library
+    List,
+    /// Show a prompt's variables and template
+    Show { name: String },
+    /// Print a prompt with its variables filled in
+    Render {
+        name: String,
+
+        /// Variable value (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        vars: Vec<(String, String)>,
+    },
 }
 
 impl Args {
@@ -115,51 +449,76 @@ impl Args {
         Ok(trimmed.to_string())
     }
 
+    // Range checks live in the request builder so library users get
+    // them too; each validator checks its own flag in isolation
     pub fn validate_temperature(&self) -> Result<()> {
         if let Some(temp) = self.temperature {
-            if temp < 0.0 || temp > 2.0 {
-                return Err(anyhow!("Temperature must be between 0.0 and 2.0"));
-            }
+            ResponseRequest::builder().temperature(temp).try_build()?;
         }
         Ok(())
     }
 
     pub fn validate_top_p(&self) -> Result<()> {
         if let Some(top_p) = self.top_p {
-            if top_p < 0.0 || top_p > 1.0 {
-                return Err(anyhow!("Top-p must be between 0.0 and 1.0"));
-            }
+            ResponseRequest::builder().top_p(top_p).try_build()?;
         }
         Ok(())
     }
 
     pub fn validate_top_logprobs(&self) -> Result<()> {
         if let Some(logprobs) = self.top_logprobs {
-            if logprobs > 20 {
-                return Err(anyhow!("Top logprobs must be between 0 and 20"));
-            }
+            ResponseRequest::builder().top_logprobs(logprobs).try_build()?;
         }
         Ok(())
     }
 
-    pub fn validate_service_tier(&self) -> Result<()> {
-        if let Some(tier) = &self.service_tier {
-            match tier.as_str() {
-                "auto" | "default" | "flex" | "priority" => Ok(()),
-                _ => Err(anyhow!(
-                    "Service tier must be one of: auto, default, flex, priority"
-                )),
-            }
-        } else {
-            Ok(())
+    pub fn validate_max_output_tokens(&self) -> Result<()> {
+        if let Some(tokens) = self.max_output_tokens {
+            ResponseRequest::builder().max_output_tokens(tokens).try_build()?;
+        }
+        Ok(())
+    }
+
+    pub fn validate_metadata(&self) -> Result<()> {
+        if !self.metadata.is_empty() {
+            let metadata: serde_json::Map<String, serde_json::Value> = self
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone().into()))
+                .collect();
+            ResponseRequest::builder()
+                .metadata(metadata.into())
+                .try_build()?;
+        }
+        Ok(())
+    }
+
+    pub fn validate_background(&self) -> Result<()> {
+        if let Some(store) = self.store {
+            ResponseRequest::builder()
+                .background(self.background)
+                .store(store)
+                .try_build()?;
+        }
+        Ok(())
+    }
+
+    pub fn validate_continuation(&self) -> Result<()> {
+        if self.continue_on_truncation && self.store == Some(false) {
+            return Err(anyhow!(
+                "--continue-on-truncation chains on the stored response and cannot be used with --store false"
+            ));
         }
+        Ok(())
     }
 
-    pub fn validate_truncation(&self) -> Result<()> {
-        match self.truncation.as_str() {
-            "auto" | "disabled" => Ok(()),
-            _ => Err(anyhow!("Truncation must be either 'auto' or 'disabled'")),
+    pub fn validate_max_cost(&self) -> Result<()> {
+        if let Some(limit) = self.max_cost {
+            if !limit.is_finite() || limit <= 0.0 {
+                return Err(anyhow!("--max-cost must be a positive amount, got {}", limit));
+            }
         }
+        Ok(())
     }
 
     pub fn validate_output_format(&self) -> Result<()> {
@@ -180,13 +539,118 @@ impl Args {
         }
     }
 
+    pub fn validate_tools(&self) -> Result<()> {
+        for server in &self.mcp_servers {
+            Self::parse_mcp_server(server)?;
+        }
+        Ok(())
+    }
+
+    pub fn validate_tool_choice(&self) -> Result<()> {
+        if let Some(choice) = &self.tool_choice {
+            ResponseRequest::builder()
+                .tools(self.get_tools()?)
+                .tool_choice(choice.clone())
+                .try_build()?;
+        }
+        Ok(())
+    }
+
+    fn parse_mcp_server(value: &str) -> Result<McpTool> {
+        match value.split_once('=') {
+            Some((label, url)) if !label.is_empty() && !url.is_empty() => {
+                Ok(McpTool::new(label.to_string(), url.to_string()))
+            }
+            _ => Err(anyhow!(
+                "MCP server must be given as LABEL=URL, got '{}'",
+                value
+            )),
+        }
+    }
+
+    /// Collect the tool definitions enabled by the tool flags
+    pub fn get_tools(&self) -> Result<Vec<ToolDefinition>> {
+        let mut tools = Vec::new();
+
+        if self.web_search {
+            let mut web_search = WebSearchTool::new();
+            if let Some(size) = self.search_context_size {
+                web_search = web_search.search_context_size(size);
+            }
+            tools.push(web_search.into());
+        }
+        if !self.file_search.is_empty() {
+            tools.push(ToolDefinition::file_search(self.file_search.clone()));
+        }
+        if self.code_interpreter {
+            tools.push(ToolDefinition::code_interpreter());
+        }
+        if self.image_generation {
+            tools.push(ToolDefinition::image_generation());
+        }
+        for server in &self.mcp_servers {
+            tools.push(Self::parse_mcp_server(server)?.into());
+        }
+
+        Ok(tools)
+    }
+
+    /// The config file's cache settings with --cache and --no-cache applied
+    pub fn resolve_cache(&self, config: &CacheConfig) -> CacheConfig {
+        let mut cache = config.clone();
+        if self.no_cache {
+            cache.enabled = false;
+        }
+        if let Some(mode) = self.cache {
+            cache.enabled = true;
+            cache.force |= mode == CacheMode::Force;
+        }
+        cache
+    }
+
+    /// Whether to save this exchange: off unless the config file or
+    /// --history turns it on, and never with --no-history
+    pub fn saves_history(&self, config: &HistoryConfig) -> bool {
+        (config.enabled || self.history) && !self.no_history
+    }
+
+    /// Profile to record in the usage ledger
+    pub fn get_profile(&self) -> String {
+        self.profile
+            .clone()
+            .or_else(|| env::var("OPENAI_CLI_PROFILE").ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// The stored prompt reference built from --prompt-id, --prompt-version and --var
+    pub fn get_prompt_template(&self) -> Option<PromptTemplate> {
+        let id = self.prompt_id.clone()?;
+        let mut template = PromptTemplate::new(id);
+        if let Some(version) = &self.prompt_version {
+            template = template.version(version.clone());
+        }
+        for (name, value) in &self.vars {
+            let Ok(value) = value.parse::<PromptVariable>();
+            template = template.variable(name.clone(), value);
+        }
+        Some(template)
+    }
+
     pub fn validate_all(&self) -> Result<()> {
         self.validate_temperature()?;
         self.validate_top_p()?;
         self.validate_top_logprobs()?;
-        self.validate_service_tier()?;
-        self.validate_truncation()?;
+        self.validate_max_output_tokens()?;
+        self.validate_background()?;
+        self.validate_metadata()?;
+        self.validate_max_cost()?;
         self.validate_output_format()?;
+        self.validate_continuation()?;
+        self.validate_tools()?;
+        // Local MCP tools are only known once their servers start
+        if self.local_mcp.is_empty() {
+            self.validate_tool_choice()?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,172 @@
+use crate::api::errors::{OpenAIError, TransportErrorKind, ValidationError};
+use crate::api::models::{IncompleteReason, ResponseApiResponse};
+use crate::mcp::client::McpError;
+
+// Process exit codes; 2 is left to clap for usage errors
+pub const SUCCESS: i32 = 0;
+pub const ERROR: i32 = 1;
+pub const TRUNCATED: i32 = 3;
+pub const INCOMPLETE: i32 = 4;
+pub const REFUSED: i32 = 5;
+
+// API failures, one code per error class
+pub const AUTHENTICATION: i32 = 10;
+pub const PERMISSION_DENIED: i32 = 11;
+pub const NOT_FOUND: i32 = 12;
+pub const RATE_LIMITED: i32 = 13;
+pub const QUOTA_EXCEEDED: i32 = 14;
+pub const CONTEXT_LENGTH_EXCEEDED: i32 = 15;
+pub const INVALID_REQUEST: i32 = 16;
+pub const CONTENT_FILTER: i32 = 17;
+pub const SERVER_ERROR: i32 = 18;
+pub const TIMEOUT: i32 = 19;
+pub const NETWORK: i32 = 20;
+
+// Refused locally by --max-cost or a configured budget
+pub const SPENDING_LIMIT: i32 = 21;
+
+/// Exit code for a response the API returned successfully
+pub fn for_response(response: &ResponseApiResponse) -> i32 {
+    if response.is_refusal() {
+        REFUSED
+    } else if response.is_truncated() {
+        TRUNCATED
+    } else if response.is_incomplete() {
+        INCOMPLETE
+    } else {
+        SUCCESS
+    }
+}
+
+/// Warning to show when the answer is refused, truncated or otherwise incomplete
+pub fn completion_notice(response: &ResponseApiResponse) -> Option<String> {
+    if response.is_refusal() {
+        return Some("🚫 The model refused to answer this request".to_string());
+    }
+    if !response.is_incomplete() {
+        return None;
+    }
+
+    Some(match response.incomplete_reason() {
+        Some(IncompleteReason::MaxOutputTokens) => {
+            "⚠️ Response truncated: reached max_output_tokens \
+             (raise --max-output-tokens or use --continue-on-truncation)"
+                .to_string()
+        }
+        Some(IncompleteReason::ContentFilter) => {
+            "⚠️ Response incomplete: stopped by the content filter".to_string()
+        }
+        _ => "⚠️ Response incomplete".to_string(),
+    })
+}
+
+/// The API error behind `error`, including one raised while running local MCP tools
+pub fn api_error(error: &anyhow::Error) -> Option<&OpenAIError> {
+    error
+        .downcast_ref::<OpenAIError>()
+        .or_else(|| match error.downcast_ref::<McpError>() {
+            Some(McpError::Api(inner)) => Some(inner),
+            _ => None,
+        })
+}
+
+/// Exit code for a failed run
+pub fn for_error(error: &anyhow::Error) -> i32 {
+    match api_error(error) {
+        Some(OpenAIError::Authentication(_)) => AUTHENTICATION,
+        Some(OpenAIError::PermissionDenied(_)) => PERMISSION_DENIED,
+        Some(OpenAIError::NotFound(_)) => NOT_FOUND,
+        Some(OpenAIError::RateLimited { .. }) => RATE_LIMITED,
+        Some(OpenAIError::QuotaExceeded(_)) => QUOTA_EXCEEDED,
+        Some(OpenAIError::ContextLengthExceeded(_)) => CONTEXT_LENGTH_EXCEEDED,
+        Some(
+            OpenAIError::InvalidRequest(_)
+            | OpenAIError::InvalidToolChoice(_)
+            | OpenAIError::Validation(_),
+        ) => INVALID_REQUEST,
+        None => match error.downcast_ref::<ValidationError>() {
+            Some(ValidationError::ContextWindowExceeded { .. }) => CONTEXT_LENGTH_EXCEEDED,
+            Some(
+                ValidationError::CostLimitExceeded { .. } | ValidationError::BudgetExceeded { .. },
+            ) => SPENDING_LIMIT,
+            Some(_) => INVALID_REQUEST,
+            None => ERROR,
+        },
+        Some(OpenAIError::ContentFilter(_)) => CONTENT_FILTER,
+        Some(OpenAIError::ServerError(_)) => SERVER_ERROR,
+        Some(OpenAIError::Transport(e)) if e.kind() == TransportErrorKind::Timeout => TIMEOUT,
+        Some(OpenAIError::Transport(_)) => NETWORK,
+        _ => ERROR,
+    }
+}
+
+/// What the user can do about a failed run, if there is a hint for it
+pub fn error_hint(error: &anyhow::Error) -> Option<String> {
+    match api_error(error) {
+        Some(error) => hint(error),
+        None => validation_hint(error.downcast_ref::<ValidationError>()?),
+    }
+}
+
+/// What the user can do about a request refused before it was sent, in
+/// terms of the CLI's flags
+pub fn validation_hint(error: &ValidationError) -> Option<String> {
+    let hint = match error {
+        ValidationError::CostLimitExceeded { .. } => {
+            "Raise --max-cost, or lower --max-output-tokens to shrink the worst case"
+        }
+        ValidationError::UnboundedCost(_) => "Set --max-output-tokens so --max-cost can bound it",
+        _ => return None,
+    };
+    Some(hint.to_string())
+}
+
+/// What the user can do about an API error
+pub fn hint(error: &OpenAIError) -> Option<String> {
+    let hint = match error {
+        OpenAIError::Authentication(_) => {
+            "Check that OPENAI_API_KEY is set to a valid, active API key".to_string()
+        }
+        OpenAIError::PermissionDenied(_) => {
+            "Your key or project does not have access to this model or resource".to_string()
+        }
+        OpenAIError::NotFound(_) => {
+            "Check the model name and any response ID passed with --previous-response-id"
+                .to_string()
+        }
+        OpenAIError::RateLimited { retry_after, .. } => match retry_after {
+            Some(delay) => format!("Wait {:.1}s and try again", delay.as_secs_f64()),
+            None => "Wait a moment and try again, or lower your request rate".to_string(),
+        },
+        OpenAIError::QuotaExceeded(_) => {
+            "Check your plan and billing details at https://platform.openai.com/account/billing"
+                .to_string()
+        }
+        OpenAIError::ContextLengthExceeded(_) => {
+            "Shorten the input or --max-output-tokens, or pass --truncation auto".to_string()
+        }
+        OpenAIError::InvalidRequest(context) => match &context.param {
+            Some(param) => format!("Check the value given for '{}'", param),
+            None => return None,
+        },
+        OpenAIError::ContentFilter(_) => {
+            "The request was blocked by the content filter; rephrase it and try again".to_string()
+        }
+        OpenAIError::ServerError(_) => {
+            "OpenAI had a problem handling the request; try again shortly".to_string()
+        }
+        OpenAIError::Transport(e) if e.kind() == TransportErrorKind::Timeout => {
+            "The request timed out; try again, or use --background for long tasks".to_string()
+        }
+        OpenAIError::Transport(_) => {
+            "Could not reach the API; check your network connection".to_string()
+        }
+        OpenAIError::Validation(error) => return validation_hint(error),
+        OpenAIError::TooManyTurns(_) => {
+            "The model kept asking for tools; raise --max-turns to allow more follow-ups"
+                .to_string()
+        }
+        _ => return None,
+    };
+    Some(hint)
+}
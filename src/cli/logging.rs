@@ -0,0 +1,42 @@
+use crate::cli::args::{Args, LogFormat};
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io;
+use std::sync::Mutex;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::EnvFilter;
+
+/// Filter for `--log-level`, or `RUST_LOG` when it is not given. Only this
+/// crate's logs are enabled by a level, not those of its HTTP dependencies.
+pub fn filter(args: &Args) -> EnvFilter {
+    match args.log_level {
+        Some(level) => EnvFilter::new(format!("openai_api={}", level.as_str())),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off")),
+    }
+}
+
+/// Send diagnostic logs to stderr or `--log-file`, as text or JSON lines
+pub fn init(args: &Args) -> Result<()> {
+    let writer = match &args.log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            BoxMakeWriter::new(Mutex::new(file))
+        }
+        None => BoxMakeWriter::new(io::stderr),
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter(args))
+        .with_writer(writer)
+        .with_ansi(args.log_file.is_none());
+    // Only fails when a subscriber is already set, which is fine to keep
+    let _ = match args.log_format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).try_init(),
+    };
+    Ok(())
+}
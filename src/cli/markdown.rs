@@ -1,11 +1,16 @@
 use crate::api::models::ResponseApiResponse;
-use crate::utils::content::extract_response_content;
+use crate::api::pricing::format_usd;
+use crate::utils::citations::{
+    extract_cited_content, format_sources, supports_hyperlinks, Source,
+};
+use crate::utils::logprobs::{collect_logprobs, perplexity};
 use anyhow::Result;
 use termimad::MadSkin;
 use crossterm::style::Color;
 
 pub struct MarkdownRenderer {
     skin: MadSkin,
+    show_thinking: bool,
 }
 
 impl MarkdownRenderer {
@@ -20,7 +25,16 @@ impl MarkdownRenderer {
         skin.inline_code.set_fg(Color::Green);
         skin.code_block.set_fg(Color::Green);
         
-        Self { skin }
+        Self {
+            skin,
+            show_thinking: false,
+        }
+    }
+
+    /// Expand the "Thinking" section instead of showing it collapsed
+    pub fn show_thinking(mut self, expanded: bool) -> Self {
+        self.show_thinking = expanded;
+        self
     }
 
     pub fn render_response(&self, response: &ResponseApiResponse, verbose: bool) -> Result<()> {
@@ -28,9 +42,13 @@ impl MarkdownRenderer {
             return Err(anyhow::anyhow!("No output messages returned from OpenAI"));
         }
 
+        if let Some(thinking) = self.format_thinking(response) {
+            println!("{}", self.skin.term_text(&thinking));
+        }
+
         println!("\n📝 Response:");
-        let content = extract_response_content(response);
-        
+        let (content, sources) = extract_cited_content(response);
+
         // Check if content contains markdown-like patterns
         if self.looks_like_markdown(&content) {
             // Render as markdown
@@ -40,6 +58,10 @@ impl MarkdownRenderer {
             self.print_as_text(&content)?;
         }
 
+        if !sources.is_empty() {
+            self.print_sources(&sources);
+        }
+
         if verbose {
             self.render_verbose_info(response)?;
         }
@@ -73,6 +95,45 @@ impl MarkdownRenderer {
         Ok(())
     }
 
+    fn print_sources(&self, sources: &[Source]) {
+        // Printed outside termimad so hyperlink escapes reach the terminal intact
+        println!("{}", self.skin.term_text("### Sources"));
+        for line in format_sources(sources, supports_hyperlinks()) {
+            println!("{}", line);
+        }
+    }
+
+    /// Markdown for the reasoning summaries: a single header line when
+    /// collapsed, the full summaries as a quote when expanded
+    pub fn format_thinking(&self, response: &ResponseApiResponse) -> Option<String> {
+        let summaries: Vec<&str> = response
+            .reasoning_items()
+            .flat_map(|item| item.summary_texts())
+            .collect();
+        if summaries.is_empty() {
+            return None;
+        }
+
+        if !self.show_thinking {
+            return Some(format!(
+                "\n▶ **Thinking** ({} summary part{}, use `--show-thinking` to expand)\n",
+                summaries.len(),
+                if summaries.len() == 1 { "" } else { "s" }
+            ));
+        }
+
+        let mut thinking = String::from("\n▼ **Thinking**\n\n");
+        for (index, summary) in summaries.iter().enumerate() {
+            if index > 0 {
+                thinking.push_str(">\n");
+            }
+            for line in summary.lines() {
+                thinking.push_str(&format!("> {}\n", line));
+            }
+        }
+        Some(thinking)
+    }
+
     fn render_verbose_info(&self, response: &ResponseApiResponse) -> Result<()> {
         let verbose_content = self.format_verbose_info(response);
         let rendered = self.skin.term_text(&verbose_content);
@@ -87,23 +148,50 @@ impl MarkdownRenderer {
         info.push_str(&format!("- **Response ID**: `{}`\n", response.id));
         info.push_str(&format!("- **Model**: `{}`\n", response.model));
         info.push_str(&format!("- **Created At**: `{}`\n", response.created_at));
-        info.push_str(&format!("- **Status**: `{}`\n", response.status));
+        if let Some(status) = &response.status {
+            info.push_str(&format!("- **Status**: `{}`\n", status));
+        }
         info.push_str(&format!("- **Object**: `{}`\n", response.object));
+        if response.cached {
+            info.push_str("- **Cached**: yes (served locally, no API call)\n");
+        }
 
-        if !response.output.is_empty() {
-            let first_output = &response.output[0];
+        if let Some(first_output) = response.messages().next() {
             info.push_str(&format!("- **Message ID**: `{}`\n", first_output.id));
             info.push_str(&format!("- **Message Status**: `{}`\n", first_output.status));
             info.push_str(&format!("- **Role**: `{}`\n", first_output.role));
         }
 
+        let mcp_calls: Vec<_> = response.mcp_calls().collect();
+        if !mcp_calls.is_empty() {
+            info.push_str("\n## 📊 MCP Calls\n\n");
+            for call in mcp_calls {
+                info.push_str(&format!("- `{}.{}({})`\n", call.server_label, call.name, call.arguments));
+                if let Some(error) = &call.error {
+                    info.push_str(&format!("  - **Error**: {}\n", error));
+                }
+            }
+        }
+
         info.push_str("\n## 📊 Configuration\n\n");
-        info.push_str(&format!("- **Temperature**: `{}`\n", response.temperature));
-        info.push_str(&format!("- **Top P**: `{}`\n", response.top_p));
-        info.push_str(&format!("- **Tool Choice**: `{}`\n", response.tool_choice));
-        info.push_str(&format!("- **Truncation**: `{}`\n", response.truncation));
-        info.push_str(&format!("- **Store**: `{}`\n", response.store));
-        info.push_str(&format!("- **Parallel Tool Calls**: `{}`\n", response.parallel_tool_calls));
+        if let Some(temperature) = &response.temperature {
+            info.push_str(&format!("- **Temperature**: `{}`\n", temperature));
+        }
+        if let Some(top_p) = &response.top_p {
+            info.push_str(&format!("- **Top P**: `{}`\n", top_p));
+        }
+        if let Some(tool_choice) = &response.tool_choice {
+            info.push_str(&format!("- **Tool Choice**: `{}`\n", tool_choice));
+        }
+        if let Some(truncation) = &response.truncation {
+            info.push_str(&format!("- **Truncation**: `{}`\n", truncation));
+        }
+        if let Some(store) = &response.store {
+            info.push_str(&format!("- **Store**: `{}`\n", store));
+        }
+        if let Some(parallel_tool_calls) = &response.parallel_tool_calls {
+            info.push_str(&format!("- **Parallel Tool Calls**: `{}`\n", parallel_tool_calls));
+        }
 
         info.push_str("\n## 📊 Token Usage\n\n");
         info.push_str(&format!("- **Input tokens**: `{}`\n", response.usage.input_tokens));
@@ -122,6 +210,23 @@ impl MarkdownRenderer {
             }
         }
 
+        if let Some(perplexity) = perplexity(&collect_logprobs(response)) {
+            info.push_str(&format!("- **Perplexity**: `{:.3}`\n", perplexity));
+        }
+
+        if let Some(cost) = &response.cost {
+            info.push_str("\n## 💰 Estimated Cost\n\n");
+            info.push_str(&format!("- **Input**: `{}`\n", format_usd(cost.input)));
+            if cost.cached_input > 0.0 {
+                info.push_str(&format!("- **Cached input**: `{}`\n", format_usd(cost.cached_input)));
+            }
+            info.push_str(&format!("- **Output**: `{}`\n", format_usd(cost.output)));
+            if cost.reasoning > 0.0 {
+                info.push_str(&format!("- **Reasoning**: `{}`\n", format_usd(cost.reasoning)));
+            }
+            info.push_str(&format!("- **Total**: `{}`\n", format_usd(cost.total)));
+        }
+
         if let Some(reasoning) = &response.reasoning {
             info.push_str("\n## 📊 Reasoning\n\n");
             if let Some(effort) = &reasoning.effort {
@@ -1,3 +1,6 @@
+pub mod approval;
 pub mod args;
+pub mod exit_codes;
+pub mod logging;
 pub mod markdown;
 pub mod output;
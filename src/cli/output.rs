@@ -1,7 +1,18 @@
-use crate::api::models::ResponseApiResponse;
+use crate::api::cache::CacheStats;
+use crate::api::models::{ResponseApiResponse, TokenLogprob};
+use crate::api::pricing::format_usd;
+use crate::cli::args::ReportFormat;
 use crate::cli::markdown::MarkdownRenderer;
-use crate::utils::content::extract_response_content;
+use crate::history::store::HistoryEntry;
+use crate::prompts::library::LocalPrompt;
+use crate::usage::report::{self, GroupBy, ReportRow};
+use crate::utils::citations::{extract_cited_content, format_sources, supports_hyperlinks};
+use crate::utils::logprobs::{collect_logprobs, perplexity, Confidence};
+use crate::utils::tokens::{context_window, Encoding};
 use anyhow::Result;
+use crossterm::style::Stylize;
+
+const HISTORY_PREVIEW_CHARS: usize = 80;
 
 pub struct OutputFormatter;
 
@@ -12,9 +23,16 @@ impl OutputFormatter {
         }
 
         println!("\n📝 Response:");
-        let content = extract_response_content(response);
+        let (content, sources) = extract_cited_content(response);
         println!("{}", content);
 
+        if !sources.is_empty() {
+            println!("\nSources:");
+            for line in format_sources(&sources, supports_hyperlinks()) {
+                println!("  {}", line);
+            }
+        }
+
         if verbose {
             Self::format_verbose_info(response);
         }
@@ -27,33 +45,355 @@ impl OutputFormatter {
         Ok(())
     }
 
-    pub fn format_markdown_response(response: &ResponseApiResponse, verbose: bool) -> Result<()> {
-        let renderer = MarkdownRenderer::new();
+    /// Print saved history entries, or one JSON object per line with `json`
+    pub fn format_history(entries: &[HistoryEntry], json: bool) -> Result<()> {
+        if json {
+            for entry in entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+            return Ok(());
+        }
+
+        if entries.is_empty() {
+            println!("🕘 No matching history entries");
+            return Ok(());
+        }
+
+        for entry in entries {
+            println!("\n🕘 {} ({})", entry.id, entry.model);
+            if !entry.metadata.is_empty() {
+                let pairs: Vec<String> = entry
+                    .metadata
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                println!("  Metadata: {}", pairs.join(", "));
+            }
+            println!(
+                "  Prompt: {}",
+                Self::preview(&entry.prompt, HISTORY_PREVIEW_CHARS)
+            );
+            println!(
+                "  Response: {}",
+                Self::preview(&entry.response, HISTORY_PREVIEW_CHARS)
+            );
+        }
+        Ok(())
+    }
+
+    /// Print one line per local prompt
+    pub fn format_prompt_list(prompts: &[LocalPrompt]) {
+        if prompts.is_empty() {
+            println!("📚 No prompts in the library");
+            return;
+        }
+
+        for prompt in prompts {
+            let mut line = format!("📚 {}", prompt.name);
+            if let Some(version) = &prompt.meta.version {
+                line.push_str(&format!(" (v{})", version));
+            }
+            if let Some(description) = &prompt.meta.description {
+                line.push_str(&format!(" - {}", description));
+            }
+            println!("{}", line);
+        }
+    }
+
+    /// Print a prompt's details, variables and template
+    pub fn format_prompt(prompt: &LocalPrompt) {
+        println!("📚 {}", prompt.name);
+        if let Some(version) = &prompt.meta.version {
+            println!("  Version: {}", version);
+        }
+        if let Some(description) = &prompt.meta.description {
+            println!("  Description: {}", description);
+        }
+
+        let placeholders = prompt.placeholders();
+        let mut names: Vec<&String> = placeholders.iter().collect();
+        names.extend(
+            prompt
+                .meta
+                .variables
+                .keys()
+                .filter(|name| !placeholders.contains(name)),
+        );
+        if !names.is_empty() {
+            println!("  Variables:");
+        }
+        for name in names {
+            let spec = prompt.meta.variables.get(name);
+            let mut line = format!("    {}", name);
+            match spec.and_then(|spec| spec.default.as_ref()) {
+                Some(default) => line.push_str(&format!(" (default: {})", default)),
+                None => line.push_str(" (required)"),
+            }
+            if let Some(description) = spec.and_then(|spec| spec.description.as_ref()) {
+                line.push_str(&format!(" - {}", description));
+            }
+            println!("{}", line);
+        }
+
+        println!("\n{}", prompt.template);
+    }
+
+    /// Print usage report rows as an aligned table with a total, CSV or JSON
+    pub fn format_usage_report(
+        rows: &[ReportRow],
+        group_by: &GroupBy,
+        format: ReportFormat,
+    ) -> Result<()> {
+        match format {
+            ReportFormat::Json => println!("{}", serde_json::to_string_pretty(rows)?),
+            ReportFormat::Csv => {
+                println!(
+                    "{},calls,input_tokens,cached_tokens,output_tokens,cost",
+                    Self::csv_field(&group_by.to_string())
+                );
+                for row in rows {
+                    println!(
+                        "{},{},{},{},{},{:.6}",
+                        Self::csv_field(&row.key),
+                        row.calls,
+                        row.input_tokens,
+                        row.cached_tokens,
+                        row.output_tokens,
+                        row.cost
+                    );
+                }
+            }
+            ReportFormat::Table => {
+                if rows.is_empty() {
+                    println!("💰 No recorded usage");
+                    return Ok(());
+                }
+
+                let total = report::total(rows);
+                let header = [
+                    group_by.to_string().to_uppercase(),
+                    "CALLS".to_string(),
+                    "INPUT".to_string(),
+                    "CACHED".to_string(),
+                    "OUTPUT".to_string(),
+                    "COST".to_string(),
+                ];
+                let lines: Vec<[String; 6]> = std::iter::once(header)
+                    .chain(rows.iter().chain([&total]).map(|row| {
+                        [
+                            row.key.clone(),
+                            row.calls.to_string(),
+                            row.input_tokens.to_string(),
+                            row.cached_tokens.to_string(),
+                            row.output_tokens.to_string(),
+                            format_usd(row.cost),
+                        ]
+                    }))
+                    .collect();
+
+                let mut widths = [0; 6];
+                for line in &lines {
+                    for (width, cell) in widths.iter_mut().zip(line) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+                for line in &lines {
+                    let mut text = format!("{:<width$}", line[0], width = widths[0]);
+                    for (cell, width) in line.iter().zip(widths).skip(1) {
+                        text.push_str(&format!("  {:>width$}", cell, width = width));
+                    }
+                    println!("{}", text);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Quote a CSV field when it contains a separator, quote or newline
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Print the response cache's size and hit rate
+    pub fn format_cache_stats(stats: &CacheStats, dir: &std::path::Path) {
+        println!("🗄️ Response cache: {}", dir.display());
+        println!("  Entries: {} ({} expired)", stats.entries, stats.expired);
+        println!("  Size: {:.1} KiB", stats.bytes as f64 / 1024.0);
+        let lookups = stats.hits + stats.misses;
+        if lookups > 0 {
+            println!(
+                "  Hits: {} of {} lookups ({:.0}%)",
+                stats.hits,
+                lookups,
+                stats.hits as f64 * 100.0 / lookups as f64
+            );
+        } else {
+            println!("  Hits: no lookups yet");
+        }
+    }
+
+    /// Print a token count and, for known models, its share of the context window
+    pub fn format_token_count(tokens: usize, encoding: Encoding, model: &str) {
+        println!("🔢 {} tokens ({})", tokens, encoding.name());
+        if let Some(window) = context_window(model) {
+            println!(
+                "   {:.1}% of the {}-token context window of {}",
+                tokens as f64 * 100.0 / window as f64,
+                window,
+                model
+            );
+        }
+    }
+
+    // First line of `text`, cut to `max_chars`
+    fn preview(text: &str, max_chars: usize) -> String {
+        let line = text.lines().next().unwrap_or_default();
+        if line.chars().count() > max_chars || text.lines().nth(1).is_some() {
+            let cut: String = line.chars().take(max_chars).collect();
+            format!("{}…", cut)
+        } else {
+            line.to_string()
+        }
+    }
+
+    pub fn format_markdown_response(
+        response: &ResponseApiResponse,
+        verbose: bool,
+        show_thinking: bool,
+    ) -> Result<()> {
+        let renderer = MarkdownRenderer::new().show_thinking(show_thinking);
         renderer.render_response(response, verbose)
     }
 
+    /// Print the output tokens colored by confidence, followed by a table of
+    /// each token's probability and its top alternatives
+    pub fn format_logprobs(response: &ResponseApiResponse) -> Result<()> {
+        let tokens = collect_logprobs(response);
+        if tokens.is_empty() {
+            println!("\n🎯 No log probabilities returned");
+            return Ok(());
+        }
+
+        println!("\n🎯 Token Confidence (green ≥ 90%, yellow ≥ 50%, red < 50%):");
+        println!("{}", Self::colorize_tokens(&tokens));
+        println!("\n{}", Self::format_alternatives_table(&tokens));
+        Ok(())
+    }
+
+    pub fn colorize_tokens(tokens: &[&TokenLogprob]) -> String {
+        tokens
+            .iter()
+            .map(|token| {
+                let styled = token.token.as_str().stylize();
+                match Confidence::from_probability(token.probability()) {
+                    Confidence::High => styled.green(),
+                    Confidence::Medium => styled.yellow(),
+                    Confidence::Low => styled.red(),
+                }
+                .to_string()
+            })
+            .collect()
+    }
+
+    pub fn format_alternatives_table(tokens: &[&TokenLogprob]) -> String {
+        let rows: Vec<(String, String, String)> = tokens
+            .iter()
+            .map(|token| {
+                let alternatives = token
+                    .top_logprobs
+                    .iter()
+                    .filter(|alternative| alternative.token != token.token)
+                    .map(|alternative| {
+                        format!(
+                            "{:?} {:.1}%",
+                            alternative.token,
+                            alternative.probability() * 100.0
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (
+                    format!("{:?}", token.token),
+                    format!("{:.1}%", token.probability() * 100.0),
+                    alternatives,
+                )
+            })
+            .collect();
+
+        let token_width = rows
+            .iter()
+            .map(|(token, _, _)| token.chars().count())
+            .chain(std::iter::once("Token".len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut table = format!("{:<token_width$}  {:>6}  Alternatives\n", "Token", "Prob");
+        for (token, probability, alternatives) in rows {
+            table.push_str(
+                format!(
+                    "{:<token_width$}  {:>6}  {}",
+                    token, probability, alternatives
+                )
+                .trim_end(),
+            );
+            table.push('\n');
+        }
+        table
+    }
+
     fn format_verbose_info(response: &ResponseApiResponse) {
         println!("\n📊 Response Metadata:");
         println!("  Response ID: {}", response.id);
         println!("  Model: {}", response.model);
         println!("  Created At: {}", response.created_at);
-        println!("  Status: {}", response.status);
+        if let Some(status) = &response.status {
+            println!("  Status: {}", status);
+        }
         println!("  Object: {}", response.object);
+        if response.cached {
+            println!("  Cached: yes (served locally, no API call)");
+        }
 
-        if !response.output.is_empty() {
-            let first_output = &response.output[0];
+        if let Some(first_output) = response.messages().next() {
             println!("  Message ID: {}", first_output.id);
             println!("  Message Status: {}", first_output.status);
             println!("  Role: {}", first_output.role);
         }
 
+        let mcp_calls: Vec<_> = response.mcp_calls().collect();
+        if !mcp_calls.is_empty() {
+            println!("\n📊 MCP Calls:");
+            for call in mcp_calls {
+                println!("  {}.{}({})", call.server_label, call.name, call.arguments);
+                if let Some(error) = &call.error {
+                    println!("    Error: {}", error);
+                }
+            }
+        }
+
         println!("\n📊 Configuration:");
-        println!("  Temperature: {}", response.temperature);
-        println!("  Top P: {}", response.top_p);
-        println!("  Tool Choice: {}", response.tool_choice);
-        println!("  Truncation: {}", response.truncation);
-        println!("  Store: {}", response.store);
-        println!("  Parallel Tool Calls: {}", response.parallel_tool_calls);
+        if let Some(temperature) = &response.temperature {
+            println!("  Temperature: {}", temperature);
+        }
+        if let Some(top_p) = &response.top_p {
+            println!("  Top P: {}", top_p);
+        }
+        if let Some(tool_choice) = &response.tool_choice {
+            println!("  Tool Choice: {}", tool_choice);
+        }
+        if let Some(truncation) = &response.truncation {
+            println!("  Truncation: {}", truncation);
+        }
+        if let Some(store) = &response.store {
+            println!("  Store: {}", store);
+        }
+        if let Some(parallel_tool_calls) = &response.parallel_tool_calls {
+            println!("  Parallel Tool Calls: {}", parallel_tool_calls);
+        }
 
         println!("\n📊 Token Usage:");
         println!("  Input tokens: {}", response.usage.input_tokens);
@@ -72,6 +412,23 @@ impl OutputFormatter {
             }
         }
 
+        if let Some(perplexity) = perplexity(&collect_logprobs(response)) {
+            println!("  Perplexity: {:.3}", perplexity);
+        }
+
+        if let Some(cost) = &response.cost {
+            println!("\n💰 Estimated Cost:");
+            println!("  Input: {}", format_usd(cost.input));
+            if cost.cached_input > 0.0 {
+                println!("  Cached input: {}", format_usd(cost.cached_input));
+            }
+            println!("  Output: {}", format_usd(cost.output));
+            if cost.reasoning > 0.0 {
+                println!("  Reasoning: {}", format_usd(cost.reasoning));
+            }
+            println!("  Total: {}", format_usd(cost.total));
+        }
+
         if let Some(reasoning) = &response.reasoning {
             println!("\n📊 Reasoning:");
             if let Some(effort) = &reasoning.effort {
@@ -1,9 +1,11 @@
+use crate::config::file::ConfigFile;
 use anyhow::{anyhow, Result};
 use std::env;
 
 pub struct Config {
     pub api_key: String,
     pub default_model: String,
+    pub file: ConfigFile,
 }
 
 impl Config {
@@ -21,6 +23,7 @@ impl Config {
         Ok(Config {
             api_key,
             default_model: "gpt-4o-mini".to_string(),
+            file: ConfigFile::load_default()?,
         })
     }
 
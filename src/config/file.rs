@@ -0,0 +1,174 @@
+use crate::api::cache::ResponseCache;
+use crate::api::pricing::PricingTable;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Settings read from the optional TOML config file
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ConfigFile {
+    /// Local MCP servers launched over stdio, keyed by name
+    pub mcp_servers: BTreeMap<String, LocalMcpServerConfig>,
+    /// Metadata attached to every request; values may use templates such as `{hostname}`
+    pub metadata: BTreeMap<String, String>,
+    pub history: HistoryConfig,
+    /// Overrides for the built-in model prices and service tier multipliers
+    pub pricing: PricingTable,
+    pub budget: BudgetConfig,
+    pub cache: CacheConfig,
+    pub policy: PolicyConfig,
+}
+
+/// Organization rules applied to every request through client middleware
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// Models that may be used; a trailing `*` matches by prefix. Empty allows all.
+    pub allowed_models: Vec<String>,
+    /// HTTP headers added to every request
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Local response cache; `--cache` and `--no-cache` override `enabled`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// Cache sampled requests too, not only those with temperature 0
+    pub force: bool,
+    pub ttl_secs: u64,
+    pub max_size_mb: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            force: false,
+            ttl_secs: 24 * 60 * 60,
+            max_size_mb: 100,
+        }
+    }
+}
+
+/// Spending limits in USD per UTC day and month; calls are refused once reached
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub daily: Option<f64>,
+    pub monthly: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Record each prompt and response in the local history file; off by
+    /// default, since it keeps everything sent in plain text
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalMcpServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Seconds to wait for each JSON-RPC response
+    #[serde(default = "default_mcp_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_mcp_timeout() -> u64 {
+    60
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Load the config file from its default location; a missing file yields
+    /// the default configuration
+    pub fn load_default() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// `OPENAI_CLI_CONFIG`, else `$XDG_CONFIG_HOME/openai-cli/config.toml`,
+    /// else `~/.config/openai-cli/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(path) = env::var("OPENAI_CLI_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        Some(config_dir()?.join("config.toml"))
+    }
+}
+
+impl CacheConfig {
+    /// Cache in the default directory with these limits
+    pub fn open_default(&self) -> Result<ResponseCache> {
+        let dir = default_cache_dir().context("Cannot locate a cache directory")?;
+        Ok(ResponseCache::new(dir)
+            .ttl(Duration::from_secs(self.ttl_secs))
+            .max_bytes(self.max_size_mb * 1024 * 1024)
+            .force(self.force))
+    }
+}
+
+// `$XDG_CONFIG_HOME/openai-cli`, else `~/.config/openai-cli`
+fn config_dir() -> Option<PathBuf> {
+    let config_dir = env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| Path::new(&home).join(".config")))
+        .ok()?;
+    Some(config_dir.join("openai-cli"))
+}
+
+/// Directory of the response cache: `OPENAI_CLI_CACHE_DIR`, else
+/// `$XDG_CACHE_HOME/openai-cli/responses`, else `~/.cache/openai-cli/responses`
+pub fn default_cache_dir() -> Option<PathBuf> {
+    if let Ok(path) = env::var("OPENAI_CLI_CACHE_DIR") {
+        return Some(PathBuf::from(path));
+    }
+    let cache_dir = env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| Path::new(&home).join(".cache")))
+        .ok()?;
+    Some(cache_dir.join("openai-cli").join("responses"))
+}
+
+/// Directory of the local prompt library: `OPENAI_CLI_PROMPTS_DIR`, else
+/// `prompts` next to the default config file
+pub fn default_prompts_dir() -> Option<PathBuf> {
+    if let Ok(path) = env::var("OPENAI_CLI_PROMPTS_DIR") {
+        return Some(PathBuf::from(path));
+    }
+    Some(config_dir()?.join("prompts"))
+}
+
+/// Directory for data the CLI keeps between runs: `OPENAI_CLI_DATA_DIR`, else
+/// `$XDG_DATA_HOME/openai-cli`, else `~/.local/share/openai-cli`
+pub fn default_data_dir() -> Option<PathBuf> {
+    if let Ok(path) = env::var("OPENAI_CLI_DATA_DIR") {
+        return Some(PathBuf::from(path));
+    }
+    let data_dir = env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| Path::new(&home).join(".local").join("share")))
+        .ok()?;
+    Some(data_dir.join("openai-cli"))
+}
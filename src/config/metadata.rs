@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Values substituted for `{hostname}`, `{git_branch}` and `{session}` in
+/// metadata values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateContext {
+    pub hostname: String,
+    pub git_branch: String,
+    pub session: String,
+}
+
+impl TemplateContext {
+    /// Look up the values for the current machine, directory and shell session
+    pub fn detect() -> Self {
+        Self {
+            hostname: detect_hostname(),
+            git_branch: detect_git_branch(),
+            session: session_id(),
+        }
+    }
+
+    /// Replace known placeholders; anything else in braces is left as written
+    pub fn render(&self, value: &str) -> String {
+        value
+            .replace("{hostname}", &self.hostname)
+            .replace("{git_branch}", &self.git_branch)
+            .replace("{session}", &self.session)
+    }
+}
+
+/// Metadata for a request: config defaults overridden by `--metadata` pairs,
+/// with templates rendered. Placeholders are only resolved when used.
+pub fn resolve_metadata(
+    defaults: &BTreeMap<String, String>,
+    overrides: &[(String, String)],
+) -> BTreeMap<String, String> {
+    let mut metadata = defaults.clone();
+    metadata.extend(overrides.iter().cloned());
+
+    if metadata.values().any(|value| value.contains('{')) {
+        let context = TemplateContext::detect();
+        render_metadata(&mut metadata, &context);
+    }
+    metadata
+}
+
+pub fn render_metadata(metadata: &mut BTreeMap<String, String>, context: &TemplateContext) {
+    for value in metadata.values_mut() {
+        *value = context.render(value);
+    }
+}
+
+/// Parse a `KEY=VALUE` pair; the value may be empty but the key may not
+pub fn parse_key_value(pair: &str) -> Result<(String, String), String> {
+    match pair.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", pair)),
+    }
+}
+
+fn detect_hostname() -> String {
+    if let Ok(hostname) = env::var("HOSTNAME") {
+        return hostname;
+    }
+    if let Ok(hostname) = fs::read_to_string("/etc/hostname") {
+        return hostname.trim().to_string();
+    }
+    command_output("hostname", &[]).unwrap_or_default()
+}
+
+fn detect_git_branch() -> String {
+    command_output("git", &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap_or_default()
+}
+
+// `OPENAI_CLI_SESSION` groups runs from one shell; otherwise each run is its own session
+fn session_id() -> String {
+    if let Ok(session) = env::var("OPENAI_CLI_SESSION") {
+        return session;
+    }
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", seconds, process::id())
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    Some(text.trim().to_string())
+}
@@ -1 +1,3 @@
 pub mod env;
+pub mod file;
+pub mod metadata;
@@ -0,0 +1,91 @@
+use crate::api::models::{ResponseApiResponse, ResponseStatus};
+use crate::config::file::default_data_dir;
+use crate::utils::content::extract_response_content;
+use crate::utils::jsonl;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// One saved exchange, stored as a line of JSON
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub created_at: u64,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ResponseStatus>,
+    pub prompt: String,
+    pub response: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        prompt: &str,
+        response: &ResponseApiResponse,
+        metadata: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            id: response.id.clone(),
+            created_at: response.created_at,
+            model: response.model.clone(),
+            status: response.status.clone(),
+            prompt: prompt.to_string(),
+            response: extract_response_content(response),
+            metadata,
+        }
+    }
+
+    /// Whether every `(key, value)` filter matches this entry's metadata
+    pub fn matches(&self, filters: &[(String, String)]) -> bool {
+        filters
+            .iter()
+            .all(|(key, value)| self.metadata.get(key) == Some(value))
+    }
+}
+
+/// Append-only history of responses in a JSONL file
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// History file in the default data directory
+    pub fn open_default() -> Result<Self> {
+        let dir = default_data_dir().ok_or_else(|| anyhow!("Cannot locate a data directory"))?;
+        Ok(Self::new(dir.join(HISTORY_FILE)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        jsonl::append(&self.path, entry)
+    }
+
+    /// All entries, oldest first; a missing file is an empty history and
+    /// unreadable lines are skipped
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        jsonl::read(&self.path)
+    }
+
+    /// The most recent `limit` entries matching all metadata filters, oldest first
+    pub fn search(&self, filters: &[(String, String)], limit: usize) -> Result<Vec<HistoryEntry>> {
+        let matching: Vec<HistoryEntry> = self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.matches(filters))
+            .collect();
+        let skip = matching.len().saturating_sub(limit);
+        Ok(matching.into_iter().skip(skip).collect())
+    }
+}
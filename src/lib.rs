@@ -1,9 +1,16 @@
 pub mod api;
 pub mod cli;
 pub mod config;
+pub mod history;
+pub mod mcp;
+#[cfg(feature = "mock-server")]
+pub mod mock;
+pub mod prompts;
+pub mod usage;
 pub mod utils;
 
 pub use api::client::OpenAIClient;
 pub use api::models::*;
+pub use api::tools::*;
 pub use cli::args::Args;
 pub use config::env::Config;
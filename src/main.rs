@@ -1,32 +1,105 @@
 use anyhow::Result;
+use futures_util::StreamExt;
+use openai_api::api::errors::{OpenAIError, ValidationError};
+use openai_api::api::middleware::{ExtraHeaders, ModelAllowlist, SpendLimit};
+use openai_api::api::telemetry::TelemetryOptions;
+use openai_api::api::models::{ResponseApiResponse, ResponseRequest};
+use openai_api::api::pricing::{format_usd, PricingTable};
 use clap::Parser;
-use openai_api::cli::args::DisplayMode;
+use openai_api::cli::approval::prompt_mcp_approval;
+use openai_api::cli::args::{
+    CacheCommand, Command, ContextCheck, DisplayMode, PromptsCommand, UsageCommand,
+};
+use openai_api::cli::exit_codes;
+use openai_api::cli::logging;
 use openai_api::cli::output::OutputFormatter;
+use openai_api::config::file::{BudgetConfig, ConfigFile};
+use openai_api::config::metadata::resolve_metadata;
+use openai_api::history::store::{HistoryEntry, HistoryStore};
+use openai_api::mcp::host::LocalMcpHost;
+use openai_api::prompts::library::PromptLibrary;
+use openai_api::usage::ledger::{self, Ledger, UsageRecorder};
+use openai_api::usage::report;
+use openai_api::utils::tokens::{ContextUsage, Encoding};
 use openai_api::{Args, Config, OpenAIClient};
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+// Alternatives requested per token when --show-logprobs is used alone
+const DEFAULT_TOP_LOGPROBS: u32 = 5;
+
+// Continuation requests allowed by --continue-on-truncation
+const MAX_CONTINUATIONS: u32 = 3;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let args = Args::parse();
 
+    // Every failure after parsing exits with the code for its error class
+    if let Err(e) = run(&args).await {
+        eprintln!("❌ Error: {}", e);
+        if let Some(hint) = exit_codes::error_hint(&e) {
+            eprintln!("💡 {}", hint);
+        }
+        std::process::exit(exit_codes::for_error(&e));
+    }
+}
+
+async fn run(args: &Args) -> Result<()> {
+    // Subcommands work offline and need no API key
+    if let Some(command) = &args.command {
+        return run_command(command);
+    }
+
+    logging::init(args)?;
+
     // Load configuration
     let config = Config::from_env()?;
 
     // Validate all parameters
     args.validate_all()?;
 
-    // Get the prompt
-    let prompt = args.get_prompt()?;
+    // Get the prompt; a stored prompt can be sent without any extra input
+    let prompt = match (&args.prompt, &args.prompt_id) {
+        (None, Some(_)) => None,
+        _ => Some(args.get_prompt()?),
+    };
 
-    // Create OpenAI client
-    let client = OpenAIClient::new(config.api_key().to_string());
+    // Create OpenAI client with the base URL and cassette from the environment
+    let telemetry = TelemetryOptions::default()
+        .log_bodies(args.log_bodies)
+        .genai_attributes(args.log_genai);
+    let mut client = OpenAIClient::from_env()?.with_telemetry(telemetry);
+    let cache = args.resolve_cache(&config.file.cache);
+    if cache.enabled {
+        client = client.with_cache(cache.open_default()?);
+    }
+    let policy = &config.file.policy;
+    if !policy.allowed_models.is_empty() {
+        client = client.with_middleware(ModelAllowlist::new(policy.allowed_models.clone()));
+    }
+    if !policy.headers.is_empty() {
+        let mut headers = ExtraHeaders::new();
+        for (name, value) in &policy.headers {
+            headers = headers.header(name, value)?;
+        }
+        client = client.with_middleware(headers);
+    }
+    // Follow-up requests are checked against what is left of --max-cost
+    if let Some(limit) = args.max_cost {
+        client = client.with_middleware(SpendLimit::new(config.file.pricing.clone(), limit));
+    }
 
     println!("🤖 Sending request to OpenAI...");
 
     // Build the request with all provided parameters
-    let mut request_builder = client
-        .request_builder()
-        .model(args.model.clone())
-        .input_text(prompt);
+    let mut request_builder = client.request_builder().model(args.model.clone());
+    if let Some(prompt) = &prompt {
+        request_builder = request_builder.input_text(prompt.clone());
+    }
+    if let Some(template) = args.get_prompt_template() {
+        request_builder = request_builder.prompt_template(template);
+    }
 
     // Add optional parameters if provided
     if let Some(temp) = args.temperature {
@@ -56,8 +129,12 @@ async fn main() -> Result<()> {
     if let Some(top_p) = args.top_p {
         request_builder = request_builder.top_p(top_p);
     }
-    if let Some(logprobs) = args.top_logprobs {
-        request_builder = request_builder.top_logprobs(logprobs);
+    match args.top_logprobs {
+        Some(logprobs) => request_builder = request_builder.top_logprobs(logprobs),
+        None if args.show_logprobs => {
+            request_builder = request_builder.top_logprobs(DEFAULT_TOP_LOGPROBS)
+        }
+        None => {}
     }
     request_builder = request_builder.truncation(args.truncation.clone());
     if let Some(store) = args.store {
@@ -66,21 +143,315 @@ async fn main() -> Result<()> {
     if let Some(parallel) = args.parallel_tool_calls {
         request_builder = request_builder.parallel_tool_calls(parallel);
     }
+    if let Some(effort) = args.reasoning_effort {
+        request_builder = request_builder.reasoning_effort(effort);
+    }
+    if let Some(summary) = args.reasoning_summary {
+        request_builder = request_builder.reasoning_summary(summary);
+    }
+    for field in &args.include {
+        request_builder = request_builder.add_include(field.clone());
+    }
+    let mut tools = args.get_tools()?;
+    let local_mcp = if args.local_mcp.is_empty() {
+        None
+    } else {
+        let host = LocalMcpHost::start_named(&config.file.mcp_servers, &args.local_mcp).await?;
+        tools.extend(host.tool_definitions().iter().cloned());
+        Some(host)
+    };
+    if !tools.is_empty() {
+        request_builder = request_builder.tools(tools);
+    }
+    if let Some(choice) = &args.tool_choice {
+        request_builder = request_builder.tool_choice(choice.clone());
+    }
 
-    // Make the API request
-    match client.get_response_with_builder(request_builder).await {
-        Ok(response) => {
-            match args.get_display_mode() {
-                DisplayMode::Json => OutputFormatter::format_json(&response)?,
-                DisplayMode::Markdown => OutputFormatter::format_markdown_response(&response, args.verbose)?,
-                DisplayMode::Plain => OutputFormatter::format_response(&response, args.verbose)?,
-            }
+    let metadata = resolve_metadata(&config.file.metadata, &args.metadata);
+    if !metadata.is_empty() {
+        request_builder = request_builder.metadata(serde_json::to_value(&metadata)?);
+    }
+
+    let request = request_builder.try_build()?;
+
+    // Each continuation is another request that may use the whole budget
+    let max_requests = if args.continue_on_truncation {
+        1 + MAX_CONTINUATIONS
+    } else {
+        1
+    };
+    check_context_window(&request, args.context_check)?;
+    check_max_cost(&request, &config.file.pricing, args.max_cost, max_requests)?;
+    check_budget(&config.file.budget)?;
+
+    // Every API call, follow-ups included, is recorded so budgets see it; a
+    // failure only warns
+    let recorder = match Ledger::open_default() {
+        Ok(ledger) => {
+            let recorder = Arc::new(UsageRecorder::new(
+                ledger,
+                config.file.pricing.clone(),
+                &args.get_profile(),
+                metadata.clone(),
+            ));
+            client = client.with_middleware(recorder.clone());
+            Some(recorder)
         }
         Err(e) => {
-            eprintln!("❌ Error: {}", e);
-            std::process::exit(1);
+            eprintln!("⚠️ Could not record usage: {}", e);
+            None
+        }
+    };
+
+    // --stream prints the first reply's text as it arrives, unless the
+    // output is JSON
+    let live = args.stream && args.get_display_mode() != DisplayMode::Json;
+    let mut streamed = None;
+    let result = send(
+        &client,
+        &request,
+        local_mcp.as_ref(),
+        args.max_turns,
+        live,
+        &mut streamed,
+    )
+    .await;
+    let result = match result {
+        Ok(response) if args.continue_on_truncation => client
+            .continue_truncated(&request, response, MAX_CONTINUATIONS)
+            .await
+            .map_err(Into::into),
+        result => result,
+    };
+    for e in recorder.iter().flat_map(|recorder| recorder.take_failures()) {
+        eprintln!("⚠️ Could not record usage: {}", e);
+    }
+    let mut response = result?;
+
+    // Cache hits made no API call, so they cost nothing
+    if !response.cached {
+        response.cost = config.file.pricing.cost(&response);
+    }
+
+    if args.saves_history(&config.file.history) {
+        let entry = HistoryEntry::new(
+            prompt.as_deref().unwrap_or_default(),
+            &response,
+            metadata,
+        );
+        let saved = HistoryStore::open_default().and_then(|store| store.append(&entry));
+        if let Err(e) = saved {
+            eprintln!("⚠️ Could not save history: {}", e);
         }
     }
 
+    match args.get_display_mode() {
+        // The text was already printed as it streamed
+        _ if streamed.as_ref() == Some(&response.id) && !args.verbose => {}
+        DisplayMode::Json => OutputFormatter::format_json(&response)?,
+        DisplayMode::Markdown => OutputFormatter::format_markdown_response(
+            &response,
+            args.verbose,
+            args.show_thinking,
+        )?,
+        DisplayMode::Plain => OutputFormatter::format_response(&response, args.verbose)?,
+    }
+    if args.show_logprobs && args.get_display_mode() != DisplayMode::Json {
+        OutputFormatter::format_logprobs(&response)?;
+    }
+    if let Some(notice) = exit_codes::completion_notice(&response) {
+        eprintln!("\n{}", notice);
+    }
+    let code = exit_codes::for_response(&response);
+    if code != exit_codes::SUCCESS {
+        std::process::exit(code);
+    }
+
     Ok(())
 }
+
+fn run_command(command: &Command) -> Result<()> {
+    match command {
+        Command::History(history) => {
+            let store = HistoryStore::open_default()?;
+            let entries = store.search(&history.metadata, history.limit)?;
+            OutputFormatter::format_history(&entries, history.json)
+        }
+        Command::Prompts(prompts) => {
+            let library = PromptLibrary::open_default()?;
+            match &prompts.command {
+                PromptsCommand::List => OutputFormatter::format_prompt_list(&library.list()?),
+                PromptsCommand::Show { name } => {
+                    OutputFormatter::format_prompt(&library.get(name)?)
+                }
+                PromptsCommand::Render { name, vars } => {
+                    println!("{}", library.get(name)?.render(vars)?)
+                }
+            }
+            Ok(())
+        }
+        Command::Usage(usage) => match &usage.command {
+            UsageCommand::Report(options) => {
+                let entries = Ledger::open_default()?.entries()?;
+                let rows = report::aggregate(
+                    &entries,
+                    &options.by,
+                    options.since.as_deref(),
+                    options.until.as_deref(),
+                );
+                OutputFormatter::format_usage_report(&rows, &options.by, options.format)
+            }
+        },
+        Command::Cache(cache) => {
+            let store = ConfigFile::load_default()?.cache.open_default()?;
+            match cache.command {
+                CacheCommand::Stats => {
+                    OutputFormatter::format_cache_stats(&store.stats()?, store.dir())
+                }
+                CacheCommand::Clear => {
+                    let removed = store.clear()?;
+                    println!("🗄️ Removed {} cached responses", removed);
+                }
+            }
+            Ok(())
+        }
+        Command::Tokens(tokens) => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            let encoding = tokens
+                .encoding
+                .unwrap_or_else(|| Encoding::for_model(&tokens.model));
+            OutputFormatter::format_token_count(
+                encoding.count_tokens(&text),
+                encoding,
+                &tokens.model,
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Refuse a request whose worst-case cost over `max_requests` calls is above
+/// `--max-cost`
+fn check_max_cost(
+    request: &ResponseRequest,
+    pricing: &PricingTable,
+    max_cost: Option<f64>,
+    max_requests: u32,
+) -> Result<()> {
+    let Some(limit) = max_cost else {
+        return Ok(());
+    };
+    let model = request.model.clone().unwrap_or_default();
+    if pricing.price(&model).is_none() {
+        return Err(ValidationError::UnknownPrice(model).into());
+    }
+    let estimate = pricing
+        .worst_case_cost(request)
+        .ok_or_else(|| ValidationError::UnboundedCost(model.clone()))?
+        * f64::from(max_requests);
+
+    if estimate > limit {
+        return Err(ValidationError::CostLimitExceeded {
+            estimate: format_usd(estimate),
+            limit: format_usd(limit),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Refuse to call the API once this UTC day's or month's budget is spent
+fn check_budget(budget: &BudgetConfig) -> Result<()> {
+    if budget.daily.is_none() && budget.monthly.is_none() {
+        return Ok(());
+    }
+    let spending = Ledger::open_default()?.spending(ledger::now())?;
+
+    let periods = [
+        ("daily", budget.daily, spending.day),
+        ("monthly", budget.monthly, spending.month),
+    ];
+    for (period, limit, spent) in periods {
+        if let Some(limit) = limit.filter(|limit| spent >= *limit) {
+            return Err(ValidationError::BudgetExceeded {
+                period,
+                spent: format_usd(spent),
+                limit: format_usd(limit),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Estimate whether the request fits the model's context window, so an
+/// overflow costs nothing; models missing from the table are not checked
+fn check_context_window(request: &ResponseRequest, check: ContextCheck) -> Result<()> {
+    if check == ContextCheck::Off {
+        return Ok(());
+    }
+    let Some(usage) = ContextUsage::of(request).filter(ContextUsage::exceeds_window) else {
+        return Ok(());
+    };
+
+    let error = ValidationError::ContextWindowExceeded {
+        model: request.model.clone().unwrap_or_default(),
+        tokens: usage.total(),
+        window: usage.window,
+    };
+    match check {
+        ContextCheck::Fail => Err(error.into()),
+        _ => {
+            eprintln!("⚠️ Warning: {} (use --context-check fail to stop)", error);
+            Ok(())
+        }
+    }
+}
+
+/// Make the API request, answering MCP approval requests interactively and
+/// running local MCP tool calls until the model produces a final answer
+async fn send(
+    client: &OpenAIClient,
+    request: &ResponseRequest,
+    local_mcp: Option<&LocalMcpHost>,
+    max_turns: u32,
+    live: bool,
+    streamed: &mut Option<String>,
+) -> Result<ResponseApiResponse> {
+    let response = if live {
+        let response = stream(client, request).await?;
+        *streamed = Some(response.id.clone());
+        response
+    } else {
+        client.send_request(request.clone()).await?
+    };
+    let mut response = client
+        .resolve_mcp_approvals(request, response, prompt_mcp_approval, max_turns)
+        .await?;
+
+    if let Some(host) = local_mcp {
+        response = host
+            .resolve_function_calls(client, request, response, max_turns)
+            .await?;
+    }
+
+    Ok(response)
+}
+
+// Print a streamed reply's text as it arrives and return the final response
+async fn stream(client: &OpenAIClient, request: &ResponseRequest) -> Result<ResponseApiResponse> {
+    let mut events = client.stream_request(request.clone()).await?;
+    while let Some(event) = events.next().await {
+        let event = event?;
+        if let Some(delta) = event.text_delta() {
+            print!("{}", delta);
+            std::io::stdout().flush()?;
+        }
+        if event.is_final() {
+            println!();
+            return Ok(event.response().ok_or(OpenAIError::EmptyResponse)?);
+        }
+    }
+    Err(OpenAIError::EmptyResponse.into())
+}
@@ -0,0 +1,312 @@
+use crate::config::file::LocalMcpServerConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+#[derive(Error, Debug)]
+pub enum McpError {
+    #[error("Failed to start MCP server '{server}': {source}")]
+    Spawn {
+        server: String,
+        source: std::io::Error,
+    },
+    #[error("MCP server '{0}' closed its output")]
+    Closed(String),
+    #[error("MCP server '{0}' timed out")]
+    Timeout(String),
+    #[error("MCP server '{server}' returned error {code}: {message}")]
+    Rpc {
+        server: String,
+        code: i64,
+        message: String,
+    },
+    #[error("Invalid message from MCP server '{server}': {message}")]
+    Protocol { server: String, message: String },
+    #[error("Unknown local MCP server '{0}'")]
+    UnknownServer(String),
+    #[error("Unknown function tool '{0}'")]
+    UnknownTool(String),
+    #[error("MCP tools '{first}' and '{second}' both map to function tool '{function}'")]
+    NameCollision {
+        function: String,
+        first: String,
+        second: String,
+    },
+    #[error("MCP I/O Error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Api(#[from] crate::api::errors::OpenAIError),
+}
+
+// Tool advertised by an MCP server in `tools/list`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct McpToolDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "inputSchema", default = "empty_schema")]
+    pub input_schema: Value,
+}
+
+fn empty_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+// Result of a `tools/call` request
+#[derive(Deserialize, Debug, Clone)]
+pub struct McpToolResult {
+    #[serde(default)]
+    pub content: Vec<Value>,
+    #[serde(rename = "isError", default)]
+    pub is_error: bool,
+}
+
+impl McpToolResult {
+    /// Flatten the content parts into the text handed back to the model
+    pub fn to_output(&self) -> String {
+        let parts: Vec<String> = self
+            .content
+            .iter()
+            .map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("text") => part
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => part.to_string(),
+            })
+            .collect();
+        let text = parts.join("\n");
+
+        if self.is_error {
+            format!("Error: {}", text)
+        } else {
+            text
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Deserialize)]
+struct RpcErrorBody {
+    code: i64,
+    message: String,
+}
+
+struct Connection {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+/// JSON-RPC client for one MCP server running as a child process over stdio
+pub struct McpStdioClient {
+    name: String,
+    timeout: Duration,
+    connection: Mutex<Connection>,
+    _child: Child,
+}
+
+impl McpStdioClient {
+    /// Launch the server and complete the `initialize` handshake
+    pub async fn start(name: &str, config: &LocalMcpServerConfig) -> Result<Self, McpError> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|source| McpError::Spawn {
+                server: name.to_string(),
+                source,
+            })?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| McpError::Closed(name.to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| McpError::Closed(name.to_string()))?;
+
+        let client = Self {
+            name: name.to_string(),
+            timeout: Duration::from_secs(config.timeout_secs),
+            connection: Mutex::new(Connection {
+                stdin,
+                stdout: BufReader::new(stdout),
+                next_id: 1,
+            }),
+            _child: child,
+        };
+        client.initialize().await?;
+        Ok(client)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn initialize(&self) -> Result<(), McpError> {
+        self.request(
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                }
+            }),
+        )
+        .await?;
+        self.notify("notifications/initialized").await
+    }
+
+    /// All tools the server exposes, following pagination cursors
+    pub async fn list_tools(&self) -> Result<Vec<McpToolDescriptor>, McpError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+
+            let page: Vec<McpToolDescriptor> =
+                serde_json::from_value(result.get("tools").cloned().unwrap_or_default())
+                    .map_err(|e| self.protocol_error(e.to_string()))?;
+            tools.extend(page);
+
+            cursor = result
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                return Ok(tools);
+            }
+        }
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<McpToolResult, McpError> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        serde_json::from_value(result).map_err(|e| self.protocol_error(e.to_string()))
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, McpError> {
+        let mut connection = self.connection.lock().await;
+        let id = connection.next_id;
+        connection.next_id += 1;
+
+        let message = RpcRequest {
+            jsonrpc: "2.0",
+            id: Some(id),
+            method,
+            params: Some(params),
+        };
+        Self::write_message(&mut connection.stdin, &message).await?;
+
+        tokio::time::timeout(self.timeout, self.read_response(&mut connection.stdout, id))
+            .await
+            .map_err(|_| McpError::Timeout(self.name.clone()))?
+    }
+
+    async fn notify(&self, method: &str) -> Result<(), McpError> {
+        let mut connection = self.connection.lock().await;
+        let message = RpcRequest {
+            jsonrpc: "2.0",
+            id: None,
+            method,
+            params: None,
+        };
+        Self::write_message(&mut connection.stdin, &message).await
+    }
+
+    async fn write_message(
+        stdin: &mut ChildStdin,
+        message: &RpcRequest<'_>,
+    ) -> Result<(), McpError> {
+        let mut line = serde_json::to_string(message).expect("JSON-RPC request serializes");
+        line.push('\n');
+        stdin.write_all(line.as_bytes()).await?;
+        stdin.flush().await?;
+        Ok(())
+    }
+
+    async fn read_response(
+        &self,
+        stdout: &mut BufReader<ChildStdout>,
+        id: u64,
+    ) -> Result<Value, McpError> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if stdout.read_line(&mut line).await? == 0 {
+                return Err(McpError::Closed(self.name.clone()));
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response: RpcResponse =
+                serde_json::from_str(&line).map_err(|e| self.protocol_error(e.to_string()))?;
+
+            // Skip server notifications and responses to other requests
+            if response.id.as_ref().and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+
+            if let Some(error) = response.error {
+                return Err(McpError::Rpc {
+                    server: self.name.clone(),
+                    code: error.code,
+                    message: error.message,
+                });
+            }
+            return Ok(response.result.unwrap_or(Value::Null));
+        }
+    }
+
+    fn protocol_error(&self, message: String) -> McpError {
+        McpError::Protocol {
+            server: self.name.clone(),
+            message,
+        }
+    }
+}
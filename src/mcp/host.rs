@@ -0,0 +1,160 @@
+use crate::api::client::OpenAIClient;
+use crate::api::errors::OpenAIError;
+use crate::api::models::{
+    FunctionCall, InputItem, InputType, ResponseApiResponse, ResponseRequest,
+};
+use crate::api::tools::{FunctionTool, ToolDefinition};
+use crate::config::file::LocalMcpServerConfig;
+use crate::mcp::client::{McpError, McpStdioClient};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+// Function names must match ^[a-zA-Z0-9_-]{1,64}$
+const MAX_FUNCTION_NAME_LEN: usize = 64;
+
+/// Local MCP servers whose tools are offered to the model as function tools
+pub struct LocalMcpHost {
+    servers: Vec<McpStdioClient>,
+    routes: HashMap<String, (usize, String)>, // function name -> (server, MCP tool name)
+    tools: Vec<ToolDefinition>,
+}
+
+impl LocalMcpHost {
+    /// Launch every configured server and collect its tools
+    pub async fn start(configs: &BTreeMap<String, LocalMcpServerConfig>) -> Result<Self, McpError> {
+        let mut host = Self {
+            servers: Vec::new(),
+            routes: HashMap::new(),
+            tools: Vec::new(),
+        };
+
+        for (name, config) in configs {
+            let server = McpStdioClient::start(name, config).await?;
+            let index = host.servers.len();
+
+            for tool in server.list_tools().await? {
+                let function_name = Self::function_name(name, &tool.name);
+                // Sanitizing and truncating can map two tools to one name
+                if let Some((other, other_tool)) = host.routes.get(&function_name) {
+                    let other_server = host.servers.get(*other).map_or(name.as_str(), |s| s.name());
+                    return Err(McpError::NameCollision {
+                        function: function_name,
+                        first: format!("{}/{}", other_server, other_tool),
+                        second: format!("{}/{}", name, tool.name),
+                    });
+                }
+                let mut function = FunctionTool::new(function_name.clone(), tool.input_schema);
+                if let Some(description) = tool.description {
+                    function = function.description(description);
+                }
+                host.tools.push(function.into());
+                host.routes.insert(function_name, (index, tool.name));
+            }
+            host.servers.push(server);
+        }
+
+        Ok(host)
+    }
+
+    /// Launch only the named servers from `configs`
+    pub async fn start_named(
+        configs: &BTreeMap<String, LocalMcpServerConfig>,
+        names: &[String],
+    ) -> Result<Self, McpError> {
+        let mut selected = BTreeMap::new();
+        for name in names {
+            let config = configs
+                .get(name)
+                .ok_or_else(|| McpError::UnknownServer(name.clone()))?;
+            selected.insert(name.clone(), config.clone());
+        }
+        Self::start(&selected).await
+    }
+
+    /// Function tool name for an MCP tool, namespaced by server
+    pub fn function_name(server: &str, tool: &str) -> String {
+        format!("{}__{}", server, tool)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_FUNCTION_NAME_LEN)
+            .collect()
+    }
+
+    /// Function tool definitions to add to the request
+    pub fn tool_definitions(&self) -> &[ToolDefinition] {
+        &self.tools
+    }
+
+    /// Whether the named function call is served by one of these servers
+    pub fn handles(&self, function_name: &str) -> bool {
+        self.routes.contains_key(function_name)
+    }
+
+    /// Run a function call through `tools/call` and wrap the result for the model
+    pub async fn call(&self, call: &FunctionCall) -> Result<InputItem, McpError> {
+        let (index, tool_name) = self
+            .routes
+            .get(&call.name)
+            .ok_or_else(|| McpError::UnknownTool(call.name.clone()))?;
+
+        let arguments: Value = if call.arguments.trim().is_empty() {
+            Value::Object(Default::default())
+        } else {
+            match serde_json::from_str(&call.arguments) {
+                Ok(arguments) => arguments,
+                Err(e) => {
+                    let output = format!("Error: invalid JSON arguments: {}", e);
+                    return Ok(InputItem::function_call_output(call, output));
+                }
+            }
+        };
+
+        // Tool-level failures go back to the model; transport failures abort
+        let output = match self.servers[*index].call_tool(tool_name, arguments).await {
+            Ok(result) => result.to_output(),
+            Err(McpError::Rpc { message, .. }) => format!("Error: {}", message),
+            Err(e) => return Err(e),
+        };
+        Ok(InputItem::function_call_output(call, output))
+    }
+
+    /// Answer function calls served by these servers until the model returns a
+    /// response without any, sending at most `max_turns` follow-up requests.
+    /// Stops early if the response calls a function this host does not serve,
+    /// leaving it to the caller.
+    pub async fn resolve_function_calls(
+        &self,
+        client: &OpenAIClient,
+        request: &ResponseRequest,
+        mut response: ResponseApiResponse,
+        max_turns: u32,
+    ) -> Result<ResponseApiResponse, McpError> {
+        let mut turns = 0;
+        loop {
+            let calls: Vec<FunctionCall> = response.function_calls().cloned().collect();
+            if calls.is_empty() || calls.iter().any(|call| !self.handles(&call.name)) {
+                return Ok(response);
+            }
+            if turns == max_turns {
+                return Err(OpenAIError::TooManyTurns(max_turns).into());
+            }
+            turns += 1;
+
+            let mut outputs = Vec::with_capacity(calls.len());
+            for call in &calls {
+                outputs.push(self.call(call).await?);
+            }
+
+            let mut follow_up = request.clone();
+            follow_up.input = Some(InputType::Items(outputs));
+            follow_up.previous_response_id = Some(response.id.clone());
+            response = client.send_request(follow_up).await?;
+        }
+    }
+}
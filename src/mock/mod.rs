@@ -0,0 +1,7 @@
+//! In-process fake of the Responses API, behind the `mock-server` feature
+
+pub mod reply;
+pub mod server;
+
+pub use reply::{MockReply, SseEvent};
+pub use server::{MockServer, ReceivedRequest, REQUEST_ID};
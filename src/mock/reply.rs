@@ -0,0 +1,186 @@
+use serde_json::{json, Value};
+
+/// One server-sent event of a streamed response
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: String,
+    pub data: Value,
+}
+
+impl SseEvent {
+    pub fn new(event: impl Into<String>, data: Value) -> Self {
+        Self {
+            event: event.into(),
+            data,
+        }
+    }
+
+    /// Wire form, `event:` and `data:` lines ending in a blank line
+    pub fn encode(&self) -> String {
+        format!("event: {}\ndata: {}\n\n", self.event, self.data)
+    }
+}
+
+/// One scripted answer to `POST /v1/responses`. Response objects may leave
+/// out `id` and `model`; the server fills them in from the request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockReply {
+    /// A response object, stored for the retrieval endpoints
+    Response(Value),
+    /// Answered as `queued`, then polled as `in_progress` `polls` times
+    /// before `response` is returned
+    Background { response: Value, polls: usize },
+    /// A `text/event-stream` body; the response in the last event is stored
+    Stream(Vec<SseEvent>),
+    /// An API error with extra headers, such as rate-limit ones
+    Error {
+        status: u16,
+        body: Value,
+        headers: Vec<(String, String)>,
+    },
+}
+
+impl MockReply {
+    /// A completed response with one assistant message
+    pub fn text(text: &str) -> Self {
+        Self::Response(completed(vec![message(text)]))
+    }
+
+    /// A completed response asking for one function call
+    pub fn function_call(name: &str, arguments: Value) -> Self {
+        Self::Response(completed(vec![json!({
+            "type": "function_call",
+            "id": format!("fc_mock_{}", name),
+            "call_id": format!("call_mock_{}", name),
+            "name": name,
+            "arguments": arguments.to_string(),
+            "status": "completed"
+        })]))
+    }
+
+    /// `text` streamed word by word, from `response.created` to
+    /// `response.completed`
+    pub fn text_stream(text: &str) -> Self {
+        let mut in_progress = completed(Vec::new());
+        in_progress["status"] = json!("in_progress");
+        in_progress["usage"] = Value::Null;
+
+        let mut events = vec![SseEvent::new(
+            "response.created",
+            json!({ "type": "response.created", "response": in_progress }),
+        )];
+        for delta in text.split_inclusive(' ') {
+            events.push(SseEvent::new(
+                "response.output_text.delta",
+                json!({
+                    "type": "response.output_text.delta",
+                    "item_id": "msg_mock",
+                    "output_index": 0,
+                    "content_index": 0,
+                    "delta": delta
+                }),
+            ));
+        }
+        events.push(SseEvent::new(
+            "response.output_text.done",
+            json!({
+                "type": "response.output_text.done",
+                "item_id": "msg_mock",
+                "output_index": 0,
+                "content_index": 0,
+                "text": text
+            }),
+        ));
+        events.push(SseEvent::new(
+            "response.completed",
+            json!({ "type": "response.completed", "response": completed(vec![message(text)]) }),
+        ));
+
+        for (sequence_number, event) in events.iter_mut().enumerate() {
+            event.data["sequence_number"] = json!(sequence_number);
+        }
+        Self::Stream(events)
+    }
+
+    /// A background response that finishes with `text` after `polls` polls
+    pub fn background(text: &str, polls: usize) -> Self {
+        let mut response = completed(vec![message(text)]);
+        response["background"] = json!(true);
+        Self::Background { response, polls }
+    }
+
+    /// An API error in the usual `{"error": {...}}` shape
+    pub fn error(status: u16, error_type: &str, code: Option<&str>, message: &str) -> Self {
+        Self::Error {
+            status,
+            body: json!({
+                "error": {
+                    "message": message,
+                    "type": error_type,
+                    "param": null,
+                    "code": code
+                }
+            }),
+            headers: Vec::new(),
+        }
+    }
+
+    /// A 429 with `retry-after` and exhausted `x-ratelimit-*` headers
+    pub fn rate_limited(retry_after_secs: u64) -> Self {
+        Self::error(
+            429,
+            "requests",
+            Some("rate_limit_exceeded"),
+            "Rate limit reached for requests",
+        )
+        .header("retry-after", &retry_after_secs.to_string())
+        .header("x-ratelimit-limit-requests", "500")
+        .header("x-ratelimit-remaining-requests", "0")
+        .header(
+            "x-ratelimit-reset-requests",
+            &format!("{}s", retry_after_secs),
+        )
+    }
+
+    /// Add a header to an error reply; other replies are unchanged
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let Self::Error { headers, .. } = &mut self {
+            headers.push((name.to_string(), value.to_string()));
+        }
+        self
+    }
+}
+
+fn message(text: &str) -> Value {
+    json!({
+        "type": "message",
+        "id": "msg_mock",
+        "status": "completed",
+        "role": "assistant",
+        "content": [{ "type": "output_text", "text": text, "annotations": [] }]
+    })
+}
+
+// A completed response around `output`, with rough word-count usage
+fn completed(output: Vec<Value>) -> Value {
+    let output_tokens: usize = output
+        .iter()
+        .filter_map(|item| item["content"][0]["text"].as_str())
+        .map(|text| text.split_whitespace().count())
+        .sum();
+    json!({
+        "object": "response",
+        "created_at": 1741476542,
+        "status": "completed",
+        "error": null,
+        "incomplete_details": null,
+        "output": output,
+        "usage": {
+            "input_tokens": 10,
+            "input_tokens_details": { "cached_tokens": 0 },
+            "output_tokens": output_tokens,
+            "output_tokens_details": { "reasoning_tokens": 0 },
+            "total_tokens": 10 + output_tokens
+        }
+    })
+}
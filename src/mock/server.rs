@@ -0,0 +1,394 @@
+use crate::api::client::OpenAIClient;
+use crate::mock::reply::MockReply;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Sent as `x-request-id` on every reply
+pub const REQUEST_ID: &str = "req_mock";
+
+/// A request the mock server received
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Value,
+}
+
+impl ReceivedRequest {
+    /// The key sent in `Authorization: Bearer ...`
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.headers.get("authorization")?.strip_prefix("Bearer ")
+    }
+
+    /// A body field by JSON pointer, e.g. `/model` or `/input/0/role`
+    pub fn field(&self, pointer: &str) -> Option<&Value> {
+        self.body.pointer(pointer)
+    }
+}
+
+// A stored response moves through `states`; each retrieval advances it
+#[derive(Debug)]
+struct Stored {
+    states: VecDeque<Value>,
+    input: Value,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    replies: VecDeque<MockReply>,
+    requests: Vec<ReceivedRequest>,
+    stored: HashMap<String, Stored>,
+    next_id: u64,
+}
+
+impl MockState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}_mock_{}", prefix, self.next_id)
+    }
+}
+
+type Shared = Arc<Mutex<MockState>>;
+
+/// Fake Responses API on a local port, answering `POST /v1/responses` from a
+/// queue of scripted replies and serving the stored-response endpoints.
+/// Every request is recorded for assertions. Stops when dropped.
+pub struct MockServer {
+    url: String,
+    state: Shared,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/v1", listener.local_addr()?);
+        let state = Shared::default();
+
+        let app = Router::new()
+            .route("/v1/responses", post(create))
+            .route("/v1/responses/{id}", get(retrieve).delete(delete))
+            .route("/v1/responses/{id}/cancel", post(cancel))
+            .route("/v1/responses/{id}/input_items", get(input_items))
+            .with_state(state.clone());
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Ok(Self { url, state, task })
+    }
+
+    /// Base URL to hand to `OpenAIClient::with_base_url` or `OPENAI_BASE_URL`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A client pointed at this server
+    pub fn client(&self) -> OpenAIClient {
+        OpenAIClient::new("sk-mock".to_string()).with_base_url(self.url.clone())
+    }
+
+    /// Queue the reply for the next `POST /v1/responses`
+    pub fn push(&self, reply: MockReply) -> &Self {
+        self.state.lock().unwrap().replies.push_back(reply);
+        self
+    }
+
+    /// Make `response` available to the retrieval endpoints
+    pub fn store(&self, response: Value) {
+        let id = response["id"].as_str().unwrap_or_default().to_string();
+        let stored = Stored {
+            states: VecDeque::from([response]),
+            input: json!([]),
+        };
+        self.state.lock().unwrap().stored.insert(id, stored);
+    }
+
+    /// Every request received so far, in order
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn last_request(&self) -> Option<ReceivedRequest> {
+        self.state.lock().unwrap().requests.last().cloned()
+    }
+
+    pub fn assert_request_count(&self, expected: usize) {
+        let requests = self.requests();
+        assert_eq!(
+            requests.len(),
+            expected,
+            "expected {} requests, received: {:?}",
+            expected,
+            requests
+                .iter()
+                .map(|request| format!("{} {}", request.method, request.path))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Panics if scripted replies were never requested
+    pub fn assert_replies_used(&self) {
+        let left = self.state.lock().unwrap().replies.len();
+        assert_eq!(left, 0, "{} scripted replies were not used", left);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn record(state: &Shared, method: &str, path: String, headers: &HeaderMap, body: Value) {
+    let headers = headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    state.lock().unwrap().requests.push(ReceivedRequest {
+        method: method.to_string(),
+        path,
+        headers,
+        body,
+    });
+}
+
+async fn create(State(state): State<Shared>, headers: HeaderMap, body: Bytes) -> Response {
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    record(
+        &state,
+        "POST",
+        "/v1/responses".to_string(),
+        &headers,
+        request.clone(),
+    );
+    if !request.is_object() {
+        return error(
+            400,
+            "invalid_request_error",
+            "Request body is not a JSON object",
+        );
+    }
+
+    let mut state = state.lock().unwrap();
+    let Some(reply) = state.replies.pop_front() else {
+        return error(
+            500,
+            "server_error",
+            "The mock server has no scripted reply left",
+        );
+    };
+    let id = state.next_id("resp");
+    let store = request["store"] != json!(false);
+    let keep = |state: &mut MockState, states: Vec<Value>| {
+        if store {
+            let stored = Stored {
+                states: states.into(),
+                input: request["input"].clone(),
+            };
+            state.stored.insert(id.clone(), stored);
+        }
+    };
+
+    match reply {
+        MockReply::Response(mut response) => {
+            fill(&mut response, &id, &request);
+            keep(&mut state, vec![response.clone()]);
+            json_response(StatusCode::OK, &response)
+        }
+        MockReply::Background {
+            mut response,
+            polls,
+        } => {
+            fill(&mut response, &id, &request);
+            let pending = |status: &str| {
+                let mut pending = response.clone();
+                pending["status"] = json!(status);
+                pending["output"] = json!([]);
+                pending["usage"] = Value::Null;
+                pending
+            };
+            let queued = pending("queued");
+            let mut states = vec![pending("in_progress"); polls];
+            states.push(response.clone());
+            keep(&mut state, states);
+            json_response(StatusCode::OK, &queued)
+        }
+        MockReply::Stream(mut events) => {
+            for event in events.iter_mut() {
+                if event.data["response"].is_object() {
+                    fill(&mut event.data["response"], &id, &request);
+                }
+            }
+            if let Some(last) = events.last() {
+                keep(&mut state, vec![last.data["response"].clone()]);
+            }
+            let body: String = events.iter().map(|event| event.encode()).collect();
+            (
+                [
+                    ("content-type", "text/event-stream"),
+                    ("x-request-id", REQUEST_ID),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        MockReply::Error {
+            status,
+            body,
+            headers,
+        } => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            let mut response = json_response(status, &body);
+            for (name, value) in headers {
+                if let (Ok(name), Ok(value)) = (name.parse(), value.parse()) {
+                    response
+                        .headers_mut()
+                        .insert::<axum::http::HeaderName>(name, value);
+                }
+            }
+            response
+        }
+    }
+}
+
+async fn retrieve(
+    State(state): State<Shared>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    record(
+        &state,
+        "GET",
+        format!("/v1/responses/{}", id),
+        &headers,
+        Value::Null,
+    );
+    let mut state = state.lock().unwrap();
+    let Some(stored) = state.stored.get_mut(&id) else {
+        return not_found(&id);
+    };
+    let current = if stored.states.len() > 1 {
+        stored.states.pop_front().unwrap()
+    } else {
+        stored.states[0].clone()
+    };
+    json_response(StatusCode::OK, &current)
+}
+
+async fn delete(
+    State(state): State<Shared>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    record(
+        &state,
+        "DELETE",
+        format!("/v1/responses/{}", id),
+        &headers,
+        Value::Null,
+    );
+    match state.lock().unwrap().stored.remove(&id) {
+        Some(_) => json_response(
+            StatusCode::OK,
+            &json!({ "id": id, "object": "response.deleted", "deleted": true }),
+        ),
+        None => not_found(&id),
+    }
+}
+
+async fn cancel(
+    State(state): State<Shared>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let path = format!("/v1/responses/{}/cancel", id);
+    record(&state, "POST", path, &headers, Value::Null);
+    let mut state = state.lock().unwrap();
+    let Some(stored) = state.stored.get_mut(&id) else {
+        return not_found(&id);
+    };
+    let mut current = stored.states[0].clone();
+    if matches!(current["status"].as_str(), Some("queued" | "in_progress")) {
+        current["status"] = json!("cancelled");
+        stored.states = VecDeque::from([current.clone()]);
+    }
+    json_response(StatusCode::OK, &current)
+}
+
+async fn input_items(
+    State(state): State<Shared>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let path = format!("/v1/responses/{}/input_items", id);
+    record(&state, "GET", path, &headers, Value::Null);
+    let state = state.lock().unwrap();
+    let Some(stored) = state.stored.get(&id) else {
+        return not_found(&id);
+    };
+    let data = match &stored.input {
+        Value::String(text) => json!([{
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": text }]
+        }]),
+        Value::Array(items) => json!(items),
+        _ => json!([]),
+    };
+    json_response(
+        StatusCode::OK,
+        &json!({ "object": "list", "data": data, "has_more": false }),
+    )
+}
+
+// Fill in what a scripted response left out, as the API would
+fn fill(response: &mut Value, id: &str, request: &Value) {
+    let Some(object) = response.as_object_mut() else {
+        return;
+    };
+    object.entry("id").or_insert_with(|| json!(id));
+    for field in ["model", "instructions", "previous_response_id", "metadata"] {
+        if !request[field].is_null() {
+            object
+                .entry(field)
+                .or_insert_with(|| request[field].clone());
+        }
+    }
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response {
+    (
+        status,
+        [
+            ("content-type", "application/json"),
+            ("x-request-id", REQUEST_ID),
+        ],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+fn error(status: u16, error_type: &str, message: &str) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = json!({
+        "error": { "message": message, "type": error_type, "param": null, "code": null }
+    });
+    json_response(status, &body)
+}
+
+fn not_found(id: &str) -> Response {
+    let message = format!("Response with id '{}' not found.", id);
+    error(404, "invalid_request_error", &message)
+}
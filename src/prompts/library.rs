@@ -0,0 +1,255 @@
+use crate::config::file::default_prompts_dir;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Front matter of a local prompt
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct PromptMeta {
+    pub description: Option<String>,
+    pub version: Option<String>,
+    /// Declared variables; placeholders in the template need not be declared
+    pub variables: BTreeMap<String, VariableSpec>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct VariableSpec {
+    pub description: Option<String>,
+    /// Value used when none is given; variables without one are required
+    pub default: Option<String>,
+}
+
+// A `.toml` prompt keeps its template next to the front matter fields
+#[derive(Deserialize)]
+struct TomlPrompt {
+    #[serde(flatten)]
+    meta: PromptMeta,
+    template: String,
+}
+
+/// A prompt from the local library, with `{{name}}` placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalPrompt {
+    pub name: String,
+    pub meta: PromptMeta,
+    pub template: String,
+}
+
+impl LocalPrompt {
+    /// Read a `.toml` or `.md` prompt; its name is the file stem
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow!("Invalid prompt file name {}", path.display()))?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read prompt {}", path.display()))?;
+
+        let prompt = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(name, &contents),
+            Some("md") => Self::from_markdown(name, &contents),
+            _ => bail!("Prompt files must end in .toml or .md"),
+        };
+        prompt.with_context(|| format!("Invalid prompt {}", path.display()))
+    }
+
+    pub fn from_toml(name: &str, contents: &str) -> Result<Self> {
+        let prompt: TomlPrompt = toml::from_str(contents)?;
+        Ok(Self {
+            name: name.to_string(),
+            meta: prompt.meta,
+            template: prompt.template,
+        })
+    }
+
+    /// Markdown with optional TOML front matter between `+++` lines. YAML
+    /// front matter between `---` lines is not supported and is rejected.
+    pub fn from_markdown(name: &str, contents: &str) -> Result<Self> {
+        let (meta, body) = match split_front_matter(contents) {
+            Some(("---", _, _)) => bail!(
+                "Prompt '{}' has `---` (YAML) front matter; use TOML between `+++` lines",
+                name
+            ),
+            Some((_, front_matter, body)) => {
+                let meta = toml::from_str(front_matter)
+                    .with_context(|| format!("Invalid TOML front matter in prompt '{}'", name))?;
+                (meta, body)
+            }
+            None => (PromptMeta::default(), contents),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            meta,
+            template: body.trim().to_string(),
+        })
+    }
+
+    /// Placeholder names in order of first appearance
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (_, name) in placeholders(&self.template) {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Substitute `vars` (then declared defaults) for every placeholder.
+    /// Unknown variables and placeholders left without a value are errors.
+    pub fn render(&self, vars: &[(String, String)]) -> Result<String> {
+        let names = self.placeholders();
+
+        let mut values: BTreeMap<&str, &str> = self
+            .meta
+            .variables
+            .iter()
+            .filter_map(|(name, spec)| Some((name.as_str(), spec.default.as_deref()?)))
+            .collect();
+        for (name, value) in vars {
+            if !names.contains(name) && !self.meta.variables.contains_key(name) {
+                bail!("Prompt '{}' has no variable '{}'", self.name, name);
+            }
+            values.insert(name, value);
+        }
+
+        let missing: Vec<&str> = names
+            .iter()
+            .map(String::as_str)
+            .filter(|name| !values.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Missing value for {} in prompt '{}' (use --var NAME=VALUE)",
+                missing.join(", "),
+                self.name
+            );
+        }
+
+        let mut rendered = String::with_capacity(self.template.len());
+        let mut last = 0;
+        for ((start, end), name) in placeholders(&self.template) {
+            rendered.push_str(&self.template[last..start]);
+            rendered.push_str(values[name]);
+            last = end;
+        }
+        rendered.push_str(&self.template[last..]);
+        Ok(rendered)
+    }
+}
+
+// Delimiter, front matter and body of a document that opens with `+++` or `---`
+fn split_front_matter(contents: &str) -> Option<(&str, &str, &str)> {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let first_line = contents.lines().next()?;
+    let delimiter = first_line.trim_end();
+    if delimiter != "+++" && delimiter != "---" {
+        return None;
+    }
+
+    let after_open = &contents[first_line.len()..];
+    let after_open = after_open.strip_prefix('\n').unwrap_or(after_open);
+    let mut offset = 0;
+    for line in after_open.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let body = &after_open[offset + line.len()..];
+            return Some((delimiter, &after_open[..offset], body));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+// Byte ranges and names of `{{ name }}` placeholders; braces around anything
+// that is not a plain identifier are left as text
+fn placeholders(template: &str) -> Vec<((usize, usize), &str)> {
+    let mut found = Vec::new();
+    let mut search_from = 0;
+    while let Some(start) = template[search_from..].find("{{").map(|i| search_from + i) {
+        let Some(end) = template[start + 2..].find("}}").map(|i| start + 2 + i) else {
+            break;
+        };
+        let name = template[start + 2..end].trim();
+        let is_identifier = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if is_identifier {
+            found.push(((start, end + 2), name));
+            search_from = end + 2;
+        } else {
+            search_from = start + 2;
+        }
+    }
+    found
+}
+
+/// Directory of `.toml` and `.md` prompt files
+pub struct PromptLibrary {
+    dir: PathBuf,
+}
+
+impl PromptLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Library in the default prompts directory
+    pub fn open_default() -> Result<Self> {
+        let dir =
+            default_prompts_dir().ok_or_else(|| anyhow!("Cannot locate a prompts directory"))?;
+        Ok(Self::new(dir))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// All prompts sorted by name; a missing directory is an empty library
+    pub fn list(&self) -> Result<Vec<LocalPrompt>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()))
+            }
+        };
+
+        let mut prompts = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if is_prompt_file(&path) {
+                prompts.push(LocalPrompt::load(&path)?);
+            }
+        }
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(prompts)
+    }
+
+    /// The prompt stored as `NAME.toml` or `NAME.md`
+    pub fn get(&self, name: &str) -> Result<LocalPrompt> {
+        for extension in ["toml", "md"] {
+            let path = self.dir.join(format!("{}.{}", name, extension));
+            if path.is_file() {
+                return LocalPrompt::load(&path);
+            }
+        }
+        Err(anyhow!(
+            "No prompt named '{}' in {}",
+            name,
+            self.dir.display()
+        ))
+    }
+}
+
+fn is_prompt_file(path: &Path) -> bool {
+    path.is_file()
+        && matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("toml" | "md")
+        )
+}
@@ -0,0 +1,224 @@
+use crate::api::errors::OpenAIError;
+use crate::api::middleware::Middleware;
+use crate::api::models::{ResponseApiResponse, ResponseRequest, ServiceTier};
+use crate::api::pricing::PricingTable;
+use crate::config::file::default_data_dir;
+use crate::utils::jsonl;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LEDGER_FILE: &str = "usage.jsonl";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Profile recorded for calls made without `--profile`
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Token usage and cost of one API call, stored as a line of JSON
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub timestamp: u64,
+    pub response_id: String,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTier>,
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub cached_tokens: u32,
+    pub output_tokens: u32,
+    #[serde(default)]
+    pub reasoning_tokens: u32,
+    /// USD; `None` when the model has no known price
+    #[serde(default)]
+    pub cost: Option<f64>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    pub profile: String,
+}
+
+impl LedgerEntry {
+    pub fn new(
+        response: &ResponseApiResponse,
+        profile: &str,
+        metadata: BTreeMap<String, String>,
+    ) -> Self {
+        let usage = &response.usage;
+        Self {
+            timestamp: match response.created_at {
+                0 => now(),
+                created_at => created_at,
+            },
+            response_id: response.id.clone(),
+            model: response.model.clone(),
+            service_tier: response.service_tier.clone(),
+            input_tokens: usage.input_tokens,
+            cached_tokens: usage
+                .input_tokens_details
+                .as_ref()
+                .and_then(|details| details.cached_tokens)
+                .unwrap_or_default(),
+            output_tokens: usage.output_tokens,
+            reasoning_tokens: usage
+                .output_tokens_details
+                .as_ref()
+                .and_then(|details| details.reasoning_tokens)
+                .unwrap_or_default(),
+            cost: response.cost.map(|cost| cost.total),
+            metadata,
+            profile: profile.to_string(),
+        }
+    }
+
+    /// UTC day of the call as `YYYY-MM-DD`
+    pub fn day(&self) -> String {
+        utc_date(self.timestamp)
+    }
+
+    /// UTC month of the call as `YYYY-MM`
+    pub fn month(&self) -> String {
+        self.day()[..7].to_string()
+    }
+}
+
+/// Append-only record of every call in a JSONL file
+pub struct Ledger {
+    path: PathBuf,
+}
+
+impl Ledger {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Ledger file in the default data directory
+    pub fn open_default() -> Result<Self> {
+        let dir = default_data_dir().ok_or_else(|| anyhow!("Cannot locate a data directory"))?;
+        Ok(Self::new(dir.join(LEDGER_FILE)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &LedgerEntry) -> Result<()> {
+        jsonl::append(&self.path, entry)
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Result<Vec<LedgerEntry>> {
+        jsonl::read(&self.path)
+    }
+
+    /// Spending so far on the UTC day and in the UTC month of `timestamp`
+    pub fn spending(&self, timestamp: u64) -> Result<Spending> {
+        let day = utc_date(timestamp);
+        let month = &day[..7];
+
+        let mut spending = Spending::default();
+        for entry in self.entries()? {
+            let entry_day = entry.day();
+            let cost = entry.cost.unwrap_or_default();
+            if entry_day[..7] == *month {
+                spending.month += cost;
+                if entry_day == day {
+                    spending.day += cost;
+                }
+            }
+        }
+        Ok(spending)
+    }
+}
+
+/// Middleware that appends an entry for every API call a client makes,
+/// including the follow-ups behind continuations, MCP approvals and tool
+/// results. A failed write does not fail the call; it is kept for
+/// `take_failures`.
+pub struct UsageRecorder {
+    ledger: Ledger,
+    pricing: PricingTable,
+    profile: String,
+    metadata: BTreeMap<String, String>,
+    failures: Mutex<Vec<anyhow::Error>>,
+}
+
+impl UsageRecorder {
+    pub fn new(
+        ledger: Ledger,
+        pricing: PricingTable,
+        profile: &str,
+        metadata: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            ledger,
+            pricing,
+            profile: profile.to_string(),
+            metadata,
+            failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Errors from entries that could not be written, oldest first
+    pub fn take_failures(&self) -> Vec<anyhow::Error> {
+        std::mem::take(&mut *self.failures.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl Middleware for UsageRecorder {
+    async fn after_response(
+        &self,
+        _request: &ResponseRequest,
+        response: &mut ResponseApiResponse,
+    ) -> Result<(), OpenAIError> {
+        // Cache hits made no API call, so they cost nothing
+        if response.cached {
+            return Ok(());
+        }
+        let mut entry = LedgerEntry::new(response, &self.profile, self.metadata.clone());
+        entry.cost = self.pricing.cost(response).map(|cost| cost.total);
+        if let Err(e) = self.ledger.append(&entry) {
+            self.failures.lock().unwrap().push(e);
+        }
+        Ok(())
+    }
+}
+
+/// USD spent in the current day and month
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Spending {
+    pub day: f64,
+    pub month: f64,
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// `YYYY-MM-DD` of a Unix timestamp in UTC
+pub fn utc_date(timestamp: u64) -> String {
+    // Days to civil date, from Howard Hinnant's `civil_from_days`
+    let days = (timestamp / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,147 @@
+use crate::usage::ledger::LedgerEntry;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// How ledger entries are grouped in a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupBy {
+    Day,
+    Month,
+    Model,
+    Profile,
+    /// Value of a metadata key; entries without it are grouped under `-`
+    Metadata(String),
+}
+
+impl GroupBy {
+    pub fn key(&self, entry: &LedgerEntry) -> String {
+        match self {
+            GroupBy::Day => entry.day(),
+            GroupBy::Month => entry.month(),
+            GroupBy::Model => entry.model.clone(),
+            GroupBy::Profile => entry.profile.clone(),
+            GroupBy::Metadata(key) => entry
+                .metadata
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(GroupBy::Day),
+            "month" => Ok(GroupBy::Month),
+            "model" => Ok(GroupBy::Model),
+            "profile" => Ok(GroupBy::Profile),
+            _ => match s.strip_prefix("metadata:") {
+                Some(key) if !key.is_empty() => Ok(GroupBy::Metadata(key.to_string())),
+                _ => Err(format!(
+                    "expected day, month, model, profile or metadata:KEY, got '{}'",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Day => write!(f, "day"),
+            GroupBy::Month => write!(f, "month"),
+            GroupBy::Model => write!(f, "model"),
+            GroupBy::Profile => write!(f, "profile"),
+            GroupBy::Metadata(key) => write!(f, "{}", key),
+        }
+    }
+}
+
+/// Check that `value` is a `YYYY-MM-DD` day
+pub fn parse_day(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let well_formed = bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, byte)| i == 4 || i == 7 || byte.is_ascii_digit());
+    if well_formed {
+        Ok(value.to_string())
+    } else {
+        Err(format!("expected a day as YYYY-MM-DD, got '{}'", value))
+    }
+}
+
+/// Totals for one group of calls
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ReportRow {
+    pub key: String,
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub cached_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+}
+
+impl ReportRow {
+    fn add(&mut self, entry: &LedgerEntry) {
+        self.calls += 1;
+        self.input_tokens += u64::from(entry.input_tokens);
+        self.cached_tokens += u64::from(entry.cached_tokens);
+        self.output_tokens += u64::from(entry.output_tokens);
+        self.cost += entry.cost.unwrap_or_default();
+    }
+}
+
+/// One row per group, sorted by key, for entries between `since` and
+/// `until` (inclusive `YYYY-MM-DD` UTC days)
+pub fn aggregate(
+    entries: &[LedgerEntry],
+    group_by: &GroupBy,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Vec<ReportRow> {
+    let mut rows: BTreeMap<String, ReportRow> = BTreeMap::new();
+    for entry in entries {
+        let day = entry.day();
+        if since.is_some_and(|since| day.as_str() < since)
+            || until.is_some_and(|until| day.as_str() > until)
+        {
+            continue;
+        }
+        let key = group_by.key(entry);
+        rows.entry(key.clone())
+            .or_insert_with(|| ReportRow {
+                key,
+                ..Default::default()
+            })
+            .add(entry);
+    }
+    rows.into_values().collect()
+}
+
+/// Sum of all rows, keyed `total`
+pub fn total(rows: &[ReportRow]) -> ReportRow {
+    rows.iter().fold(
+        ReportRow {
+            key: "total".to_string(),
+            ..Default::default()
+        },
+        |mut total, row| {
+            total.calls += row.calls;
+            total.input_tokens += row.input_tokens;
+            total.cached_tokens += row.cached_tokens;
+            total.output_tokens += row.output_tokens;
+            total.cost += row.cost;
+            total
+        },
+    )
+}
@@ -0,0 +1,139 @@
+use crate::api::models::{Annotation, ContentType, ResponseApiResponse};
+use std::io::IsTerminal;
+
+// A cited source, numbered by its position in the list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    pub title: String,
+    pub url: Option<String>,
+    key: String,
+}
+
+impl Source {
+    fn from_annotation(annotation: &Annotation) -> Option<Self> {
+        match annotation {
+            Annotation::UrlCitation { url, title, .. } => Some(Self {
+                title: title.clone().unwrap_or_else(|| url.clone()),
+                url: Some(url.clone()),
+                key: url.clone(),
+            }),
+            Annotation::FileCitation {
+                file_id, filename, ..
+            } => Some(Self {
+                title: filename.clone().unwrap_or_else(|| file_id.clone()),
+                url: None,
+                key: file_id.clone(),
+            }),
+            Annotation::ContainerFileCitation {
+                container_id,
+                file_id,
+                filename,
+                ..
+            } => Some(Self {
+                title: filename.clone().unwrap_or_else(|| file_id.clone()),
+                url: None,
+                key: format!("{}/{}", container_id, file_id),
+            }),
+            Annotation::FilePath { file_id, .. } => Some(Self {
+                title: file_id.clone(),
+                url: None,
+                key: file_id.clone(),
+            }),
+            Annotation::Other(_) => None,
+        }
+    }
+}
+
+/// Response text with inline `[n]` citation markers, plus the numbered sources
+pub fn extract_cited_content(response: &ResponseApiResponse) -> (String, Vec<Source>) {
+    let mut sources = Vec::new();
+    let mut text_parts = Vec::new();
+
+    for output_message in response.messages() {
+        for content in &output_message.content {
+            match content {
+                ContentType::Text { text } => text_parts.push(text.clone()),
+                ContentType::Refusal { refusal } => text_parts.push(refusal.clone()),
+                ContentType::Other(_) => {}
+                ContentType::OutputText {
+                    text, annotations, ..
+                } => text_parts.push(cite_text(text, annotations, &mut sources)),
+            }
+        }
+    }
+
+    if text_parts.is_empty() {
+        ("No content available".to_string(), sources)
+    } else {
+        (text_parts.join(" "), sources)
+    }
+}
+
+/// Insert a `[n]` marker for each annotation, numbering sources in order of
+/// first appearance and reusing the number for repeated sources
+pub fn cite_text(text: &str, annotations: &[Annotation], sources: &mut Vec<Source>) -> String {
+    let mut markers: Vec<(usize, usize)> = Vec::new(); // (char index, source number)
+    for annotation in annotations {
+        let (Some(index), Some(source)) = (
+            annotation.marker_index(),
+            Source::from_annotation(annotation),
+        ) else {
+            continue;
+        };
+
+        let number = match sources.iter().position(|known| known.key == source.key) {
+            Some(position) => position + 1,
+            None => {
+                sources.push(source);
+                sources.len()
+            }
+        };
+        if !markers.contains(&(index, number)) {
+            markers.push((index, number));
+        }
+    }
+    markers.sort();
+
+    let char_count = text.chars().count();
+    let mut cited = String::with_capacity(text.len() + markers.len() * 4);
+    let mut markers = markers.into_iter().peekable();
+    for (position, c) in text.chars().enumerate() {
+        while let Some((_, number)) = markers.next_if(|(index, _)| *index == position) {
+            cited.push_str(&format!("[{}]", number));
+        }
+        cited.push(c);
+    }
+    // Markers at or past the end of the text
+    for (index, number) in markers {
+        if index >= char_count {
+            cited.push_str(&format!("[{}]", number));
+        }
+    }
+
+    cited
+}
+
+/// Whether stdout is a terminal that can render OSC 8 hyperlinks
+pub fn supports_hyperlinks() -> bool {
+    std::io::stdout().is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// Wrap `text` in an OSC 8 terminal hyperlink to `url`
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Numbered "Sources" footer lines, linking titles when `hyperlinks` is set
+pub fn format_sources(sources: &[Source], hyperlinks: bool) -> Vec<String> {
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| match &source.url {
+            Some(url) if hyperlinks => format!("[{}] {}", index + 1, hyperlink(&source.title, url)),
+            Some(url) if *url != source.title => {
+                format!("[{}] {} ({})", index + 1, source.title, url)
+            }
+            _ => format!("[{}] {}", index + 1, source.title),
+        })
+        .collect()
+}
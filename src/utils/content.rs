@@ -9,10 +9,12 @@ pub fn extract_response_content(response: &ResponseApiResponse) -> String {
     let mut text_parts = Vec::new();
 
     // Extract content from all output messages
-    for output_message in &response.output {
+    for output_message in response.messages() {
         for content in &output_message.content {
             match content {
                 ContentType::Text { text } => text_parts.push(text.clone()),
+                ContentType::Refusal { refusal } => text_parts.push(refusal.clone()),
+                ContentType::Other(_) => {}
                 ContentType::OutputText { text, .. } => text_parts.push(text.clone()),
             }
         }
@@ -31,6 +33,8 @@ pub fn extract_content_from_array(content_array: &[ContentType]) -> String {
     for content in content_array {
         match content {
             ContentType::Text { text } => text_parts.push(text.clone()),
+            ContentType::Refusal { refusal } => text_parts.push(refusal.clone()),
+            ContentType::Other(_) => {}
             ContentType::OutputText { text, .. } => text_parts.push(text.clone()),
         }
     }
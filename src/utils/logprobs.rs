@@ -0,0 +1,45 @@
+use crate::api::models::{ContentType, ResponseApiResponse, TokenLogprob};
+
+// Probability thresholds separating confidence levels
+const HIGH_CONFIDENCE: f64 = 0.9;
+const MEDIUM_CONFIDENCE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    pub fn from_probability(probability: f64) -> Self {
+        if probability >= HIGH_CONFIDENCE {
+            Confidence::High
+        } else if probability >= MEDIUM_CONFIDENCE {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        }
+    }
+}
+
+/// Per-token log probabilities across all output text, in order
+pub fn collect_logprobs(response: &ResponseApiResponse) -> Vec<&TokenLogprob> {
+    response
+        .messages()
+        .flat_map(|message| &message.content)
+        .flat_map(|content| match content {
+            ContentType::OutputText { logprobs, .. } => logprobs.as_slice(),
+            _ => &[],
+        })
+        .collect()
+}
+
+/// exp of the negative mean log probability; `None` without any tokens
+pub fn perplexity(tokens: &[&TokenLogprob]) -> Option<f64> {
+    if tokens.is_empty() {
+        return None;
+    }
+    let total: f64 = tokens.iter().map(|token| token.logprob).sum();
+    Some((-total / tokens.len() as f64).exp())
+}
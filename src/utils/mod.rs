@@ -1 +1,5 @@
+pub mod citations;
 pub mod content;
+pub mod logprobs;
+pub mod tokens;
+pub mod jsonl;
@@ -0,0 +1,207 @@
+use crate::api::models::{
+    InputItem, InputType, PromptInput, PromptVariable, ReasoningSummaryPart, ResponseRequest,
+};
+use crate::api::pricing::in_family;
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton};
+
+// Tokens the API adds around each message for its role and delimiters
+const MESSAGE_OVERHEAD: usize = 4;
+
+// Rough cost of an image input: one high-detail 512px tile plus the base
+// charge. Files are not counted; their cost depends on the extracted text.
+const IMAGE_TOKEN_ESTIMATE: usize = 255;
+
+// Context windows by model family; the longest matching family wins, as for
+// prices
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("gpt-5", 400_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4.5", 128_000),
+    ("gpt-4o", 128_000),
+    ("chatgpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4-0125-preview", 128_000),
+    ("gpt-4-1106-preview", 128_000),
+    ("gpt-4-vision-preview", 128_000),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("o1-mini", 128_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4-mini", 200_000),
+    ("codex-mini", 200_000),
+];
+
+/// BPE vocabulary used to split text into tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Encoding {
+    #[value(name = "o200k_base")]
+    O200kBase,
+    #[value(name = "cl100k_base")]
+    Cl100kBase,
+}
+
+impl Encoding {
+    /// Encoding for `model`; models newer than this table use o200k_base
+    pub fn for_model(model: &str) -> Self {
+        let is_legacy = model.starts_with("gpt-3.5")
+            || (model.starts_with("gpt-4")
+                && !model.starts_with("gpt-4o")
+                && !model.starts_with("gpt-4."));
+        if is_legacy {
+            Encoding::Cl100kBase
+        } else {
+            Encoding::O200kBase
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::O200kBase => "o200k_base",
+            Encoding::Cl100kBase => "cl100k_base",
+        }
+    }
+
+    /// Number of tokens in `text`, with special-token markers counted as text
+    pub fn count_tokens(&self, text: &str) -> usize {
+        let bpe = match self {
+            Encoding::O200kBase => o200k_base_singleton(),
+            Encoding::Cl100kBase => cl100k_base_singleton(),
+        };
+        bpe.encode_ordinary(text).len()
+    }
+}
+
+/// Context window of `model` in tokens, if the model is known. A family
+/// matches the model itself and its `-` suffixed variants, as in the pricing
+/// table.
+pub fn context_window(model: &str) -> Option<usize> {
+    CONTEXT_WINDOWS
+        .iter()
+        .filter(|(family, _)| in_family(model, family))
+        .max_by_key(|(family, _)| family.len())
+        .map(|(_, window)| *window)
+}
+
+/// Estimated tokens of an input, counting each message's overhead
+pub fn count_input_tokens(input: &InputType, encoding: Encoding) -> usize {
+    match input {
+        InputType::Text(text) => MESSAGE_OVERHEAD + encoding.count_tokens(text),
+        InputType::Array(values) => values
+            .iter()
+            .map(|value| MESSAGE_OVERHEAD + count_value_tokens(value, encoding))
+            .sum(),
+        InputType::Items(items) => items
+            .iter()
+            .map(|item| MESSAGE_OVERHEAD + count_item_tokens(item, encoding))
+            .sum(),
+    }
+}
+
+/// Estimated tokens of a typed input item
+pub fn count_item_tokens(item: &InputItem, encoding: Encoding) -> usize {
+    match item {
+        InputItem::McpApprovalResponse { reason, .. } => reason
+            .as_deref()
+            .map_or(0, |reason| encoding.count_tokens(reason)),
+        InputItem::FunctionCallOutput { output, .. } => encoding.count_tokens(output),
+        // Encrypted reasoning is opaque; only its summary can be counted
+        InputItem::Reasoning(reasoning) => reasoning
+            .summary
+            .iter()
+            .map(|ReasoningSummaryPart::SummaryText { text }| encoding.count_tokens(text))
+            .sum(),
+    }
+}
+
+// Untyped input (messages with content parts): count the text and
+// estimate images; everything else is structure
+fn count_value_tokens(value: &serde_json::Value, encoding: Encoding) -> usize {
+    match value {
+        serde_json::Value::String(text) => encoding.count_tokens(text),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .map(|value| count_value_tokens(value, encoding))
+            .sum(),
+        serde_json::Value::Object(map) => {
+            if map.get("type").and_then(|kind| kind.as_str()) == Some("input_image") {
+                return IMAGE_TOKEN_ESTIMATE;
+            }
+            ["text", "content", "output"]
+                .iter()
+                .filter_map(|key| map.get(*key))
+                .map(|value| count_value_tokens(value, encoding))
+                .sum()
+        }
+        _ => 0,
+    }
+}
+
+fn count_variable_tokens(variable: &PromptVariable, encoding: Encoding) -> usize {
+    match variable {
+        PromptVariable::Text(text) | PromptVariable::Input(PromptInput::Text { text }) => {
+            encoding.count_tokens(text)
+        }
+        PromptVariable::Input(PromptInput::Image { .. }) => IMAGE_TOKEN_ESTIMATE,
+        PromptVariable::Input(_) => 0,
+    }
+}
+
+/// Estimated input tokens of a request: instructions, input, tool
+/// definitions and stored prompt variables. Context carried over through
+/// `previous_response_id` and the stored prompt's own text are not included.
+pub fn count_request_tokens(request: &ResponseRequest) -> usize {
+    let encoding = Encoding::for_model(request.model.as_deref().unwrap_or_default());
+
+    let instructions = request.instructions.as_deref().map_or(0, |instructions| {
+        MESSAGE_OVERHEAD + encoding.count_tokens(instructions)
+    });
+    let input = request
+        .input
+        .as_ref()
+        .map_or(0, |input| count_input_tokens(input, encoding));
+    let tools = request
+        .tools
+        .as_ref()
+        .filter(|tools| !tools.is_empty())
+        .and_then(|tools| serde_json::to_string(tools).ok())
+        .map_or(0, |tools| encoding.count_tokens(&tools));
+    let variables = request.prompt.as_ref().map_or(0, |prompt| {
+        prompt
+            .variables
+            .values()
+            .map(|variable| count_variable_tokens(variable, encoding))
+            .sum()
+    });
+
+    instructions + input + tools + variables
+}
+
+/// How much of a model's context window a request would use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextUsage {
+    pub input_tokens: usize,
+    pub max_output_tokens: usize,
+    pub window: usize,
+}
+
+impl ContextUsage {
+    /// Usage of `request`, or `None` when its model's window is unknown
+    pub fn of(request: &ResponseRequest) -> Option<Self> {
+        let window = context_window(request.model.as_deref()?)?;
+        Some(Self {
+            input_tokens: count_request_tokens(request),
+            max_output_tokens: request.max_output_tokens.unwrap_or_default() as usize,
+            window,
+        })
+    }
+
+    pub fn total(&self) -> usize {
+        self.input_tokens + self.max_output_tokens
+    }
+
+    pub fn exceeds_window(&self) -> bool {
+        self.total() > self.window
+    }
+}
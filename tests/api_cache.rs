@@ -0,0 +1,174 @@
+use openai_api::api::cache::{CacheScope, ResponseCache};
+use openai_api::api::models::*;
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_cache(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("openai-cli-cache-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn request(prompt: &str) -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text(prompt.to_string())
+        .temperature(0.0)
+        .build()
+}
+
+fn scope() -> CacheScope {
+    CacheScope::new("https://api.openai.com/v1", "sk-test")
+}
+
+fn response(status: &str) -> ResponseApiResponse {
+    serde_json::from_value(json!({
+        "id": "resp_1",
+        "status": status,
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": "Paris", "annotations": [] }]
+        }],
+        "usage": { "input_tokens": 10, "output_tokens": 2, "total_tokens": 12 }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_fingerprint_is_canonical() {
+    let a = request("Capital of France?");
+    let b = request("Capital of France?");
+    assert_eq!(
+        ResponseCache::fingerprint(&scope(), &a),
+        ResponseCache::fingerprint(&scope(), &b)
+    );
+    assert_eq!(ResponseCache::fingerprint(&scope(), &a).len(), 64);
+
+    assert_ne!(
+        ResponseCache::fingerprint(&scope(), &a),
+        ResponseCache::fingerprint(&scope(), &request("Capital of Spain?"))
+    );
+
+    // Metadata maps built in different orders hash the same
+    let with_metadata = |pairs: &[(&str, &str)]| {
+        let metadata: serde_json::Map<String, serde_json::Value> = pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .collect();
+        ResponseRequest::builder()
+            .model("gpt-4.1".to_string())
+            .metadata(metadata.into())
+            .build()
+    };
+    assert_eq!(
+        ResponseCache::fingerprint(&scope(), &with_metadata(&[("a", "1"), ("b", "2")])),
+        ResponseCache::fingerprint(&scope(), &with_metadata(&[("b", "2"), ("a", "1")]))
+    );
+
+    // Responses are not shared across servers or API keys
+    let other_key = CacheScope::new("https://api.openai.com/v1", "sk-other");
+    let other_server = CacheScope::new("http://localhost:8080/v1", "sk-test");
+    for other in [other_key, other_server] {
+        assert_ne!(
+            ResponseCache::fingerprint(&other, &a),
+            ResponseCache::fingerprint(&scope(), &a)
+        );
+    }
+    assert_eq!(
+        CacheScope::new("https://api.openai.com/v1/", "sk-test"),
+        scope()
+    );
+}
+
+#[test]
+fn test_bypass_rules() {
+    let cache = ResponseCache::new(temp_cache("bypass"));
+
+    assert!(cache.accepts(&request("Hi")));
+
+    let sampled = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("Hi".to_string())
+        .build();
+    assert!(!cache.accepts(&sampled));
+    assert!(cache.clone().force(true).accepts(&sampled));
+
+    let mut streamed = request("Hi");
+    streamed.stream = Some(true);
+    assert!(!cache.clone().force(true).accepts(&streamed));
+
+    let mut background = request("Hi");
+    background.background = Some(true);
+    assert!(!cache.clone().force(true).accepts(&background));
+}
+
+#[test]
+fn test_put_get_and_stats() {
+    let dir = temp_cache("roundtrip");
+    let cache = ResponseCache::new(dir.clone());
+    let request = request("Capital of France?");
+
+    assert!(cache.get(&scope(), &request).is_none());
+
+    let mut original = response("completed");
+    original.cost = Some(Default::default());
+    cache.put(&scope(), &request, &original).unwrap();
+
+    let hit = cache.get(&scope(), &request).unwrap();
+    assert!(hit.cached);
+    assert!(hit.cost.is_none());
+    assert_eq!(hit.id, "resp_1");
+    assert!(!original.cached);
+
+    // Only completed responses are stored
+    let other = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("Other".to_string())
+        .temperature(0.0)
+        .build();
+    cache
+        .put(&scope(), &other, &response("incomplete"))
+        .unwrap();
+    assert!(cache.get(&scope(), &other).is_none());
+
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.expired, 0);
+    assert!(stats.bytes > 0);
+    assert_eq!((stats.hits, stats.misses), (1, 2));
+
+    assert_eq!(cache.clear().unwrap(), 1);
+    assert_eq!(cache.stats().unwrap(), Default::default());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_ttl_and_size_limit() {
+    let dir = temp_cache("limits");
+
+    let expired = ResponseCache::new(dir.clone()).ttl(Duration::ZERO);
+    let request_a = request("a");
+    expired
+        .put(&scope(), &request_a, &response("completed"))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(1100));
+    assert_eq!(expired.stats().unwrap().expired, 1);
+    assert!(expired.get(&scope(), &request_a).is_none());
+    assert_eq!(expired.stats().unwrap().entries, 0);
+
+    // Room for a single entry: each new one evicts the previous
+    let tiny = ResponseCache::new(dir.clone()).max_bytes(1);
+    tiny.put(&scope(), &request("b"), &response("completed"))
+        .unwrap();
+    assert_eq!(tiny.stats().unwrap().entries, 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,265 @@
+use openai_api::api::cassette::{
+    Cassette, CassetteMode, Interaction, MatchMode, RecordedRequest, RecordedResponse,
+};
+use openai_api::api::errors::{CassetteError, OpenAIError, ValidationError};
+use openai_api::api::models::*;
+use openai_api::utils::content::extract_response_content;
+use openai_api::OpenAIClient;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const URL: &str = "https://api.openai.com/v1/responses";
+
+fn temp_cassette(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "openai-cli-cassette-{}-{}.json",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn fixture() -> String {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/responses/completed_text.json");
+    fs::read_to_string(path).unwrap()
+}
+
+fn request(prompt: &str) -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text(prompt.to_string())
+        .build()
+}
+
+fn interaction(prompt: &str, status: u16, body: String) -> Interaction {
+    Interaction {
+        request: RecordedRequest {
+            method: "POST".to_string(),
+            url: URL.to_string(),
+            headers: Default::default(),
+            body: serde_json::to_value(request(prompt)).unwrap(),
+        },
+        response: RecordedResponse {
+            status,
+            headers: [("x-request-id".to_string(), "req_123".to_string())].into(),
+            body,
+        },
+    }
+}
+
+// Writes a cassette the way record mode does
+fn write_cassette(path: &Path, interactions: Vec<Interaction>) {
+    let recorder = Cassette::record(path);
+    for interaction in interactions {
+        recorder.save(interaction).unwrap();
+    }
+}
+
+#[test]
+fn test_recorded_request_redacts_credentials() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "authorization",
+        HeaderValue::from_static("Bearer sk-secret"),
+    );
+    headers.insert("openai-organization", HeaderValue::from_static("org-1"));
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+    let recorded = RecordedRequest::new("POST", URL, &headers, json!({"model": "gpt-4.1"}));
+    assert_eq!(recorded.headers["authorization"], "[REDACTED]");
+    assert_eq!(recorded.headers["openai-organization"], "[REDACTED]");
+    assert_eq!(recorded.headers["content-type"], "application/json");
+    assert!(!serde_json::to_string(&recorded)
+        .unwrap()
+        .contains("sk-secret"));
+
+    // Remote MCP servers get their credentials through tool headers
+    let body = json!({
+        "model": "gpt-4.1",
+        "tools": [
+            {
+                "type": "mcp",
+                "server_label": "deepwiki",
+                "server_url": "https://mcp.deepwiki.com/mcp",
+                "headers": { "Authorization": "Bearer mcp-secret", "X-Team": "search" }
+            },
+            { "type": "web_search_preview" }
+        ]
+    });
+    let recorded = RecordedRequest::new("POST", URL, &headers, body);
+    let tool_headers = &recorded.body["tools"][0]["headers"];
+    assert_eq!(tool_headers["Authorization"], "[REDACTED]");
+    assert_eq!(tool_headers["X-Team"], "[REDACTED]");
+    assert_eq!(recorded.body["tools"][0]["server_label"], "deepwiki");
+    assert_eq!(
+        recorded.body["tools"][1],
+        json!({ "type": "web_search_preview" })
+    );
+}
+
+#[test]
+fn test_configured_headers_are_redacted() {
+    let path = temp_cassette("redact");
+    let mut headers = HeaderMap::new();
+    headers.insert("x-api-key", HeaderValue::from_static("gateway-secret"));
+    headers.insert("x-team", HeaderValue::from_static("search"));
+    let recorder = Cassette::record(&path).redact_headers(["X-Api-Key", " "]);
+    recorder
+        .save(Interaction {
+            request: RecordedRequest::new("POST", URL, &headers, json!({})),
+            ..interaction("Hi", 200, fixture())
+        })
+        .unwrap();
+
+    let recorded = &Cassette::replay(&path).interactions().unwrap()[0].request;
+    assert_eq!(recorded.headers["x-api-key"], "[REDACTED]");
+    assert_eq!(recorded.headers["x-team"], "search");
+    assert!(!fs::read_to_string(&path)
+        .unwrap()
+        .contains("gateway-secret"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_recorded_response_keeps_raw_body() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "content-type",
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert("set-cookie", HeaderValue::from_static("session=1"));
+    let stream = "event: response.created\ndata: {}\n\nevent: response.completed\ndata: {}\n\n";
+
+    let recorded = RecordedResponse::new(StatusCode::OK, &headers, stream.to_string());
+    assert_eq!(recorded.body, stream);
+    assert!(!recorded.headers.contains_key("set-cookie"));
+    assert_eq!(recorded.status(), StatusCode::OK);
+    assert_eq!(recorded.header_map()["content-type"], "text/event-stream");
+}
+
+#[test]
+fn test_save_appends_and_reloads() {
+    let path = temp_cassette("save");
+    write_cassette(
+        &path,
+        vec![
+            interaction("one", 200, fixture()),
+            interaction("two", 200, fixture()),
+        ],
+    );
+
+    let replay = Cassette::replay(&path);
+    assert_eq!(replay.mode(), CassetteMode::Replay);
+    assert_eq!(replay.interactions().unwrap().len(), 2);
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_serves_recorded_responses() {
+    let path = temp_cassette("replay");
+    write_cassette(&path, vec![interaction("Tell me a story", 200, fixture())]);
+
+    let client = OpenAIClient::new("any-key".to_string()).with_cassette(Cassette::replay(&path));
+    let response = client
+        .send_request(request("Tell me a story"))
+        .await
+        .unwrap();
+    assert_eq!(response.status, Some(ResponseStatus::Completed));
+    assert!(extract_response_content(&response).contains("unicorn"));
+
+    // Each interaction is served once
+    let error = client.send_request(request("Tell me a story")).await;
+    assert!(matches!(error, Err(OpenAIError::Cassette(_))));
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replayed_errors_are_typed() {
+    let path = temp_cassette("errors");
+    let body = json!({"error": {"message": "Slow down", "type": "requests", "code": "rate_limit_exceeded"}});
+    write_cassette(&path, vec![interaction("Hi", 429, body.to_string())]);
+
+    let client = OpenAIClient::new("any-key".to_string()).with_cassette(Cassette::replay(&path));
+    let error = client.send_request(request("Hi")).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::RateLimited { .. }));
+    assert_eq!(error.request_id(), Some("req_123"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_strict_and_loose_matching() {
+    let path = temp_cassette("matching");
+    write_cassette(
+        &path,
+        vec![
+            interaction("first", 200, fixture()),
+            interaction("second", 200, fixture()),
+        ],
+    );
+
+    // Strict replays in recorded order with identical bodies
+    let strict = OpenAIClient::new("any-key".to_string()).with_cassette(Cassette::replay(&path));
+    assert!(matches!(
+        strict.send_request(request("second")).await,
+        Err(OpenAIError::Cassette(_))
+    ));
+    assert!(strict.send_request(request("first")).await.is_ok());
+    assert!(strict.send_request(request("second")).await.is_ok());
+
+    // Loose ignores the body
+    let loose = OpenAIClient::new("any-key".to_string())
+        .with_cassette(Cassette::replay(&path).matching(MatchMode::Loose));
+    assert!(loose.send_request(request("something else")).await.is_ok());
+    assert!(loose.send_request(request("and another")).await.is_ok());
+    assert!(loose.send_request(request("one too many")).await.is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_missing_cassette_is_an_error() {
+    let path = temp_cassette("missing");
+    let client = OpenAIClient::new("any-key".to_string()).with_cassette(Cassette::replay(&path));
+    let error = client.send_request(request("Hi")).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::Cassette(_)));
+}
+
+// The only test in this file that sets environment variables
+#[test]
+fn test_client_from_env() {
+    std::env::set_var("OPENAI_API_KEY", "sk-env");
+    std::env::set_var("OPENAI_BASE_URL", "http://localhost:8080/v1/");
+    std::env::set_var("OPENAI_API_CASSETTE", temp_cassette("env"));
+    std::env::set_var("OPENAI_API_CASSETTE_MODE", "rewind");
+    let error = OpenAIClient::from_env().err().unwrap();
+    assert!(matches!(
+        error,
+        OpenAIError::Cassette(CassetteError::InvalidSetting(_))
+    ));
+
+    std::env::set_var("OPENAI_API_CASSETTE_MODE", "replay");
+    let client = OpenAIClient::from_env().unwrap();
+    assert_eq!(client.base_url(), "http://localhost:8080/v1");
+    // A plain client never reads the environment
+    let plain = OpenAIClient::new("sk-test".to_string());
+    assert_eq!(plain.base_url(), "https://api.openai.com/v1");
+
+    std::env::set_var("OPENAI_API_KEY", " ");
+    let error = OpenAIClient::from_env().err().unwrap();
+    assert!(matches!(
+        error,
+        OpenAIError::Validation(ValidationError::MissingApiKey)
+    ));
+    for name in [
+        "OPENAI_API_KEY",
+        "OPENAI_BASE_URL",
+        "OPENAI_API_CASSETTE",
+        "OPENAI_API_CASSETTE_MODE",
+    ] {
+        std::env::remove_var(name);
+    }
+}
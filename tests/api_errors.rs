@@ -1,5 +1,19 @@
 use openai_api::api::errors::*;
+use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::StatusCode;
+use std::time::Duration;
+
+fn error_body(message: &str, error_type: &str, code: Option<&str>, param: Option<&str>) -> String {
+    serde_json::json!({
+        "error": {
+            "message": message,
+            "type": error_type,
+            "code": code,
+            "param": param
+        }
+    })
+    .to_string()
+}
 
 #[test]
 fn test_api_error_parsing() {
@@ -16,16 +30,13 @@ fn test_api_error_parsing() {
     let error = OpenAIError::parse_api_error(error_json, StatusCode::UNAUTHORIZED);
 
     match error {
-        OpenAIError::ApiError {
-            message,
-            error_type,
-            code,
-        } => {
-            assert_eq!(message, "Invalid API key");
-            assert_eq!(error_type, "invalid_request_error");
-            assert_eq!(code, "invalid_api_key");
+        OpenAIError::Authentication(context) => {
+            assert_eq!(context.message, "Invalid API key");
+            assert_eq!(context.error_type.as_deref(), Some("invalid_request_error"));
+            assert_eq!(context.code.as_deref(), Some("invalid_api_key"));
+            assert_eq!(context.status, StatusCode::UNAUTHORIZED);
         }
-        _ => panic!("Expected ApiError variant"),
+        _ => panic!("Expected Authentication variant"),
     }
 }
 
@@ -35,10 +46,182 @@ fn test_http_error_parsing() {
     let error = OpenAIError::parse_api_error(error_text, StatusCode::INTERNAL_SERVER_ERROR);
 
     match error {
-        OpenAIError::HttpError { status, message } => {
-            assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
-            assert_eq!(message, "Server Error");
+        OpenAIError::ServerError(context) => {
+            assert_eq!(context.status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(context.message, "Server Error");
+        }
+        _ => panic!("Expected ServerError variant"),
+    }
+
+    let error = OpenAIError::parse_api_error("", StatusCode::BAD_GATEWAY);
+    assert_eq!(error.context().unwrap().message, "Bad Gateway");
+}
+
+#[test]
+fn test_classification_by_status() {
+    let classify = |status: StatusCode| {
+        OpenAIError::parse_api_error(&error_body("failed", "error", None, None), status)
+    };
+
+    assert!(matches!(
+        classify(StatusCode::FORBIDDEN),
+        OpenAIError::PermissionDenied(_)
+    ));
+    assert!(matches!(
+        classify(StatusCode::NOT_FOUND),
+        OpenAIError::NotFound(_)
+    ));
+    assert!(matches!(
+        classify(StatusCode::TOO_MANY_REQUESTS),
+        OpenAIError::RateLimited { .. }
+    ));
+    assert!(matches!(
+        classify(StatusCode::BAD_REQUEST),
+        OpenAIError::InvalidRequest(_)
+    ));
+    assert!(matches!(
+        classify(StatusCode::SERVICE_UNAVAILABLE),
+        OpenAIError::ServerError(_)
+    ));
+    assert!(matches!(
+        classify(StatusCode::CONFLICT),
+        OpenAIError::ApiError(_)
+    ));
+}
+
+#[test]
+fn test_classification_by_code() {
+    let quota = OpenAIError::parse_api_error(
+        &error_body(
+            "You exceeded your current quota",
+            "insufficient_quota",
+            Some("insufficient_quota"),
+            None,
+        ),
+        StatusCode::TOO_MANY_REQUESTS,
+    );
+    assert!(matches!(quota, OpenAIError::QuotaExceeded(_)));
+
+    let context_length = OpenAIError::parse_api_error(
+        &error_body(
+            "Your input exceeds the context window of this model",
+            "invalid_request_error",
+            Some("context_length_exceeded"),
+            Some("input"),
+        ),
+        StatusCode::BAD_REQUEST,
+    );
+    assert!(matches!(
+        context_length,
+        OpenAIError::ContextLengthExceeded(_)
+    ));
+
+    let filtered = OpenAIError::parse_api_error(
+        &error_body(
+            "Your request was rejected by the safety system",
+            "invalid_request_error",
+            Some("content_policy_violation"),
+            None,
+        ),
+        StatusCode::BAD_REQUEST,
+    );
+    assert!(matches!(filtered, OpenAIError::ContentFilter(_)));
+}
+
+#[test]
+fn test_invalid_request_keeps_param() {
+    let error = OpenAIError::parse_api_error(
+        &error_body(
+            "Unsupported value: 'temperature' does not support 0.5 with this model.",
+            "invalid_request_error",
+            Some("unsupported_value"),
+            Some("temperature"),
+        ),
+        StatusCode::BAD_REQUEST,
+    );
+
+    match &error {
+        OpenAIError::InvalidRequest(context) => {
+            assert_eq!(context.param.as_deref(), Some("temperature"));
         }
-        _ => panic!("Expected HttpError variant"),
+        _ => panic!("Expected InvalidRequest variant"),
     }
+    assert!(error.to_string().contains("(param: temperature)"));
+    assert!(!error.is_retryable());
+}
+
+#[test]
+fn test_rate_limit_retry_hint_and_request_id() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-request-id", HeaderValue::from_static("req_123"));
+    headers.insert("retry-after", HeaderValue::from_static("20"));
+
+    let error = OpenAIError::from_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        &headers,
+        &error_body(
+            "Rate limit reached",
+            "requests",
+            Some("rate_limit_exceeded"),
+            None,
+        ),
+    );
+
+    match &error {
+        OpenAIError::RateLimited { retry_after, .. } => {
+            assert_eq!(*retry_after, Some(Duration::from_secs(20)));
+        }
+        _ => panic!("Expected RateLimited variant"),
+    }
+    assert_eq!(error.request_id(), Some("req_123"));
+    assert!(error.to_string().contains("request req_123"));
+    assert!(error.is_retryable());
+
+    // The millisecond header wins when both are present
+    headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+    let error = OpenAIError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, "");
+    assert!(matches!(
+        error,
+        OpenAIError::RateLimited { retry_after: Some(delay), .. } if delay == Duration::from_millis(1500)
+    ));
+
+    // Absurd values are clamped to a day instead of overflowing
+    let mut headers = HeaderMap::new();
+    headers.insert("retry-after", HeaderValue::from_static("1e30"));
+    let error = OpenAIError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, "");
+    assert!(matches!(
+        error,
+        OpenAIError::RateLimited { retry_after: Some(delay), .. } if delay == Duration::from_secs(86400)
+    ));
+}
+
+#[test]
+fn test_is_retryable() {
+    let server = OpenAIError::parse_api_error("", StatusCode::INTERNAL_SERVER_ERROR);
+    let auth = OpenAIError::parse_api_error("", StatusCode::UNAUTHORIZED);
+    let quota = OpenAIError::parse_api_error(
+        &error_body(
+            "quota",
+            "insufficient_quota",
+            Some("insufficient_quota"),
+            None,
+        ),
+        StatusCode::TOO_MANY_REQUESTS,
+    );
+
+    assert!(server.is_retryable());
+    assert!(!auth.is_retryable());
+    assert!(!quota.is_retryable());
+    assert!(!OpenAIError::EmptyResponse.is_retryable());
+
+    // Transport failures retry by kind, whatever transport raised them
+    let timeout = OpenAIError::from(TransportError::timeout("deadline elapsed"));
+    let reset = OpenAIError::from(TransportError::connection("connection reset"));
+    let other = OpenAIError::from(TransportError::other("bad header"));
+    assert!(timeout.is_retryable());
+    assert!(reset.is_retryable());
+    assert!(!other.is_retryable());
+    assert_eq!(timeout.to_string(), "Request timed out: deadline elapsed");
+    assert_eq!(reset.to_string(), "Network Error: connection reset");
+    assert_eq!(other.to_string(), "Transport Error: bad header");
 }
@@ -0,0 +1,222 @@
+#![cfg(all(feature = "metrics", feature = "mock-server"))]
+
+use futures_util::StreamExt;
+use openai_api::api::errors::OpenAIError;
+use openai_api::api::metrics::{error_class, MetricsRegistry, LATENCY_BUCKETS};
+use openai_api::api::models::*;
+use openai_api::mock::{MockReply, MockServer};
+use openai_api::OpenAIClient;
+use std::sync::Arc;
+
+const MODEL: &str = "gpt-4.1";
+
+fn request() -> ResponseRequest {
+    ResponseRequest::builder()
+        .model(MODEL.to_string())
+        .input_text("Hi".to_string())
+        .build()
+}
+
+fn client(server: &MockServer, metrics: &Arc<MetricsRegistry>) -> OpenAIClient {
+    server.client().with_metrics(metrics.clone())
+}
+
+#[tokio::test]
+async fn test_successful_requests() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server
+        .push(MockReply::text("One two three"))
+        .push(MockReply::text("Four five"));
+    let client = client(&server, &metrics);
+
+    client.send_request(request()).await.unwrap();
+    client.send_request(request()).await.unwrap();
+
+    let completed = [("model", MODEL), ("status", "completed")];
+    assert_eq!(metrics.counter("openai_requests_total", &completed), 2.0);
+    let tokens = |kind| metrics.counter("openai_tokens_total", &[("model", MODEL), ("type", kind)]);
+    assert_eq!(tokens("input"), 20.0);
+    assert_eq!(tokens("output"), 5.0);
+    assert_eq!(tokens("cached"), 0.0);
+
+    // gpt-4.1 list prices: $2 in, $8 out per million tokens
+    let cost = metrics.counter("openai_cost_usd_total", &[("model", MODEL)]);
+    assert!((cost - (20.0 * 2.0 + 5.0 * 8.0) / 1e6).abs() < 1e-12);
+
+    let duration = metrics
+        .histogram("openai_request_duration_seconds", &[("model", MODEL)])
+        .unwrap();
+    assert_eq!(duration.count, 2);
+    assert_eq!(duration.buckets.len(), LATENCY_BUCKETS.len());
+    assert_eq!(*duration.buckets.last().unwrap(), 2);
+    let first_byte = metrics
+        .histogram("openai_time_to_first_byte_seconds", &[("model", MODEL)])
+        .unwrap();
+    assert_eq!(first_byte.count, 2);
+    assert!(first_byte.sum <= duration.sum);
+
+    // Only streamed replies have a first token
+    assert!(metrics
+        .histogram("openai_time_to_first_token_seconds", &[("model", MODEL)])
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_streamed_requests() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server
+        .push(MockReply::text_stream("One two three"))
+        .push(MockReply::error(500, "server_error", None, "Boom"));
+    let client = client(&server, &metrics);
+
+    let events: Vec<_> = client
+        .stream_request(request())
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(events.iter().all(Result::is_ok));
+    assert!(client.stream_request(request()).await.is_err());
+
+    let completed = [("model", MODEL), ("status", "completed")];
+    assert_eq!(metrics.counter("openai_requests_total", &completed), 1.0);
+    let failed = [("model", MODEL), ("status", "error")];
+    assert_eq!(metrics.counter("openai_requests_total", &failed), 1.0);
+    assert_eq!(
+        metrics.counter(
+            "openai_request_errors_total",
+            &[("model", MODEL), ("error_class", "server_error")]
+        ),
+        1.0
+    );
+    assert!(
+        metrics.counter(
+            "openai_tokens_total",
+            &[("model", MODEL), ("type", "output")]
+        ) > 0.0
+    );
+    assert!(metrics.counter("openai_cost_usd_total", &[("model", MODEL)]) > 0.0);
+
+    let histogram = |name| metrics.histogram(name, &[("model", MODEL)]).unwrap();
+    assert_eq!(histogram("openai_request_duration_seconds").count, 2);
+    assert_eq!(histogram("openai_time_to_first_token_seconds").count, 1);
+}
+
+#[tokio::test]
+async fn test_streamed_first_token() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server.push(MockReply::text_stream("One two three"));
+    let client = client(&server, &metrics);
+
+    client
+        .get_streaming_response(MODEL, "Hi", None)
+        .await
+        .unwrap();
+
+    let histogram = |name| metrics.histogram(name, &[("model", MODEL)]).unwrap();
+    let first_token = histogram("openai_time_to_first_token_seconds");
+    assert_eq!(first_token.count, 1);
+    assert!(first_token.sum >= histogram("openai_time_to_first_byte_seconds").sum);
+    assert!(first_token.sum <= histogram("openai_request_duration_seconds").sum);
+    let completed = [("model", MODEL), ("status", "completed")];
+    assert_eq!(metrics.counter("openai_requests_total", &completed), 1.0);
+}
+
+#[tokio::test]
+async fn test_errors_by_class() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server
+        .push(MockReply::error(400, "invalid_request_error", None, "Bad"))
+        .push(MockReply::error(500, "server_error", None, "Boom"));
+    let client = client(&server, &metrics);
+
+    assert!(client.send_request(request()).await.is_err());
+    assert!(client.send_request(request()).await.is_err());
+
+    let errors = |class| {
+        metrics.counter(
+            "openai_request_errors_total",
+            &[("model", MODEL), ("error_class", class)],
+        )
+    };
+    assert_eq!(errors("invalid_request"), 1.0);
+    assert_eq!(errors("server_error"), 1.0);
+    let failed = [("model", MODEL), ("status", "error")];
+    assert_eq!(metrics.counter("openai_requests_total", &failed), 2.0);
+    assert_eq!(
+        metrics.counter(
+            "openai_tokens_total",
+            &[("model", MODEL), ("type", "input")]
+        ),
+        0.0
+    );
+    assert!(metrics
+        .histogram("openai_time_to_first_byte_seconds", &[("model", MODEL)])
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_retries() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server
+        .push(MockReply::rate_limited(0))
+        .push(MockReply::rate_limited(0))
+        .push(MockReply::text("Finally"));
+    let client = client(&server, &metrics).with_max_retries(2);
+
+    client.send_request(request()).await.unwrap();
+    server.assert_request_count(3);
+    assert_eq!(
+        metrics.counter("openai_retries_total", &[("model", MODEL)]),
+        2.0
+    );
+    let completed = [("model", MODEL), ("status", "completed")];
+    assert_eq!(metrics.counter("openai_requests_total", &completed), 1.0);
+
+    // Once retries run out the error is counted
+    server
+        .push(MockReply::rate_limited(0))
+        .push(MockReply::rate_limited(0))
+        .push(MockReply::rate_limited(0));
+    let error = client.send_request(request()).await.unwrap_err();
+    assert_eq!(error_class(&error), "rate_limited");
+    assert_eq!(
+        metrics.counter("openai_retries_total", &[("model", MODEL)]),
+        4.0
+    );
+    let limited = [("model", MODEL), ("error_class", "rate_limited")];
+    assert_eq!(
+        metrics.counter("openai_request_errors_total", &limited),
+        1.0
+    );
+}
+
+#[tokio::test]
+async fn test_prometheus_text() {
+    let server = MockServer::start().await.unwrap();
+    let metrics = Arc::new(MetricsRegistry::new());
+    server.push(MockReply::text("Hello"));
+    client(&server, &metrics)
+        .send_request(request())
+        .await
+        .unwrap();
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE openai_requests_total counter\n"));
+    assert!(text.contains("openai_requests_total{model=\"gpt-4.1\",status=\"completed\"} 1\n"));
+    assert!(text.contains("# TYPE openai_request_duration_seconds histogram\n"));
+    assert!(
+        text.contains("openai_request_duration_seconds_bucket{model=\"gpt-4.1\",le=\"+Inf\"} 1\n")
+    );
+    assert!(text.contains("openai_request_duration_seconds_count{model=\"gpt-4.1\"} 1\n"));
+    assert!(text.contains("openai_tokens_total{model=\"gpt-4.1\",type=\"output\"} 1\n"));
+    // Nothing was retried, so the metric is left out
+    assert!(!text.contains("openai_retries_total"));
+
+    assert_eq!(error_class(&OpenAIError::EmptyResponse), "other");
+}
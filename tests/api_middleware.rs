@@ -0,0 +1,424 @@
+use async_trait::async_trait;
+use futures_util::{StreamExt, TryStreamExt};
+use openai_api::api::errors::{OpenAIError, ValidationError};
+use openai_api::api::middleware::{ExtraHeaders, Flow, Middleware, ModelAllowlist, SpendLimit};
+use openai_api::api::models::*;
+use openai_api::api::pricing::PricingTable;
+use openai_api::api::streaming::StreamEvent;
+use openai_api::api::transport::{HttpRequest, HttpResponse, Transport};
+use openai_api::config::file::ConfigFile;
+use openai_api::utils::content::extract_response_content;
+use openai_api::OpenAIClient;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+fn completed(text: &str) -> Value {
+    json!({
+        "id": "resp_1",
+        "status": "completed",
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": text, "annotations": [] }]
+        }]
+    })
+}
+
+// An SSE body streaming `text` a word at a time
+fn sse(text: &str) -> String {
+    let mut events: Vec<Value> = text
+        .split_inclusive(' ')
+        .map(|word| json!({ "type": "response.output_text.delta", "delta": word }))
+        .collect();
+    events.push(json!({ "type": "response.completed", "response": completed(text) }));
+    let mut body: String = events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap()
+            )
+        })
+        .collect();
+    body.push_str("data: [DONE]\n\n");
+    body
+}
+
+// Replies with a fixed status and body, keeping what it was sent
+#[derive(Clone)]
+struct FakeTransport {
+    status: StatusCode,
+    body: Value,
+    sent: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl FakeTransport {
+    fn ok() -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: completed("Hello!"),
+            sent: Arc::default(),
+        }
+    }
+
+    fn failing() -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            body: json!({ "error": { "message": "Boom", "type": "server_error" } }),
+            sent: Arc::default(),
+        }
+    }
+
+    fn streaming(body: String) -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: Value::String(body),
+            sent: Arc::default(),
+        }
+    }
+
+    fn sent(&self) -> Vec<HttpRequest> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    fn sent_body(&self) -> Value {
+        serde_json::from_slice(self.sent()[0].body.as_ref().unwrap()).unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        self.sent.lock().unwrap().push(request);
+        // A string body is sent as is, e.g. an SSE stream
+        let body = match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        };
+        Ok(HttpResponse::new(self.status, HeaderMap::new(), body))
+    }
+}
+
+// Logs each hook it sees under its name
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Middleware for Recorder {
+    async fn before_request(
+        &self,
+        _request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("before {}", self.name));
+        Ok(Flow::Continue)
+    }
+
+    async fn after_response(
+        &self,
+        _request: &ResponseRequest,
+        _response: &mut ResponseApiResponse,
+    ) -> Result<(), OpenAIError> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("after {}", self.name));
+        Ok(())
+    }
+
+    async fn on_error(
+        &self,
+        _request: &ResponseRequest,
+        error: OpenAIError,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("error {}", self.name));
+        Err(error)
+    }
+}
+
+// Replaces email addresses in text input
+struct RedactEmails;
+
+#[async_trait]
+impl Middleware for RedactEmails {
+    async fn before_request(
+        &self,
+        request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        if let Some(InputType::Text(text)) = &mut request.input {
+            *text = text
+                .split(' ')
+                .map(|word| if word.contains('@') { "[email]" } else { word })
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        Ok(Flow::Continue)
+    }
+}
+
+// Answers from memory without calling the API
+struct Canned;
+
+#[async_trait]
+impl Middleware for Canned {
+    async fn before_request(
+        &self,
+        _request: &mut ResponseRequest,
+        _headers: &mut HeaderMap,
+    ) -> Result<Flow, OpenAIError> {
+        let response = serde_json::from_value(completed("From memory.")).unwrap();
+        Ok(Flow::Respond(Box::new(response)))
+    }
+}
+
+// Turns any failure into a fallback answer
+struct Fallback;
+
+#[async_trait]
+impl Middleware for Fallback {
+    async fn on_error(
+        &self,
+        _request: &ResponseRequest,
+        _error: OpenAIError,
+    ) -> Result<ResponseApiResponse, OpenAIError> {
+        Ok(serde_json::from_value(completed("Fallback.")).unwrap())
+    }
+}
+
+fn request(model: &str, prompt: &str) -> ResponseRequest {
+    ResponseRequest::builder()
+        .model(model.to_string())
+        .input_text(prompt.to_string())
+        .build()
+}
+
+fn client(transport: &FakeTransport) -> OpenAIClient {
+    OpenAIClient::new("sk-test".to_string()).with_transport(transport.clone())
+}
+
+#[tokio::test]
+async fn test_hooks_run_in_order() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let recorder = |name| Recorder {
+        name,
+        log: log.clone(),
+    };
+
+    let client = client(&FakeTransport::ok())
+        .with_middleware(recorder("a"))
+        .with_middleware(recorder("b"));
+    client.send_request(request("gpt-4.1", "Hi")).await.unwrap();
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["before a", "before b", "after a", "after b"]
+    );
+
+    log.lock().unwrap().clear();
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(FakeTransport::failing())
+        .with_middleware(recorder("a"))
+        .with_middleware(recorder("b"));
+    let error = client.send_request(request("gpt-4.1", "Hi")).await;
+    assert!(matches!(error, Err(OpenAIError::ServerError(_))));
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["before a", "before b", "error a", "error b"]
+    );
+}
+
+#[tokio::test]
+async fn test_before_request_rewrites_request_and_headers() {
+    let transport = FakeTransport::ok();
+    let headers = ExtraHeaders::new().header("x-team", "search").unwrap();
+    let client = client(&transport)
+        .with_middleware(RedactEmails)
+        .with_middleware(headers);
+
+    client
+        .send_request(request("gpt-4.1", "Email jane@example.com today"))
+        .await
+        .unwrap();
+    assert_eq!(transport.sent_body()["input"], "Email [email] today");
+    assert_eq!(transport.sent()[0].headers["x-team"], "search");
+    assert_eq!(
+        transport.sent()[0].headers["authorization"],
+        "Bearer sk-test"
+    );
+
+    assert!(matches!(
+        ExtraHeaders::new().header("bad header", "x"),
+        Err(ValidationError::InvalidHeader(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_model_allowlist() {
+    let allowlist = ModelAllowlist::new(vec!["gpt-4.1*".to_string(), "o3".to_string()]);
+    assert!(allowlist.allows("gpt-4.1-mini"));
+    assert!(allowlist.allows("o3"));
+    assert!(!allowlist.allows("o3-pro"));
+
+    let transport = FakeTransport::ok();
+    let client = client(&transport).with_middleware(allowlist);
+    assert!(client.send_request(request("o3", "Hi")).await.is_ok());
+
+    let error = client
+        .send_request(request("gpt-4o", "Hi"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        OpenAIError::Validation(ValidationError::ModelNotAllowed(ref model)) if model == "gpt-4o"
+    ));
+    assert_eq!(transport.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn test_short_circuits() {
+    // A canned response skips the API but still reaches after_response
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let transport = FakeTransport::ok();
+    let client = client(&transport)
+        .with_middleware(Canned)
+        .with_middleware(Recorder {
+            name: "a",
+            log: log.clone(),
+        });
+    let response = client.send_request(request("gpt-4.1", "Hi")).await.unwrap();
+    assert_eq!(extract_response_content(&response), "From memory.");
+    assert!(transport.sent().is_empty());
+    assert_eq!(*log.lock().unwrap(), ["after a"]);
+
+    // on_error can recover, including from a rejected request
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(FakeTransport::failing())
+        .with_middleware(Fallback);
+    let response = client.send_request(request("gpt-4.1", "Hi")).await.unwrap();
+    assert_eq!(extract_response_content(&response), "Fallback.");
+
+    let client = client
+        .with_middleware(ModelAllowlist::new(Vec::new()))
+        .with_middleware(Fallback);
+    let response = client.send_request(request("gpt-4.1", "Hi")).await.unwrap();
+    assert_eq!(extract_response_content(&response), "Fallback.");
+}
+
+#[tokio::test]
+async fn test_streaming_calls_use_middleware() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let transport = FakeTransport::streaming(sse("Hello there!"));
+    let streaming = client(&transport)
+        .with_middleware(RedactEmails)
+        .with_middleware(Recorder {
+            name: "a",
+            log: log.clone(),
+        });
+    let events: Vec<StreamEvent> = streaming
+        .stream_request(request("gpt-4.1", "Ping bob@example.com"))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let deltas: Vec<&str> = events.iter().filter_map(StreamEvent::text_delta).collect();
+    assert_eq!(deltas, ["Hello ", "there!"]);
+    assert!(events.last().unwrap().is_final());
+    assert_eq!(*log.lock().unwrap(), ["before a", "after a"]);
+
+    let body = transport.sent_body();
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["input"], "Ping [email]");
+
+    // Collected streams end with the final response
+    let response = streaming
+        .get_streaming_response("gpt-4.1", "Ping", None)
+        .await
+        .unwrap();
+    assert_eq!(extract_response_content(&response), "Hello there!");
+
+    // An error event reaches on_error, which can recover
+    let error = json!({ "type": "error", "code": "server_error", "message": "Boom" });
+    let transport = FakeTransport::streaming(format!("event: error\ndata: {error}\n\n"));
+    let mut events = client(&transport)
+        .stream_request(request("gpt-4.1", "Hi"))
+        .await
+        .unwrap();
+    assert!(events.next().await.unwrap().is_err());
+
+    let events: Vec<StreamEvent> = client(&transport)
+        .with_middleware(Fallback)
+        .stream_request(request("gpt-4.1", "Hi"))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    let response = events[0].response().unwrap();
+    assert_eq!(extract_response_content(&response), "Fallback.");
+}
+
+#[tokio::test]
+async fn test_spend_limit_counts_every_request() {
+    // Each reply costs 1000 * $2 + 500 * $8 per million, $0.006 on gpt-4.1
+    let mut body = completed("Hello!");
+    body["usage"] = json!({ "input_tokens": 1000, "output_tokens": 500, "total_tokens": 1500 });
+    let transport = FakeTransport {
+        status: StatusCode::OK,
+        body,
+        sent: Arc::default(),
+    };
+    let limit = Arc::new(SpendLimit::new(PricingTable::default(), 0.015));
+    let client = client(&transport).with_middleware(limit.clone());
+    let mut bounded = request("gpt-4.1", "Hi");
+    bounded.max_output_tokens = Some(500);
+
+    // Worst case is about $0.004 a request, so two fit but a third would not
+    client.send_request(bounded.clone()).await.unwrap();
+    client.send_request(bounded.clone()).await.unwrap();
+    assert!((limit.spent() - 0.012).abs() < 1e-12);
+    let error = client.send_request(bounded).await.unwrap_err();
+    assert!(matches!(
+        error,
+        OpenAIError::Validation(ValidationError::CostLimitExceeded { .. })
+    ));
+    assert_eq!(transport.sent().len(), 2);
+
+    let error = client
+        .send_request(request("ft:custom", "Hi"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        OpenAIError::Validation(ValidationError::UnknownPrice(_))
+    ));
+}
+
+#[test]
+fn test_policy_config() {
+    let config = ConfigFile::parse(
+        r#"
+        [policy]
+        allowed_models = ["gpt-4.1*", "o3"]
+        headers = { "x-team" = "search" }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.policy.allowed_models, ["gpt-4.1*", "o3"]);
+    assert_eq!(config.policy.headers["x-team"], "search");
+    assert!(ConfigFile::default().policy.allowed_models.is_empty());
+}
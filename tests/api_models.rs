@@ -1,3 +1,4 @@
+use openai_api::api::errors::{ToolChoiceError, ValidationError};
 use openai_api::api::models::*;
 use openai_api::utils::content::extract_response_content;
 use serde_json::json;
@@ -69,9 +70,9 @@ fn test_response_api_deserialization() {
     );
     assert_eq!(response.object, "response");
     assert_eq!(response.model, "gpt-4.1-2025-04-14");
-    assert_eq!(response.status, "completed");
+    assert_eq!(response.status, Some(ResponseStatus::Completed));
     assert_eq!(response.output.len(), 1);
-    assert_eq!(response.output[0].role, "assistant");
+    assert_eq!(response.messages().next().unwrap().role, Role::Assistant);
 
     assert_eq!(response.usage.total_tokens, 123);
     assert_eq!(response.usage.input_tokens, 36);
@@ -85,17 +86,16 @@ fn test_extract_response_content() {
         id: "test-123".to_string(),
         object: "response".to_string(),
         created_at: 1741476542,
-        status: "completed".to_string(),
+        status: Some(ResponseStatus::Completed),
         error: None,
         incomplete_details: None,
         instructions: None,
         max_output_tokens: None,
         model: "gpt-4.1".to_string(),
-        output: vec![OutputMessage {
-            message_type: "message".to_string(),
+        output: vec![OutputItem::Message(OutputMessage {
             id: "msg-123".to_string(),
-            status: "completed".to_string(),
-            role: "assistant".to_string(),
+            status: ItemStatus::Completed,
+            role: Role::Assistant,
             content: vec![
                 ContentType::Text {
                     text: "Hello".to_string(),
@@ -103,23 +103,28 @@ fn test_extract_response_content() {
                 ContentType::OutputText {
                     text: "World".to_string(),
                     annotations: vec![],
+                    logprobs: vec![],
                 },
             ],
-        }],
-        parallel_tool_calls: true,
+        })],
+        parallel_tool_calls: Some(true),
         previous_response_id: None,
+        prompt: None,
         reasoning: None,
-        store: true,
-        temperature: 1.0,
-        text: TextConfig {
+        service_tier: None,
+        store: Some(true),
+        temperature: Some(1.0),
+        text: Some(TextConfig {
             format: TextFormat {
-                format_type: "text".to_string(),
+                format_type: TextFormatType::Text,
+                extra: Default::default(),
             },
-        },
-        tool_choice: "auto".to_string(),
+            extra: Default::default(),
+        }),
+        tool_choice: Some(ToolChoice::auto()),
         tools: vec![],
-        top_p: 1.0,
-        truncation: "disabled".to_string(),
+        top_p: Some(1.0),
+        truncation: Some(Truncation::Disabled),
         usage: Usage {
             input_tokens: 10,
             output_tokens: 20,
@@ -129,6 +134,9 @@ fn test_extract_response_content() {
         },
         user: None,
         metadata: None,
+        cost: None,
+        cached: false,
+        extra: Default::default(),
     };
 
     assert_eq!(extract_response_content(&response), "Hello World");
@@ -159,11 +167,11 @@ fn test_response_request_builder() {
         .instructions("You are a helpful assistant".to_string())
         .stream(true)
         .background(false)
-        .service_tier("priority".to_string())
+        .service_tier(ServiceTier::Priority)
         .user("user123".to_string())
         .top_p(0.9)
         .top_logprobs(5)
-        .truncation("auto".to_string())
+        .truncation(Truncation::Auto)
         .store(true)
         .parallel_tool_calls(true)
         .build();
@@ -177,11 +185,11 @@ fn test_response_request_builder() {
     );
     assert_eq!(request.stream, Some(true));
     assert_eq!(request.background, Some(false));
-    assert_eq!(request.service_tier, Some("priority".to_string()));
+    assert_eq!(request.service_tier, Some(ServiceTier::Priority));
     assert_eq!(request.user, Some("user123".to_string()));
     assert_eq!(request.top_p, Some(0.9));
     assert_eq!(request.top_logprobs, Some(5));
-    assert_eq!(request.truncation, Some("auto".to_string()));
+    assert_eq!(request.truncation, Some(Truncation::Auto));
     assert_eq!(request.store, Some(true));
     assert_eq!(request.parallel_tool_calls, Some(true));
 }
@@ -194,3 +202,521 @@ fn test_simple_request() {
     assert_eq!(request.store, Some(true));
     assert!(matches!(request.input, Some(InputType::Text(_))));
 }
+
+#[test]
+fn test_mcp_output_items_deserialization() {
+    let output = json!([
+        {
+            "type": "mcp_list_tools",
+            "id": "mcpl_1",
+            "server_label": "deepwiki",
+            "tools": [
+                {
+                    "name": "ask_question",
+                    "description": "Ask a question about a repository",
+                    "input_schema": { "type": "object" }
+                }
+            ]
+        },
+        {
+            "type": "mcp_call",
+            "id": "mcp_1",
+            "server_label": "deepwiki",
+            "name": "ask_question",
+            "arguments": "{\"repoName\":\"rust-lang/rust\"}",
+            "output": "Rust is a systems language",
+            "error": null
+        },
+        {
+            "type": "mcp_approval_request",
+            "id": "mcpr_1",
+            "server_label": "deepwiki",
+            "name": "ask_question",
+            "arguments": "{}"
+        },
+        {
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": "Done", "annotations": [] }]
+        }
+    ]);
+
+    let items: Vec<OutputItem> = serde_json::from_value(output).unwrap();
+    assert_eq!(items.len(), 4);
+    match &items[0] {
+        OutputItem::McpListTools(list) => {
+            assert_eq!(list.server_label, "deepwiki");
+            assert_eq!(list.tools[0].name, "ask_question");
+        }
+        _ => panic!("Expected McpListTools item"),
+    }
+    match &items[1] {
+        OutputItem::McpCall(call) => {
+            assert_eq!(call.name, "ask_question");
+            assert_eq!(call.output.as_deref(), Some("Rust is a systems language"));
+        }
+        _ => panic!("Expected McpCall item"),
+    }
+    assert!(matches!(items[2], OutputItem::McpApprovalRequest(_)));
+    assert!(matches!(items[3], OutputItem::Message(_)));
+}
+
+#[test]
+fn test_mcp_approval_response_serialization() {
+    let request = McpApprovalRequest {
+        id: "mcpr_1".to_string(),
+        server_label: "deepwiki".to_string(),
+        name: "ask_question".to_string(),
+        arguments: "{}".to_string(),
+    };
+
+    let request = ResponseRequest::builder()
+        .previous_response_id("resp_1".to_string())
+        .input_items(vec![InputItem::mcp_approval_response(&request, true)])
+        .build();
+
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        value["input"],
+        json!([{
+            "type": "mcp_approval_response",
+            "approval_request_id": "mcpr_1",
+            "approve": true
+        }])
+    );
+}
+
+#[test]
+fn test_reasoning_config_serialization() {
+    let request = ResponseRequest::builder()
+        .model("o4-mini".to_string())
+        .reasoning_effort(ReasoningEffort::Minimal)
+        .reasoning_summary(ReasoningSummary::Detailed)
+        .add_include(IncludeField::ReasoningEncryptedContent)
+        .add_include(IncludeField::ReasoningEncryptedContent)
+        .build();
+
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        value["reasoning"],
+        json!({ "effort": "minimal", "summary": "detailed" })
+    );
+    assert_eq!(value["include"], json!(["reasoning.encrypted_content"]));
+}
+
+#[test]
+fn test_reasoning_item_round_trip() {
+    let output = json!([
+        {
+            "type": "reasoning",
+            "id": "rs_1",
+            "summary": [
+                { "type": "summary_text", "text": "First I considered the question." },
+                { "type": "summary_text", "text": "Then I answered it." }
+            ],
+            "encrypted_content": "gAAAA..."
+        },
+        {
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": "42", "annotations": [] }]
+        }
+    ]);
+    let items: Vec<OutputItem> = serde_json::from_value(output).unwrap();
+
+    let reasoning = match &items[0] {
+        OutputItem::Reasoning(reasoning) => reasoning,
+        _ => panic!("Expected Reasoning item"),
+    };
+    let summaries: Vec<&str> = reasoning.summary_texts().collect();
+    assert_eq!(
+        summaries,
+        vec!["First I considered the question.", "Then I answered it."]
+    );
+
+    // Passing the item back as input keeps the encrypted content intact
+    let input = serde_json::to_value(InputItem::Reasoning(reasoning.clone())).unwrap();
+    assert_eq!(input["type"], "reasoning");
+    assert_eq!(input["id"], "rs_1");
+    assert_eq!(input["encrypted_content"], "gAAAA...");
+}
+
+#[test]
+fn test_top_logprobs_requests_logprobs_include() {
+    let request = ResponseRequest::builder().top_logprobs(3).build();
+
+    assert_eq!(request.top_logprobs, Some(3));
+    assert_eq!(
+        request.include,
+        Some(vec![IncludeField::OutputTextLogprobs])
+    );
+}
+
+fn response_fixture(
+    status: &str,
+    incomplete_details: serde_json::Value,
+    content: serde_json::Value,
+) -> ResponseApiResponse {
+    serde_json::from_value(json!({
+        "id": "resp_1",
+        "object": "response",
+        "created_at": 1741476542,
+        "status": status,
+        "error": null,
+        "incomplete_details": incomplete_details,
+        "instructions": null,
+        "max_output_tokens": 16,
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": status,
+            "role": "assistant",
+            "content": content
+        }],
+        "parallel_tool_calls": true,
+        "previous_response_id": null,
+        "reasoning": {"effort": null, "summary": null},
+        "store": true,
+        "temperature": 1.0,
+        "text": {"format": {"type": "text"}},
+        "tool_choice": "auto",
+        "tools": [],
+        "top_p": 1.0,
+        "truncation": "disabled",
+        "usage": {
+            "input_tokens": 10,
+            "output_tokens": 16,
+            "total_tokens": 26,
+            "input_tokens_details": {"cached_tokens": 0},
+            "output_tokens_details": {"reasoning_tokens": 0}
+        },
+        "user": null,
+        "metadata": {}
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_incomplete_response_deserialization() {
+    let response = response_fixture(
+        "incomplete",
+        json!({"reason": "max_output_tokens"}),
+        json!([{"type": "output_text", "text": "The first part", "annotations": []}]),
+    );
+
+    assert!(response.is_incomplete());
+    assert!(response.is_truncated());
+    assert!(!response.is_refusal());
+    assert_eq!(
+        response.incomplete_reason(),
+        Some(IncompleteReason::MaxOutputTokens)
+    );
+
+    let filtered = response_fixture("incomplete", json!({"reason": "content_filter"}), json!([]));
+    assert!(!filtered.is_truncated());
+    assert_eq!(
+        filtered.incomplete_reason(),
+        Some(IncompleteReason::ContentFilter)
+    );
+
+    let unknown = response_fixture("incomplete", json!({"reason": "something_new"}), json!([]));
+    assert_eq!(unknown.incomplete_reason(), Some(IncompleteReason::Other));
+}
+
+#[test]
+fn test_refusal_deserialization() {
+    let response = response_fixture(
+        "completed",
+        json!(null),
+        json!([{"type": "refusal", "refusal": "I can't help with that."}]),
+    );
+
+    assert!(response.is_refusal());
+    assert!(!response.is_incomplete());
+    assert_eq!(
+        response.refusals().collect::<Vec<_>>(),
+        vec!["I can't help with that."]
+    );
+    assert_eq!(
+        extract_response_content(&response),
+        "I can't help with that."
+    );
+}
+
+#[test]
+fn test_extend_with_continuation() {
+    let mut response = response_fixture(
+        "incomplete",
+        json!({"reason": "max_output_tokens"}),
+        json!([{"type": "output_text", "text": "The first part", "annotations": []}]),
+    );
+    let mut continuation = response_fixture(
+        "completed",
+        json!(null),
+        json!([{"type": "output_text", "text": " and the rest.", "annotations": []}]),
+    );
+    continuation.id = "resp_2".to_string();
+    response.usage.output_tokens_details = Some(OutputTokensDetails {
+        reasoning_tokens: Some(4),
+    });
+    continuation.usage.input_tokens_details = Some(InputTokensDetails {
+        cached_tokens: Some(8),
+    });
+    continuation.usage.output_tokens_details = Some(OutputTokensDetails {
+        reasoning_tokens: Some(6),
+    });
+
+    response.extend_with(continuation);
+
+    assert_eq!(response.id, "resp_2");
+    assert_eq!(response.status, Some(ResponseStatus::Completed));
+    assert!(!response.is_truncated());
+    assert_eq!(response.output.len(), 2);
+    assert_eq!(response.usage.output_tokens, 32);
+    assert_eq!(response.usage.total_tokens, 52);
+    let cached = response.usage.input_tokens_details.as_ref().unwrap();
+    assert_eq!(cached.cached_tokens, Some(8));
+    let reasoning = response.usage.output_tokens_details.as_ref().unwrap();
+    assert_eq!(reasoning.reasoning_tokens, Some(10));
+}
+
+#[test]
+fn test_try_build_ranges() {
+    assert!(ResponseRequest::builder()
+        .temperature(1.5)
+        .top_p(0.5)
+        .top_logprobs(20)
+        .max_output_tokens(16)
+        .try_build()
+        .is_ok());
+
+    let error = ResponseRequest::builder()
+        .temperature(2.5)
+        .try_build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "temperature must be between 0 and 2, got 2.5"
+    );
+
+    assert!(ResponseRequest::builder().top_p(-0.1).try_build().is_err());
+    assert!(ResponseRequest::builder()
+        .top_logprobs(21)
+        .try_build()
+        .is_err());
+    assert!(ResponseRequest::builder()
+        .max_output_tokens(8)
+        .try_build()
+        .is_err());
+}
+
+#[test]
+fn test_string_enums() {
+    assert_eq!(ServiceTier::from("flex"), ServiceTier::Flex);
+    assert_eq!(
+        ServiceTier::from("gold"),
+        ServiceTier::Other("gold".to_string())
+    );
+    assert_eq!(
+        IncludeField::ReasoningEncryptedContent.as_str(),
+        "reasoning.encrypted_content"
+    );
+    assert_eq!(ResponseStatus::InProgress.to_string(), "in_progress");
+
+    let request = ResponseRequest::builder()
+        .service_tier(ServiceTier::Other("gold".to_string()))
+        .truncation(Truncation::Auto)
+        .add_include(IncludeField::Other("future.field".to_string()))
+        .try_build()
+        .unwrap();
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["service_tier"], "gold");
+    assert_eq!(json["truncation"], "auto");
+    assert_eq!(json["include"], json!(["future.field"]));
+
+    let role: Role = serde_json::from_value(json!("critic")).unwrap();
+    assert_eq!(role, Role::Other("critic".to_string()));
+    assert_eq!(serde_json::to_value(&role).unwrap(), json!("critic"));
+
+    let format: TextFormat =
+        serde_json::from_value(json!({"type": "json_schema", "name": "event"})).unwrap();
+    assert_eq!(format.format_type, TextFormatType::JsonSchema);
+    let format: TextFormat = serde_json::from_value(json!({"type": "grammar"})).unwrap();
+    assert_eq!(
+        format.format_type,
+        TextFormatType::Other("grammar".to_string())
+    );
+    assert_eq!(ItemStatus::from("incomplete"), ItemStatus::Incomplete);
+}
+
+#[test]
+fn test_try_build_metadata_limits() {
+    let ok = json!({"ticket": "42", "team": "search"});
+    assert!(ResponseRequest::builder().metadata(ok).try_build().is_ok());
+
+    let too_many: serde_json::Map<String, serde_json::Value> = (0..17)
+        .map(|i| (format!("key{}", i), json!("value")))
+        .collect();
+    assert_eq!(
+        ResponseRequest::builder()
+            .metadata(serde_json::Value::Object(too_many))
+            .try_build()
+            .unwrap_err(),
+        ValidationError::TooManyMetadataKeys { max: 16, count: 17 }
+    );
+
+    let long_key = json!({ "k".repeat(65): "value" });
+    assert!(matches!(
+        ResponseRequest::builder().metadata(long_key).try_build(),
+        Err(ValidationError::MetadataKeyTooLong { .. })
+    ));
+
+    let long_value = json!({ "key": "v".repeat(513) });
+    assert!(matches!(
+        ResponseRequest::builder().metadata(long_value).try_build(),
+        Err(ValidationError::MetadataValueTooLong { .. })
+    ));
+
+    assert!(matches!(
+        ResponseRequest::builder()
+            .metadata(json!({"count": 3}))
+            .try_build(),
+        Err(ValidationError::MetadataValueNotString { .. })
+    ));
+    assert_eq!(
+        ResponseRequest::builder()
+            .metadata(json!(["not", "an", "object"]))
+            .try_build()
+            .unwrap_err(),
+        ValidationError::MetadataNotObject
+    );
+}
+
+#[test]
+fn test_try_build_conflicts() {
+    assert!(matches!(
+        ResponseRequest::builder()
+            .background(true)
+            .store(false)
+            .try_build(),
+        Err(ValidationError::Conflict(_))
+    ));
+    assert!(ResponseRequest::builder()
+        .background(true)
+        .store(true)
+        .try_build()
+        .is_ok());
+
+    assert_eq!(
+        ResponseRequest::builder()
+            .tool_choice(ToolChoice::required())
+            .try_build()
+            .unwrap_err(),
+        ValidationError::ToolChoice(ToolChoiceError::NoTools)
+    );
+}
+
+#[test]
+fn test_prompt_template_serialization() {
+    let request = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .prompt_template(
+            PromptTemplate::new("pmpt_123".to_string())
+                .version("2".to_string())
+                .variable("city".to_string(), "Paris".parse().unwrap())
+                .variable(
+                    "photo".to_string(),
+                    "image:https://example.com/a.png".parse().unwrap(),
+                )
+                .variable("report".to_string(), "file:file-abc".parse().unwrap()),
+        )
+        .build();
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        json["prompt"],
+        json!({
+            "id": "pmpt_123",
+            "version": "2",
+            "variables": {
+                "city": "Paris",
+                "photo": {
+                    "type": "input_image",
+                    "image_url": "https://example.com/a.png",
+                    "detail": "auto"
+                },
+                "report": { "type": "input_file", "file_id": "file-abc" }
+            }
+        })
+    );
+    assert!(json.get("input").is_none());
+
+    let latest = serde_json::to_value(PromptTemplate::new("pmpt_123".to_string())).unwrap();
+    assert_eq!(latest, json!({ "id": "pmpt_123" }));
+}
+
+#[test]
+fn test_prompt_variable_parsing() {
+    let parse = |value: &str| value.parse::<PromptVariable>().unwrap();
+
+    assert_eq!(parse("plain"), PromptVariable::Text("plain".to_string()));
+    assert_eq!(
+        parse("text:image:not really"),
+        PromptVariable::Text("image:not really".to_string())
+    );
+    assert_eq!(
+        parse("image:file-xyz"),
+        PromptVariable::Input(PromptInput::Image {
+            image_url: None,
+            file_id: Some("file-xyz".to_string()),
+            detail: ImageDetail::Auto,
+        })
+    );
+    assert_eq!(
+        parse("file:https://example.com/report.pdf"),
+        PromptVariable::Input(PromptInput::File {
+            file_url: Some("https://example.com/report.pdf".to_string()),
+            file_id: None,
+        })
+    );
+}
+
+#[test]
+fn test_response_prompt_reference() {
+    let response: ResponseApiResponse = serde_json::from_value(json!({
+        "id": "resp_1",
+        "prompt": {
+            "id": "pmpt_123",
+            "version": "2",
+            "variables": {
+                "city": { "type": "input_text", "text": "Paris" },
+                "clip": { "type": "input_audio", "data": "..." }
+            }
+        }
+    }))
+    .unwrap();
+
+    let prompt = response.prompt.unwrap();
+    assert_eq!(prompt.version.as_deref(), Some("2"));
+    assert_eq!(
+        prompt.variables["city"],
+        PromptVariable::Input(PromptInput::Text {
+            text: "Paris".to_string()
+        })
+    );
+    assert!(matches!(
+        prompt.variables["clip"],
+        PromptVariable::Input(PromptInput::Other(_))
+    ));
+
+    let response: ResponseApiResponse = serde_json::from_value(
+        json!({ "id": "resp_2", "prompt": { "id": "pmpt_1", "variables": null } }),
+    )
+    .unwrap();
+    assert!(response.prompt.unwrap().variables.is_empty());
+}
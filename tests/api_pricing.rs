@@ -0,0 +1,189 @@
+use openai_api::api::models::*;
+use openai_api::api::pricing::{format_usd, ModelPrice, PricingTable};
+use openai_api::config::file::ConfigFile;
+use serde_json::json;
+
+fn response(model: &str, service_tier: Option<&str>) -> ResponseApiResponse {
+    serde_json::from_value(json!({
+        "id": "resp_1",
+        "model": model,
+        "service_tier": service_tier,
+        "usage": {
+            "input_tokens": 1_000_000,
+            "input_tokens_details": { "cached_tokens": 400_000 },
+            "output_tokens": 500_000,
+            "output_tokens_details": { "reasoning_tokens": 100_000 },
+            "total_tokens": 1_500_000
+        }
+    }))
+    .unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_price_lookup_by_prefix() {
+    let pricing = PricingTable::default();
+
+    assert_eq!(pricing.price("gpt-4.1-2025-04-14").unwrap().input, 2.00);
+    assert_eq!(
+        pricing.price("gpt-4.1-mini-2025-04-14").unwrap().input,
+        0.40
+    );
+    assert_eq!(pricing.price("gpt-4o-mini").unwrap().output, 0.60);
+    assert_eq!(pricing.price("o3-mini").unwrap().input, 1.10);
+    assert!(pricing.price("ft:custom").is_none());
+
+    // Prefixes only match whole model families
+    assert_eq!(pricing.price("gpt-4-0613").unwrap().input, 30.00);
+    assert!(pricing.price("gpt-4.5-preview").is_none());
+    assert!(pricing.price("gpt-5.1").is_none());
+    assert!(pricing.price("o10").is_none());
+}
+
+#[test]
+fn test_response_cost() {
+    let pricing = PricingTable::default();
+    let cost = pricing.cost(&response("gpt-4.1", None)).unwrap();
+
+    // 600k uncached at $2, 400k cached at $0.50, 400k output and 100k
+    // reasoning at $8 per million
+    assert_close(cost.input, 1.2);
+    assert_close(cost.cached_input, 0.2);
+    assert_close(cost.output, 3.2);
+    assert_close(cost.reasoning, 0.8);
+    assert_close(cost.total, 5.4);
+
+    assert!(pricing.cost(&response("ft:custom", None)).is_none());
+}
+
+#[test]
+fn test_service_tier_multipliers() {
+    let pricing = PricingTable::default();
+    let standard = pricing.cost(&response("gpt-4.1", Some("default"))).unwrap();
+    let flex = pricing.cost(&response("gpt-4.1", Some("flex"))).unwrap();
+    let priority = pricing
+        .cost(&response("gpt-4.1", Some("priority")))
+        .unwrap();
+
+    assert_close(standard.total, 5.4);
+    assert_close(flex.total, 2.7);
+    assert_close(priority.total, 5.4 * 1.75);
+}
+
+#[test]
+fn test_config_overrides() {
+    let config = ConfigFile::parse(
+        r#"
+        [pricing.models."gpt-4.1"]
+        input = 1.0
+        output = 4.0
+        reasoning = 6.0
+
+        [pricing.models."ft:custom"]
+        input = 3.0
+        cached_input = 1.0
+        output = 12.0
+
+        [pricing.service_tiers]
+        priority = 2.0
+        "#,
+    )
+    .unwrap();
+    let pricing = &config.pricing;
+
+    assert_eq!(
+        pricing.price("ft:custom-model"),
+        Some(ModelPrice {
+            input: 3.0,
+            cached_input: Some(1.0),
+            output: 12.0,
+            reasoning: None,
+        })
+    );
+    // Built-in prices still apply to models without an override
+    assert_eq!(pricing.price("gpt-4o").unwrap().input, 2.50);
+
+    let cost = pricing
+        .cost(&response("gpt-4.1", Some("priority")))
+        .unwrap();
+    // Cached input falls back to the input rate
+    assert_close(cost.input, 0.6 * 2.0);
+    assert_close(cost.cached_input, 0.4 * 2.0);
+    assert_close(cost.output, 1.6 * 2.0);
+    assert_close(cost.reasoning, 0.6 * 2.0);
+}
+
+#[test]
+fn test_longest_key_wins_across_config_and_built_in() {
+    let config = ConfigFile::parse(
+        r#"
+        [pricing.models.gpt-4o]
+        input = 2.0
+        output = 8.0
+
+        [pricing.models.o3-mini]
+        input = 1.0
+        output = 4.0
+        "#,
+    )
+    .unwrap();
+    let pricing = &config.pricing;
+
+    assert_eq!(pricing.price("gpt-4o-2024-08-06").unwrap().input, 2.0);
+    // The built-in gpt-4o-mini entry is more specific than the gpt-4o override
+    assert_eq!(pricing.price("gpt-4o-mini").unwrap().input, 0.15);
+    assert_eq!(pricing.price("gpt-4o-mini-2024-07-18").unwrap().input, 0.15);
+    // Keys of equal length go to the override
+    assert_eq!(pricing.price("o3-mini").unwrap().input, 1.0);
+    assert_eq!(pricing.price("o3").unwrap().input, 2.0);
+}
+
+#[test]
+fn test_worst_case_cost() {
+    let pricing = PricingTable::default();
+    let bounded = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("hello world".to_string())
+        .max_output_tokens(1_000)
+        .build();
+    let estimate = pricing.worst_case_cost(&bounded).unwrap();
+    // 6 input tokens at $2 and 1000 output tokens at $8 per million
+    assert_close(estimate, (6.0 * 2.0 + 1_000.0 * 8.0) / 1_000_000.0);
+
+    let flex = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("hello world".to_string())
+        .max_output_tokens(1_000)
+        .service_tier(ServiceTier::Flex)
+        .build();
+    assert_close(pricing.worst_case_cost(&flex).unwrap(), estimate / 2.0);
+
+    // Without a limit the output may fill the rest of the context window
+    let unbounded = ResponseRequest::builder()
+        .model("gpt-4o".to_string())
+        .input_text("hello world".to_string())
+        .build();
+    assert_close(
+        pricing.worst_case_cost(&unbounded).unwrap(),
+        (6.0 * 2.5 + (128_000.0 - 6.0) * 10.0) / 1_000_000.0,
+    );
+}
+
+#[test]
+fn test_cost_in_json_output() {
+    let pricing = PricingTable::default();
+    let mut response = response("gpt-4.1", None);
+    response.cost = pricing.cost(&response);
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["cost"]["total"], json!(response.cost.unwrap().total));
+    assert_eq!(format_usd(0.0123), "$0.012300");
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use openai_api::api::errors::OpenAIError;
+use openai_api::api::models::*;
+use openai_api::api::rate_limit::{RateLimitScope, RateLimiter};
+use openai_api::api::transport::{HttpRequest, HttpResponse, Transport};
+use openai_api::OpenAIClient;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+const COMPLETED: &str = r#"{
+    "id": "resp_1",
+    "status": "completed",
+    "model": "gpt-4.1",
+    "output": []
+}"#;
+
+// Answers after `delay` with `headers`, keeping the input of each request
+// and the most requests it saw at once
+#[derive(Clone, Default)]
+struct SlowTransport {
+    delay: Duration,
+    headers: HeaderMap,
+    inputs: Arc<Mutex<Vec<String>>>,
+    in_flight: Arc<Mutex<(usize, usize)>>,
+}
+
+impl SlowTransport {
+    fn inputs(&self) -> Vec<String> {
+        self.inputs.lock().unwrap().clone()
+    }
+
+    fn max_in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().1
+    }
+}
+
+#[async_trait]
+impl Transport for SlowTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        let body: Value = match &request.body {
+            Some(body) => serde_json::from_slice(body).unwrap(),
+            None => Value::Null,
+        };
+        let input = body["input"].as_str().unwrap_or_default().to_string();
+        self.inputs.lock().unwrap().push(input);
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight.0 += 1;
+            in_flight.1 = in_flight.1.max(in_flight.0);
+        }
+        tokio::time::sleep(self.delay).await;
+        self.in_flight.lock().unwrap().0 -= 1;
+        Ok(HttpResponse::new(
+            StatusCode::OK,
+            self.headers.clone(),
+            COMPLETED,
+        ))
+    }
+}
+
+fn request(input: &str) -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text(input.to_string())
+        .build()
+}
+
+fn client(transport: &SlowTransport, limiter: &Arc<RateLimiter>) -> OpenAIClient {
+    OpenAIClient::new("sk-test".to_string())
+        .with_transport(transport.clone())
+        .with_rate_limiter(limiter.clone())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_requests_per_minute() {
+    let transport = SlowTransport::default();
+    let limiter = Arc::new(RateLimiter::new().requests_per_minute(2));
+    let client = client(&transport, &limiter);
+
+    let started = Instant::now();
+    client.send_request(request("a")).await.unwrap();
+    client.send_request(request("b")).await.unwrap();
+    assert_eq!(started.elapsed(), Duration::ZERO);
+
+    // The bucket refills at one request every 30 seconds
+    client.send_request(request("c")).await.unwrap();
+    let waited = started.elapsed();
+    assert!(waited >= Duration::from_secs(30) && waited < Duration::from_secs(31));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_tokens_per_minute() {
+    let transport = SlowTransport::default();
+    let limiter = Arc::new(RateLimiter::new().tokens_per_minute(1000));
+    let client = client(&transport, &limiter);
+    let mut request = request("Hello");
+    request.max_output_tokens = Some(590);
+    let tokens = RateLimiter::estimated_tokens(&request);
+    assert!(tokens > 590 && tokens < 610);
+
+    let started = Instant::now();
+    client.send_request(request.clone()).await.unwrap();
+    assert_eq!(
+        limiter.available_tokens("sk-test", Some("gpt-4.1")),
+        Some(1000.0 - tokens as f64)
+    );
+    client.send_request(request).await.unwrap();
+    let expected = Duration::from_secs_f64((2 * tokens - 1000) as f64 * 60.0 / 1000.0);
+    assert!(started.elapsed() >= expected);
+    assert!(started.elapsed() < expected + Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_max_in_flight() {
+    let transport = SlowTransport {
+        delay: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let limiter = Arc::new(RateLimiter::new().max_in_flight(2));
+    let client = client(&transport, &limiter);
+
+    let started = Instant::now();
+    let requests = (0..5).map(|i| client.send_request(request(&i.to_string())));
+    assert!(join_all(requests).await.iter().all(Result::is_ok));
+    assert_eq!(transport.max_in_flight(), 2);
+    assert!(started.elapsed() >= Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_waiting_requests_go_in_order() {
+    let transport = SlowTransport::default();
+    let limiter = Arc::new(RateLimiter::new().requests_per_minute(1));
+    let client = client(&transport, &limiter);
+
+    client.send_request(request("first")).await.unwrap();
+    let requests = ["b", "c", "d", "e"].map(|input| client.send_request(request(input)));
+    join_all(requests).await;
+    assert_eq!(transport.inputs(), ["first", "b", "c", "d", "e"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_corrects_from_headers() {
+    let mut transport = SlowTransport::default();
+    transport.headers.insert(
+        "x-ratelimit-remaining-requests",
+        HeaderValue::from_static("0"),
+    );
+    transport.headers.insert(
+        "x-ratelimit-remaining-tokens",
+        HeaderValue::from_static("42"),
+    );
+    let limiter = Arc::new(
+        RateLimiter::new()
+            .requests_per_minute(60)
+            .tokens_per_minute(100_000)
+            .scope(RateLimitScope::Model),
+    );
+    let client = client(&transport, &limiter);
+    let model = Some("gpt-4.1");
+
+    let started = Instant::now();
+    client.send_request(request("a")).await.unwrap();
+    assert_eq!(limiter.available_requests("sk-test", model), Some(0.0));
+    assert_eq!(limiter.available_tokens("sk-test", model), Some(42.0));
+    assert_eq!(
+        limiter.available_requests("sk-test", Some("o3")),
+        Some(60.0)
+    );
+
+    // The API said no requests were left, so wait for one to refill
+    client.send_request(request("b")).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1));
+
+    // Headers without limits leave the buckets alone
+    limiter.update("sk-test", model, &HeaderMap::new());
+    assert_eq!(limiter.available_requests("sk-test", model), Some(0.0));
+
+    // Per key, one model's remaining allowance says nothing about the others
+    let per_key = Arc::new(RateLimiter::new().requests_per_minute(60));
+    OpenAIClient::new("sk-test".to_string())
+        .with_transport(transport.clone())
+        .with_rate_limiter(per_key.clone())
+        .send_request(request("c"))
+        .await
+        .unwrap();
+    assert_eq!(per_key.available_requests("sk-test", None), Some(59.0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_polling_is_limited() {
+    let transport = SlowTransport::default();
+    let limiter = Arc::new(
+        RateLimiter::new()
+            .requests_per_minute(2)
+            .tokens_per_minute(1000),
+    );
+    let client = client(&transport, &limiter);
+
+    let started = Instant::now();
+    client.retrieve_response("resp_1").await.unwrap();
+    client.retrieve_response("resp_1").await.unwrap();
+    assert_eq!(started.elapsed(), Duration::ZERO);
+    assert_eq!(limiter.available_tokens("sk-test", None), Some(1000.0));
+
+    client.retrieve_response("resp_1").await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(30));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scopes() {
+    let per_key = RateLimiter::new().requests_per_minute(10);
+    per_key.acquire("sk-one", &request("a")).await;
+    assert_eq!(per_key.available_requests("sk-one", Some("o3")), Some(9.0));
+    assert_eq!(per_key.available_requests("sk-two", None), Some(10.0));
+    assert_eq!(per_key.available_tokens("sk-one", None), None);
+
+    let per_model = RateLimiter::new()
+        .requests_per_minute(10)
+        .scope(RateLimitScope::Model);
+    per_model.acquire("sk-one", &request("a")).await;
+    assert_eq!(
+        per_model.available_requests("sk-one", Some("gpt-4.1")),
+        Some(9.0)
+    );
+    assert_eq!(
+        per_model.available_requests("sk-one", Some("o3")),
+        Some(10.0)
+    );
+
+    // No limits set means nothing to wait for
+    let unlimited = RateLimiter::new().requests_per_minute(0);
+    assert_eq!(unlimited.available_requests("sk-one", None), None);
+}
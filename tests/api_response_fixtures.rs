@@ -0,0 +1,142 @@
+use openai_api::api::models::*;
+use openai_api::utils::content::extract_response_content;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/responses")
+}
+
+fn load(name: &str) -> ResponseApiResponse {
+    let path = fixtures_dir().join(name);
+    let json = fs::read_to_string(&path).unwrap();
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn test_every_fixture_deserializes_and_round_trips() {
+    let mut count = 0;
+    for entry in fs::read_dir(fixtures_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        let json: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+        let response: ResponseApiResponse = serde_json::from_value(json.clone())
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let reserialized = serde_json::to_value(&response).unwrap();
+
+        // Every top-level field with a value survives, and unknown ones verbatim
+        for (key, value) in json.as_object().unwrap() {
+            if value.is_null() {
+                continue;
+            }
+            assert!(
+                reserialized.get(key).is_some(),
+                "{}: field '{}' was dropped",
+                path.display(),
+                key
+            );
+            if let Some(extra) = response.extra.get(key) {
+                assert_eq!(extra, value, "{}: field '{}' changed", path.display(), key);
+            }
+        }
+        count += 1;
+    }
+    assert!(count >= 8);
+}
+
+#[test]
+fn test_reasoning_model_without_sampling_parameters() {
+    let response = load("reasoning_model.json");
+
+    assert_eq!(response.temperature, None);
+    assert_eq!(response.top_p, None);
+    assert_eq!(response.reasoning_items().count(), 1);
+    assert_eq!(response.service_tier, Some(ServiceTier::Default));
+    assert_eq!(
+        response.text.unwrap().extra.get("verbosity"),
+        Some(&Value::from("medium"))
+    );
+}
+
+#[test]
+fn test_tool_choice_object() {
+    let response = load("function_call.json");
+
+    assert_eq!(
+        response.tool_choice,
+        Some(ToolChoice::function("get_current_weather".to_string()))
+    );
+    assert_eq!(response.function_calls().count(), 1);
+    assert!(response.usage.input_tokens_details.is_none());
+}
+
+#[test]
+fn test_hosted_tool_call_items_are_kept() {
+    let response = load("web_search.json");
+
+    assert!(
+        matches!(&response.output[0], OutputItem::Other(item) if item["type"] == "web_search_call")
+    );
+    assert_eq!(response.messages().count(), 1);
+}
+
+#[test]
+fn test_background_response_with_null_usage() {
+    let response = load("background_queued.json");
+
+    assert_eq!(response.status, Some(ResponseStatus::Queued));
+    assert!(response.output.is_empty());
+    assert_eq!(response.usage.total_tokens, 0);
+    assert_eq!(response.extra.get("background"), Some(&Value::Bool(true)));
+}
+
+#[test]
+fn test_incomplete_fixture() {
+    let response = load("incomplete_max_output_tokens.json");
+
+    assert!(response.is_truncated());
+    assert_eq!(response.tool_choice, Some(ToolChoice::none()));
+    assert_eq!(
+        response.instructions,
+        Some(Value::from("You are a concise assistant."))
+    );
+}
+
+#[test]
+fn test_json_schema_text_format() {
+    let response = load("structured_output.json");
+    let format = response.text.unwrap().format;
+
+    assert_eq!(format.format_type, TextFormatType::JsonSchema);
+    assert_eq!(
+        format.extra.get("name"),
+        Some(&Value::from("calendar_event"))
+    );
+    assert!(format.extra.contains_key("schema"));
+}
+
+#[test]
+fn test_unknown_fields_and_shapes() {
+    let response = load("future_fields.json");
+
+    assert_eq!(response.object, "response");
+    assert_eq!(response.store, None);
+    assert_eq!(response.truncation, None);
+    assert!(matches!(response.tool_choice, Some(ToolChoice::Other(_))));
+    assert_eq!(
+        response.tool_choice.as_ref().unwrap().to_string(),
+        r#"{"name":"run_sql","type":"custom"}"#
+    );
+    assert!(matches!(&response.output[0], OutputItem::Other(_)));
+    assert!(response.extra.contains_key("conversation"));
+    assert_eq!(
+        response.prompt.as_ref().unwrap().version.as_deref(),
+        Some("3")
+    );
+    assert!(response.instructions.as_ref().unwrap().is_array());
+
+    let message = response.messages().next().unwrap();
+    assert!(matches!(message.content[1], ContentType::Other(_)));
+    assert_eq!(extract_response_content(&response), "Done.");
+}
@@ -0,0 +1,79 @@
+use bytes::Bytes;
+use futures_util::stream::{self, StreamExt};
+use openai_api::api::errors::{OpenAIError, TransportError};
+use openai_api::api::streaming::{self, SseDecoder, StreamEvent};
+use openai_api::utils::content::extract_response_content;
+use serde_json::json;
+
+const COMPLETED: &str = r#"{"type":"response.completed","response":{"id":"resp_1","status":"completed","model":"gpt-4.1","output":[{"type":"message","id":"msg_1","status":"completed","role":"assistant","content":[{"type":"output_text","text":"Hi!","annotations":[]}]}]}}"#;
+
+#[test]
+fn test_decoder_joins_split_chunks() {
+    let mut decoder = SseDecoder::new();
+    assert!(decoder
+        .push(b"event: response.output_text.delta\ndata: {\"de")
+        .is_empty());
+    let events = decoder.push(b"lta\":\"Hi\"}\n\nevent: response.output_text.delta\n");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].text_delta(), Some("Hi"));
+
+    let events = decoder.push(b"data: {\"delta\":\"!\"}\n\n");
+    assert_eq!(events[0].text_delta(), Some("!"));
+    assert_eq!(decoder.finish(), None);
+}
+
+#[test]
+fn test_decoder_handles_crlf_comments_and_done() {
+    let mut decoder = SseDecoder::new();
+    let events = decoder.push(
+        b": keep-alive\r\n\r\ndata: {\"type\":\"response.created\"}\r\n\r\ndata: [DONE]\r\n\r\n",
+    );
+    assert_eq!(
+        events,
+        [StreamEvent::new(
+            "response.created",
+            json!({ "type": "response.created" })
+        )]
+    );
+
+    // A last event without its blank line is still read
+    assert!(decoder.push(b"data: plain\ndata: text").is_empty());
+    assert_eq!(
+        decoder.finish(),
+        Some(StreamEvent::new("message", json!("plain\ntext")))
+    );
+}
+
+#[tokio::test]
+async fn test_collect_response() {
+    let body = format!(
+        "event: response.output_text.delta\ndata: {{\"delta\":\"Hi!\"}}\n\nevent: response.completed\ndata: {COMPLETED}\n\n"
+    );
+    let chunks: Vec<Bytes> = body
+        .as_bytes()
+        .chunks(7)
+        .map(Bytes::copy_from_slice)
+        .collect();
+    let events = streaming::decode(stream::iter(chunks).map(Ok).boxed());
+    let response = streaming::collect_response(events).await.unwrap();
+    assert_eq!(extract_response_content(&response), "Hi!");
+
+    // An error event or a broken body fails the stream
+    let error = json!({ "type": "error", "code": "rate_limit_exceeded", "message": "Slow down" });
+    let body = format!("event: error\ndata: {error}\n\n");
+    let events = streaming::decode(stream::iter([Ok(Bytes::from(body))]).boxed());
+    let error = streaming::collect_response(events).await.unwrap_err();
+    assert!(error.to_string().contains("Slow down"));
+
+    let chunks = [
+        Ok(Bytes::from_static(b"data: {}\n\n")),
+        Err(TransportError::connection("reset").into()),
+    ];
+    let events = streaming::decode(stream::iter(chunks).boxed());
+    let error = streaming::collect_response(events).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::Transport(_)));
+
+    let events = streaming::decode(stream::empty().boxed());
+    let error = streaming::collect_response(events).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::EmptyResponse));
+}
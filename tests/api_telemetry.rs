@@ -0,0 +1,223 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use openai_api::api::errors::OpenAIError;
+use openai_api::api::models::*;
+use openai_api::api::telemetry::TelemetryOptions;
+use openai_api::api::transport::{HttpRequest, HttpResponse, Transport};
+use openai_api::OpenAIClient;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+// Replies with a fixed status and body and an `x-request-id`
+struct FakeTransport {
+    status: StatusCode,
+    body: Value,
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("req_42"));
+        // A string body is sent as is, e.g. an SSE stream
+        let body = match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        };
+        Ok(HttpResponse::new(self.status, headers, body))
+    }
+}
+
+fn completed() -> FakeTransport {
+    FakeTransport {
+        status: StatusCode::OK,
+        body: json!({
+            "id": "resp_1",
+            "status": "completed",
+            "model": "gpt-4.1-2025-04-14",
+            "output": [],
+            "usage": { "input_tokens": 12, "output_tokens": 34, "total_tokens": 46 }
+        }),
+    }
+}
+
+// Collects JSON log lines written by the subscriber
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn lines(&self) -> Vec<Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+// Capture logs at debug level into `buffer` until the guard is dropped
+fn capture_into(buffer: &Buffer) -> tracing::subscriber::DefaultGuard {
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::set_default(subscriber)
+}
+
+fn request() -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("My secret prompt".to_string())
+        .temperature(0.2)
+        .build()
+}
+
+// Send one request with logs captured at debug level
+async fn capture(client: OpenAIClient) -> (Result<ResponseApiResponse, OpenAIError>, Vec<Value>) {
+    let buffer = Buffer::default();
+    let _guard = capture_into(&buffer);
+    let result = client.send_request(request()).await;
+    (result, buffer.lines())
+}
+
+fn client(transport: FakeTransport) -> OpenAIClient {
+    OpenAIClient::new("sk-test".to_string()).with_transport(transport)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_request_span_fields() {
+    let (result, lines) = capture(client(completed())).await;
+    result.unwrap();
+
+    assert_eq!(lines.len(), 1);
+    let event = &lines[0];
+    assert_eq!(event["level"], "INFO");
+    assert_eq!(event["fields"]["message"], "response received");
+
+    let span = &event["span"];
+    assert_eq!(span["name"], "openai.response");
+    assert_eq!(span["model"], "gpt-4.1");
+    assert_eq!(span["request_id"], "req_42");
+    assert_eq!(span["response_id"], "resp_1");
+    assert_eq!(span["status"], "completed");
+    assert_eq!(span["http_status"], 200);
+    assert_eq!(span["input_tokens"], 12);
+    assert_eq!(span["output_tokens"], 34);
+    assert_eq!(span["retries"], 0);
+    assert!(span["latency_ms"].is_u64());
+
+    // Optional attributes and bodies stay out by default
+    assert!(span.get("gen_ai.system").is_none());
+    assert!(!event.to_string().contains("My secret prompt"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_genai_attributes_and_bodies_when_enabled() {
+    let options = TelemetryOptions::default()
+        .genai_attributes(true)
+        .log_bodies(true);
+    let (result, lines) = capture(client(completed()).with_telemetry(options)).await;
+    result.unwrap();
+
+    let bodies: Vec<&Value> = lines
+        .iter()
+        .filter(|line| line["fields"]["message"] == "body")
+        .collect();
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[0]["fields"]["kind"], "request");
+    assert!(bodies[0]["fields"]["body"]
+        .as_str()
+        .unwrap()
+        .contains("My secret prompt"));
+    assert_eq!(bodies[1]["fields"]["kind"], "response");
+
+    let span = &lines.last().unwrap()["span"];
+    assert_eq!(span["gen_ai.operation.name"], "chat");
+    assert_eq!(span["gen_ai.system"], "openai");
+    assert_eq!(span["gen_ai.request.model"], "gpt-4.1");
+    assert!((span["gen_ai.request.temperature"].as_f64().unwrap() - 0.2).abs() < 1e-6);
+    assert_eq!(span["gen_ai.response.model"], "gpt-4.1-2025-04-14");
+    assert_eq!(span["gen_ai.usage.input_tokens"], 12);
+    assert_eq!(span["gen_ai.usage.output_tokens"], 34);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_failed_requests_log_a_warning() {
+    let transport = FakeTransport {
+        status: StatusCode::TOO_MANY_REQUESTS,
+        body: json!({ "error": { "message": "Slow down", "type": "requests" } }),
+    };
+    let (result, lines) = capture(client(transport)).await;
+    assert!(result.is_err());
+
+    let event = lines.last().unwrap();
+    assert_eq!(event["level"], "WARN");
+    assert_eq!(event["fields"]["message"], "request failed");
+    assert!(event["fields"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Slow down"));
+    assert_eq!(event["span"]["http_status"], 429);
+    assert_eq!(event["span"]["request_id"], "req_42");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_streamed_requests_are_recorded() {
+    let completed = json!({
+        "type": "response.completed",
+        "response": completed().body,
+    });
+    let transport = FakeTransport {
+        status: StatusCode::OK,
+        body: Value::String(format!(
+            "event: response.output_text.delta\ndata: {{\"delta\":\"Hi\"}}\n\nevent: response.completed\ndata: {completed}\n\n"
+        )),
+    };
+    let buffer = Buffer::default();
+    let _guard = capture_into(&buffer);
+    let events: Vec<_> = client(transport)
+        .stream_request(request())
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(events.iter().all(Result::is_ok));
+
+    let lines = buffer.lines();
+    let event = lines.last().unwrap();
+    assert_eq!(event["fields"]["message"], "response received");
+    let span = &event["span"];
+    assert_eq!(span["name"], "openai.response");
+    assert_eq!(span["request_id"], "req_42");
+    assert_eq!(span["response_id"], "resp_1");
+    assert_eq!(span["input_tokens"], 12);
+    assert_eq!(span["output_tokens"], 34);
+    assert!(span["latency_ms"].is_u64());
+
+    // A stream that fails to open is recorded as an error
+    let transport = FakeTransport {
+        status: StatusCode::TOO_MANY_REQUESTS,
+        body: json!({ "error": { "message": "Slow down", "type": "requests" } }),
+    };
+    assert!(client(transport).stream_request(request()).await.is_err());
+    let event = buffer.lines().pop().unwrap();
+    assert_eq!(event["fields"]["message"], "request failed");
+    assert_eq!(event["span"]["http_status"], 429);
+}
@@ -0,0 +1,342 @@
+use openai_api::api::errors::ToolChoiceError;
+use openai_api::api::models::{AllowedToolsMode, ResponseRequest, ToolChoice, ToolReference};
+use openai_api::api::tools::*;
+use serde_json::json;
+
+#[test]
+fn test_function_tool_serialization() {
+    let tool = FunctionTool::new(
+        "get_weather".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        }),
+    )
+    .description("Get the current weather".to_string())
+    .strict(true);
+
+    let value = serde_json::to_value(ToolDefinition::from(tool)).unwrap();
+    assert_eq!(value["type"], "function");
+    assert_eq!(value["name"], "get_weather");
+    assert_eq!(value["description"], "Get the current weather");
+    assert_eq!(value["strict"], true);
+    assert_eq!(value["parameters"]["required"][0], "city");
+}
+
+#[test]
+fn test_web_search_tool_serialization() {
+    let tool = WebSearchTool::new()
+        .search_context_size(SearchContextSize::High)
+        .user_location(
+            UserLocation::approximate()
+                .country("GB".to_string())
+                .city("London".to_string()),
+        );
+
+    let value = serde_json::to_value(ToolDefinition::from(tool)).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "web_search_preview",
+            "search_context_size": "high",
+            "user_location": {
+                "type": "approximate",
+                "city": "London",
+                "country": "GB"
+            }
+        })
+    );
+
+    let bare = serde_json::to_value(ToolDefinition::web_search()).unwrap();
+    assert_eq!(bare, json!({ "type": "web_search_preview" }));
+}
+
+#[test]
+fn test_web_search_alias_deserialization() {
+    let tool: ToolDefinition = serde_json::from_value(json!({ "type": "web_search" })).unwrap();
+    assert!(matches!(tool, ToolDefinition::WebSearch(_)));
+}
+
+#[test]
+fn test_unmodeled_tools_round_trip() {
+    let shell = json!({ "type": "local_shell" });
+    let tool: ToolDefinition = serde_json::from_value(shell.clone()).unwrap();
+    assert_eq!(tool, ToolDefinition::Other(shell.clone()));
+    assert_eq!(tool.tool_type(), "local_shell");
+    assert_eq!(serde_json::to_value(&tool).unwrap(), shell);
+
+    let request = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("List files".to_string())
+        .tools(vec![tool, ToolDefinition::web_search()])
+        .build();
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(value["tools"][0], shell);
+    assert_eq!(value["tools"][1]["type"], "web_search_preview");
+}
+
+#[test]
+fn test_file_search_tool_serialization() {
+    let tool = FileSearchTool::new(vec!["vs_123".to_string()]).max_num_results(5);
+
+    let value = serde_json::to_value(ToolDefinition::from(tool)).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "file_search",
+            "vector_store_ids": ["vs_123"],
+            "max_num_results": 5
+        })
+    );
+}
+
+#[test]
+fn test_code_interpreter_container_serialization() {
+    let auto = serde_json::to_value(ToolDefinition::code_interpreter()).unwrap();
+    assert_eq!(
+        auto,
+        json!({ "type": "code_interpreter", "container": { "type": "auto" } })
+    );
+
+    let with_files = CodeInterpreterTool::new().file_ids(vec!["file_1".to_string()]);
+    let value = serde_json::to_value(ToolDefinition::from(with_files)).unwrap();
+    assert_eq!(value["container"]["file_ids"][0], "file_1");
+
+    let existing = CodeInterpreterTool::new().container_id("cntr_abc".to_string());
+    let value = serde_json::to_value(ToolDefinition::from(existing)).unwrap();
+    assert_eq!(value["container"], "cntr_abc");
+}
+
+#[test]
+fn test_image_generation_tool_serialization() {
+    let tool = ImageGenerationTool::new()
+        .size("1024x1024".to_string())
+        .quality(ImageQuality::High)
+        .output_format(ImageOutputFormat::Webp)
+        .background(ImageBackground::Transparent);
+
+    let value = serde_json::to_value(ToolDefinition::from(tool)).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "image_generation",
+            "size": "1024x1024",
+            "quality": "high",
+            "output_format": "webp",
+            "background": "transparent"
+        })
+    );
+}
+
+#[test]
+fn test_computer_use_tool_serialization() {
+    let tool = ToolDefinition::computer_use(1024, 768, ComputerEnvironment::Browser);
+
+    let value = serde_json::to_value(&tool).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "computer_use_preview",
+            "display_width": 1024,
+            "display_height": 768,
+            "environment": "browser"
+        })
+    );
+}
+
+#[test]
+fn test_mcp_tool_serialization() {
+    let tool = McpTool::new(
+        "deepwiki".to_string(),
+        "https://mcp.deepwiki.com/mcp".to_string(),
+    )
+    .allowed_tools(vec!["ask_question".to_string()])
+    .require_approval(McpApproval::Mode(McpApprovalMode::Never));
+
+    let value = serde_json::to_value(ToolDefinition::from(tool)).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "mcp",
+            "server_label": "deepwiki",
+            "server_url": "https://mcp.deepwiki.com/mcp",
+            "allowed_tools": ["ask_question"],
+            "require_approval": "never"
+        })
+    );
+
+    let filtered = McpTool::new("docs".to_string(), "https://example.com/mcp".to_string())
+        .require_approval(McpApproval::Filter {
+            always: None,
+            never: Some(McpToolFilter {
+                tool_names: vec!["search".to_string()],
+            }),
+        });
+    let value = serde_json::to_value(ToolDefinition::from(filtered)).unwrap();
+    assert_eq!(
+        value["require_approval"],
+        json!({ "never": { "tool_names": ["search"] } })
+    );
+}
+
+#[test]
+fn test_request_builder_tools() {
+    let request = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .tool(ToolDefinition::web_search())
+        .tool(CodeInterpreterTool::new())
+        .build();
+
+    let tools = request.tools.unwrap();
+    assert_eq!(tools.len(), 2);
+    assert_eq!(tools[0].tool_type(), "web_search_preview");
+    assert_eq!(tools[1].tool_type(), "code_interpreter");
+}
+
+#[test]
+fn test_tool_choice_mode_serialization() {
+    assert_eq!(
+        serde_json::to_value(ToolChoice::none()).unwrap(),
+        json!("none")
+    );
+    assert_eq!(
+        serde_json::to_value(ToolChoice::auto()).unwrap(),
+        json!("auto")
+    );
+    assert_eq!(
+        serde_json::to_value(ToolChoice::required()).unwrap(),
+        json!("required")
+    );
+}
+
+#[test]
+fn test_tool_choice_forced_serialization() {
+    let function = ToolChoice::function("get_weather".to_string());
+    assert_eq!(
+        serde_json::to_value(&function).unwrap(),
+        json!({ "type": "function", "name": "get_weather" })
+    );
+
+    let hosted = ToolChoice::Tool(ToolReference::FileSearch);
+    assert_eq!(
+        serde_json::to_value(&hosted).unwrap(),
+        json!({ "type": "file_search" })
+    );
+
+    let allowed = ToolChoice::allowed_tools(
+        AllowedToolsMode::Required,
+        vec![
+            ToolReference::Function {
+                name: "get_weather".to_string(),
+            },
+            ToolReference::WebSearch,
+        ],
+    );
+    assert_eq!(
+        serde_json::to_value(&allowed).unwrap(),
+        json!({
+            "type": "allowed_tools",
+            "mode": "required",
+            "tools": [
+                { "type": "function", "name": "get_weather" },
+                { "type": "web_search_preview" }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_tool_choice_deserialization() {
+    let mode: ToolChoice = serde_json::from_value(json!("auto")).unwrap();
+    assert_eq!(mode, ToolChoice::auto());
+
+    let function: ToolChoice =
+        serde_json::from_value(json!({ "type": "function", "name": "lookup" })).unwrap();
+    assert_eq!(function, ToolChoice::function("lookup".to_string()));
+
+    let allowed: ToolChoice = serde_json::from_value(json!({
+        "type": "allowed_tools",
+        "mode": "auto",
+        "tools": [{ "type": "mcp", "server_label": "docs" }]
+    }))
+    .unwrap();
+    assert!(matches!(allowed, ToolChoice::AllowedTools(_)));
+}
+
+#[test]
+fn test_tool_choice_parsing() {
+    assert_eq!("required".parse::<ToolChoice>(), Ok(ToolChoice::required()));
+    assert_eq!(
+        "function=lookup".parse::<ToolChoice>(),
+        Ok(ToolChoice::function("lookup".to_string()))
+    );
+    assert_eq!(
+        "code_interpreter".parse::<ToolChoice>(),
+        Ok(ToolChoice::Tool(ToolReference::CodeInterpreter))
+    );
+    assert_eq!(
+        "allowed_tools:auto:function=a,mcp=docs".parse::<ToolChoice>(),
+        Ok(ToolChoice::allowed_tools(
+            AllowedToolsMode::Auto,
+            vec![
+                ToolReference::Function {
+                    name: "a".to_string()
+                },
+                ToolReference::Mcp {
+                    server_label: "docs".to_string(),
+                    name: None
+                },
+            ]
+        ))
+    );
+    assert!("sometimes".parse::<ToolChoice>().is_err());
+    assert!("allowed_tools:maybe:web_search"
+        .parse::<ToolChoice>()
+        .is_err());
+}
+
+#[test]
+fn test_tool_choice_validation() {
+    let tools = vec![
+        ToolDefinition::function("lookup".to_string(), json!({ "type": "object" })),
+        ToolDefinition::web_search(),
+    ];
+
+    assert!(ToolChoice::function("lookup".to_string())
+        .validate(&tools)
+        .is_ok());
+    assert!(ToolChoice::Tool(ToolReference::WebSearch)
+        .validate(&tools)
+        .is_ok());
+    assert_eq!(
+        ToolChoice::function("missing".to_string()).validate(&tools),
+        Err(ToolChoiceError::UnknownTool("function=missing".to_string()))
+    );
+    assert_eq!(
+        ToolChoice::Tool(ToolReference::FileSearch).validate(&tools),
+        Err(ToolChoiceError::UnknownTool("file_search".to_string()))
+    );
+    assert_eq!(
+        ToolChoice::required().validate(&[]),
+        Err(ToolChoiceError::NoTools)
+    );
+    assert_eq!(
+        ToolChoice::allowed_tools(AllowedToolsMode::Auto, vec![]).validate(&tools),
+        Err(ToolChoiceError::EmptyAllowedTools)
+    );
+}
+
+#[test]
+fn test_request_validate_tool_choice() {
+    let valid = ResponseRequest::builder()
+        .tool(ToolDefinition::code_interpreter())
+        .tool_choice(ToolChoice::Tool(ToolReference::CodeInterpreter))
+        .build();
+    assert!(valid.validate_tool_choice().is_ok());
+
+    let invalid = ResponseRequest::builder()
+        .tool_choice(ToolChoice::function("lookup".to_string()))
+        .build();
+    assert!(invalid.validate_tool_choice().is_err());
+}
@@ -0,0 +1,257 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::stream::{self, StreamExt};
+use openai_api::api::errors::{OpenAIError, TransportError, TransportErrorKind};
+use openai_api::api::models::*;
+use openai_api::api::transport::{HttpRequest, HttpResponse, Transport};
+use openai_api::utils::content::extract_response_content;
+use openai_api::OpenAIClient;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+const COMPLETED: &str = r#"{
+    "id": "resp_1",
+    "status": "completed",
+    "model": "gpt-4.1",
+    "output": [{
+        "type": "message",
+        "id": "msg_1",
+        "status": "completed",
+        "role": "assistant",
+        "content": [{ "type": "output_text", "text": "Hello!", "annotations": [] }]
+    }]
+}"#;
+
+// Answers every request with a fixed reply and keeps what it was sent
+#[derive(Clone)]
+struct FakeTransport {
+    status: StatusCode,
+    headers: HeaderMap,
+    chunks: Vec<&'static str>,
+    sent: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl FakeTransport {
+    fn new(status: StatusCode, chunks: Vec<&'static str>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            chunks,
+            sent: Arc::default(),
+        }
+    }
+
+    fn sent(&self) -> Vec<HttpRequest> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        self.sent.lock().unwrap().push(request);
+        let chunks = self.chunks.clone();
+        Ok(HttpResponse {
+            status: self.status,
+            headers: self.headers.clone(),
+            body: stream::iter(chunks)
+                .map(|chunk| Bytes::from_static(chunk.as_bytes()))
+                .map(Ok)
+                .boxed(),
+        })
+    }
+}
+
+// Wraps another transport, like a middleware layer would
+struct HeaderLayer<T> {
+    inner: T,
+}
+
+#[async_trait]
+impl<T: Transport> Transport for HeaderLayer<T> {
+    async fn send(&self, mut request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        request
+            .headers
+            .insert("x-team", HeaderValue::from_static("research"));
+        self.inner.send(request).await
+    }
+}
+
+struct FailingTransport;
+
+#[async_trait]
+impl Transport for FailingTransport {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        Err(TransportError::connection("connection reset").into())
+    }
+}
+
+// Rate limits the first `failures` requests, then answers like `inner`
+struct FlakyTransport {
+    failures: Mutex<u32>,
+    inner: FakeTransport,
+}
+
+#[async_trait]
+impl Transport for FlakyTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                let mut headers = HeaderMap::new();
+                headers.insert("retry-after", HeaderValue::from_static("0"));
+                let body = r#"{"error": {"message": "Slow down", "type": "requests"}}"#;
+                return Ok(HttpResponse::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    headers,
+                    body,
+                ));
+            }
+        }
+        self.inner.send(request).await
+    }
+}
+
+fn request() -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("Hi".to_string())
+        .build()
+}
+
+#[tokio::test]
+async fn test_client_sends_through_transport() {
+    let (head, tail) = COMPLETED.split_at(40);
+    let transport = FakeTransport::new(StatusCode::OK, vec![head, tail]);
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_base_url("https://example.test/v1/")
+        .with_transport(transport.clone());
+
+    let response = client.send_request(request()).await.unwrap();
+    assert_eq!(extract_response_content(&response), "Hello!");
+
+    let sent = transport.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].method, Method::POST);
+    assert_eq!(sent[0].url, "https://example.test/v1/responses");
+    assert_eq!(sent[0].headers["authorization"], "Bearer sk-test");
+    assert_eq!(sent[0].headers["content-type"], "application/json");
+    let body: Value = serde_json::from_slice(sent[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["model"], "gpt-4.1");
+    assert_eq!(body["input"], "Hi");
+}
+
+#[tokio::test]
+async fn test_stored_response_calls_have_no_body() {
+    let transport = FakeTransport::new(StatusCode::OK, vec![COMPLETED]);
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_base_url("https://example.test/v1")
+        .with_transport(transport.clone());
+
+    client.retrieve_response("resp_1").await.unwrap();
+    let sent = transport.sent();
+    assert_eq!(sent[0].method, Method::GET);
+    assert_eq!(sent[0].url, "https://example.test/v1/responses/resp_1");
+    assert!(sent[0].body.is_none());
+    assert!(!sent[0].headers.contains_key("content-type"));
+}
+
+#[tokio::test]
+async fn test_transport_errors_and_api_errors() {
+    let client = OpenAIClient::new("sk-test".to_string()).with_transport(FailingTransport);
+    let error = client.send_request(request()).await.unwrap_err();
+    assert!(matches!(
+        &error,
+        OpenAIError::Transport(e) if e.kind() == TransportErrorKind::Connection
+    ));
+    assert!(error.to_string().contains("connection reset"));
+
+    let mut transport = FakeTransport::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        vec![r#"{"error": {"message": "Slow down", "type": "requests"}}"#],
+    );
+    transport
+        .headers
+        .insert("retry-after", HeaderValue::from_static("3"));
+    let client = OpenAIClient::new("sk-test".to_string()).with_transport(transport);
+    let error = client.send_request(request()).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::RateLimited { .. }));
+}
+
+#[tokio::test]
+async fn test_retries() {
+    let inner = FakeTransport::new(StatusCode::OK, vec![COMPLETED]);
+    let flaky = |failures| FlakyTransport {
+        failures: Mutex::new(failures),
+        inner: inner.clone(),
+    };
+
+    // No retries unless asked for
+    let client = OpenAIClient::new("sk-test".to_string()).with_transport(flaky(1));
+    let error = client.send_request(request()).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::RateLimited { .. }));
+
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(flaky(2))
+        .with_max_retries(2);
+    let response = client.send_request(request()).await.unwrap();
+    assert_eq!(extract_response_content(&response), "Hello!");
+    assert_eq!(inner.sent().len(), 1);
+
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(flaky(3))
+        .with_max_retries(2);
+    assert!(client.send_request(request()).await.is_err());
+    assert_eq!(inner.sent().len(), 1);
+    // A server asking for a long wait is not retried
+    let mut slow = FakeTransport::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        vec![r#"{"error": {"message": "Slow down", "type": "requests"}}"#],
+    );
+    slow.headers
+        .insert("retry-after", HeaderValue::from_static("600"));
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(slow.clone())
+        .with_max_retries(2);
+    let error = client.send_request(request()).await.unwrap_err();
+    assert!(matches!(
+        error,
+        OpenAIError::RateLimited { retry_after: Some(delay), .. } if delay.as_secs() == 600
+    ));
+    assert_eq!(slow.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn test_wrapping_transports() {
+    let inner = FakeTransport::new(StatusCode::OK, vec![COMPLETED]);
+    let client = OpenAIClient::new("sk-test".to_string()).with_transport(HeaderLayer {
+        inner: inner.clone(),
+    });
+
+    client.send_request(request()).await.unwrap();
+    assert_eq!(inner.sent()[0].headers["x-team"], "research");
+}
+
+#[tokio::test]
+async fn test_http_response_body() {
+    let response = HttpResponse::new(
+        StatusCode::OK,
+        HeaderMap::new(),
+        json!({"a": 1}).to_string(),
+    );
+    assert_eq!(response.text().await.unwrap(), r#"{"a":1}"#);
+
+    let chunks = vec![
+        Ok(Bytes::from("data: 1\n\n")),
+        Ok(Bytes::from("data: 2\n\n")),
+    ];
+    let response = HttpResponse {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body: stream::iter(chunks).boxed(),
+    };
+    assert_eq!(response.bytes().await.unwrap(), "data: 1\n\ndata: 2\n\n");
+}
@@ -0,0 +1,21 @@
+use openai_api::cli::approval::{format_arguments, is_approval};
+
+#[test]
+fn test_is_approval() {
+    assert!(is_approval("y\n"));
+    assert!(is_approval("YES"));
+    assert!(is_approval("  yes  "));
+
+    assert!(!is_approval("\n"));
+    assert!(!is_approval("n"));
+    assert!(!is_approval("maybe"));
+}
+
+#[test]
+fn test_format_arguments() {
+    assert_eq!(
+        format_arguments("{\"city\":\"Paris\"}"),
+        "{\n  \"city\": \"Paris\"\n}"
+    );
+    assert_eq!(format_arguments("not json"), "not json");
+}
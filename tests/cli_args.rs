@@ -1,5 +1,21 @@
+// Baseline assertions are kept as written
+#![allow(clippy::bool_assert_comparison, clippy::needless_borrows_for_generic_args)]
+
 use clap::Parser;
-use openai_api::cli::args::Args;
+use openai_api::api::models::{
+    IncludeField, PromptInput, PromptVariable, ReasoningEffort, ReasoningSummary, ServiceTier,
+    ToolChoice, ToolReference, Truncation,
+};
+use openai_api::api::tools::{SearchContextSize, ToolDefinition};
+use openai_api::cli::args::{
+    Args, CacheArgs, CacheCommand, CacheMode, Command, ContextCheck, HistoryArgs, LogFormat,
+    LogLevel, PromptsArgs, PromptsCommand, ReportArgs, ReportFormat, TokensArgs, UsageArgs,
+    UsageCommand,
+};
+use openai_api::cli::logging;
+use openai_api::config::file::{CacheConfig, HistoryConfig};
+use openai_api::usage::report::GroupBy;
+use openai_api::utils::tokens::Encoding;
 
 #[test]
 fn test_args_parsing() {
@@ -35,9 +51,10 @@ fn test_temperature_validation() {
         previous_response_id: None,
         top_p: None,
         top_logprobs: None,
-        truncation: "disabled".to_string(),
+        truncation: Truncation::Disabled,
         store: None,
         parallel_tool_calls: None,
+        ..Default::default()
     };
 
     assert!(args.validate_temperature().is_ok());
@@ -59,9 +76,10 @@ fn test_temperature_validation() {
         previous_response_id: None,
         top_p: None,
         top_logprobs: None,
-        truncation: "disabled".to_string(),
+        truncation: Truncation::Disabled,
         store: None,
         parallel_tool_calls: None,
+        ..Default::default()
     };
 
     assert!(invalid_args.validate_temperature().is_err());
@@ -69,53 +87,43 @@ fn test_temperature_validation() {
 
 #[test]
 fn test_service_tier_validation() {
-    let valid_args = Args {
-        prompt: Some("test".to_string()),
-        model: "gpt-4".to_string(),
-        verbose: false,
-        temperature: None,
-        json: false,
-        markdown: false,
-        plain: false,
-        max_output_tokens: None,
-        instructions: None,
-        stream: false,
-        background: false,
-        service_tier: Some("priority".to_string()),
-        user: None,
-        previous_response_id: None,
-        top_p: None,
-        top_logprobs: None,
-        truncation: "disabled".to_string(),
-        store: None,
-        parallel_tool_calls: None,
-    };
+    let args = Args::parse_from(["test", "--service-tier", "priority", "Hi"]);
+    assert_eq!(args.service_tier, Some(ServiceTier::Priority));
 
-    assert!(valid_args.validate_service_tier().is_ok());
+    // Tiers newer than the CLI are passed through rather than rejected
+    let args = Args::parse_from(["test", "--service-tier", "turbo", "Hi"]);
+    assert_eq!(
+        args.service_tier,
+        Some(ServiceTier::Other("turbo".to_string()))
+    );
+}
 
-    let invalid_args = Args {
-        prompt: Some("test".to_string()),
-        model: "gpt-4".to_string(),
-        verbose: false,
-        temperature: None,
-        json: false,
-        markdown: false,
-        plain: false,
-        max_output_tokens: None,
-        instructions: None,
-        stream: false,
-        background: false,
-        service_tier: Some("invalid".to_string()),
-        user: None,
-        previous_response_id: None,
-        top_p: None,
-        top_logprobs: None,
-        truncation: "disabled".to_string(),
-        store: None,
-        parallel_tool_calls: None,
-    };
+#[test]
+fn test_truncation_and_include_values() {
+    let args = Args::parse_from(["test", "Hi"]);
+    assert_eq!(args.truncation, Truncation::Disabled);
+
+    let args = Args::parse_from([
+        "test",
+        "--truncation",
+        "auto",
+        "--include",
+        "reasoning.encrypted_content",
+        "--include",
+        "some.future_field",
+        "Hi",
+    ]);
+    assert_eq!(args.truncation, Truncation::Auto);
+    assert_eq!(
+        args.include,
+        vec![
+            IncludeField::ReasoningEncryptedContent,
+            IncludeField::Other("some.future_field".to_string())
+        ]
+    );
 
-    assert!(invalid_args.validate_service_tier().is_err());
+    let args = Args::parse_from(["test", "--truncation", "sometimes", "Hi"]);
+    assert_eq!(args.truncation, Truncation::Other("sometimes".to_string()));
 }
 
 #[test]
@@ -137,9 +145,10 @@ fn test_top_p_validation() {
         previous_response_id: None,
         top_p: Some(0.9),
         top_logprobs: None,
-        truncation: "disabled".to_string(),
+        truncation: Truncation::Disabled,
         store: None,
         parallel_tool_calls: None,
+        ..Default::default()
     };
 
     assert!(valid_args.validate_top_p().is_ok());
@@ -161,10 +170,388 @@ fn test_top_p_validation() {
         previous_response_id: None,
         top_p: Some(1.5), // Invalid top_p
         top_logprobs: None,
-        truncation: "disabled".to_string(),
+        truncation: Truncation::Disabled,
         store: None,
         parallel_tool_calls: None,
+        ..Default::default()
     };
 
     assert!(invalid_args.validate_top_p().is_err());
 }
+
+#[test]
+fn test_tool_flags() {
+    let args = Args::parse_from([
+        "test",
+        "--web-search",
+        "--search-context-size",
+        "low",
+        "--code-interpreter",
+        "--file-search",
+        "vs_1",
+        "--mcp",
+        "docs=https://example.com/mcp",
+        "Hello",
+    ]);
+
+    let tools = args.get_tools().unwrap();
+    let types: Vec<&str> = tools.iter().map(|t| t.tool_type()).collect();
+    assert_eq!(
+        types,
+        vec![
+            "web_search_preview",
+            "file_search",
+            "code_interpreter",
+            "mcp"
+        ]
+    );
+    match &tools[0] {
+        ToolDefinition::WebSearch(tool) => {
+            assert_eq!(tool.search_context_size, Some(SearchContextSize::Low))
+        }
+        _ => panic!("Expected WebSearch tool"),
+    }
+}
+
+#[test]
+fn test_invalid_mcp_flag() {
+    let args = Args::parse_from(["test", "--mcp", "missing-url", "Hello"]);
+    assert!(args.validate_tools().is_err());
+}
+
+#[test]
+fn test_no_tool_flags() {
+    let args = Args::parse_from(["test", "Hello"]);
+    assert!(args.get_tools().unwrap().is_empty());
+}
+
+#[test]
+fn test_tool_choice_flag() {
+    let args = Args::parse_from(["test", "--web-search", "--tool-choice", "web_search", "Hi"]);
+    assert_eq!(
+        args.tool_choice,
+        Some(ToolChoice::Tool(ToolReference::WebSearch))
+    );
+    assert!(args.validate_tool_choice().is_ok());
+
+    let mismatched = Args::parse_from(["test", "--tool-choice", "function=lookup", "Hi"]);
+    assert!(mismatched.validate_tool_choice().is_err());
+}
+
+#[test]
+fn test_reasoning_flags() {
+    let args = Args::parse_from([
+        "test",
+        "--reasoning-effort",
+        "high",
+        "--reasoning-summary",
+        "auto",
+        "--include",
+        "reasoning.encrypted_content",
+        "--show-thinking",
+        "Hi",
+    ]);
+
+    assert_eq!(args.reasoning_effort, Some(ReasoningEffort::High));
+    assert_eq!(args.reasoning_summary, Some(ReasoningSummary::Auto));
+    assert_eq!(
+        args.include,
+        vec![IncludeField::ReasoningEncryptedContent]
+    );
+    assert!(args.show_thinking);
+
+    assert!(Args::try_parse_from(["test", "--reasoning-effort", "extreme", "Hi"]).is_err());
+}
+
+#[test]
+fn test_show_logprobs_flag() {
+    let args = Args::parse_from(["test", "--show-logprobs", "--top-logprobs", "3", "Hi"]);
+    assert!(args.show_logprobs);
+    assert_eq!(args.top_logprobs, Some(3));
+}
+
+#[test]
+fn test_continue_on_truncation_validation() {
+    let args = Args::parse_from(["test", "--continue-on-truncation", "Hi"]);
+    assert!(args.continue_on_truncation);
+    assert!(args.validate_continuation().is_ok());
+
+    let args = Args::parse_from(["test", "--continue-on-truncation", "--store", "false", "Hi"]);
+    assert!(args.validate_continuation().is_err());
+}
+
+#[test]
+fn test_max_output_tokens_validation() {
+    let args = Args::parse_from(["test", "--max-output-tokens", "256", "Hi"]);
+    assert!(args.validate_max_output_tokens().is_ok());
+
+    let args = Args::parse_from(["test", "--max-output-tokens", "4", "Hi"]);
+    assert!(args.validate_max_output_tokens().is_err());
+}
+
+#[test]
+fn test_background_validation() {
+    let args = Args::parse_from(["test", "--background", "--store", "true", "Hi"]);
+    assert!(args.validate_background().is_ok());
+
+    let args = Args::parse_from(["test", "--background", "--store", "false", "Hi"]);
+    assert!(args.validate_background().is_err());
+    assert!(args.validate_all().is_err());
+}
+
+#[test]
+fn test_metadata_flag() {
+    let args = Args::parse_from([
+        "test",
+        "--metadata",
+        "team=search",
+        "--metadata",
+        "branch={git_branch}",
+        "Hi",
+    ]);
+    assert_eq!(
+        args.metadata,
+        vec![
+            ("team".to_string(), "search".to_string()),
+            ("branch".to_string(), "{git_branch}".to_string())
+        ]
+    );
+    assert!(args.validate_metadata().is_ok());
+
+    assert!(Args::try_parse_from(["test", "--metadata", "team", "Hi"]).is_err());
+
+    let mut args = Args::parse_from(["test", "Hi"]);
+    args.metadata = (0..17)
+        .map(|i| (format!("key{}", i), "value".to_string()))
+        .collect();
+    assert!(args.validate_metadata().is_err());
+}
+
+#[test]
+fn test_history_subcommand() {
+    let args = Args::parse_from(["test", "history", "--metadata", "team=search", "--limit", "5"]);
+
+    assert_eq!(
+        args.command,
+        Some(Command::History(HistoryArgs {
+            metadata: vec![("team".to_string(), "search".to_string())],
+            limit: 5,
+            json: false,
+        }))
+    );
+    assert_eq!(args.prompt, None);
+
+    let args = Args::parse_from(["test", "What is history?"]);
+    assert_eq!(args.command, None);
+    assert_eq!(args.prompt, Some("What is history?".to_string()));
+}
+
+#[test]
+fn test_history_is_opt_in() {
+    let enabled = HistoryConfig { enabled: true };
+    let disabled = HistoryConfig::default();
+
+    let args = Args::parse_from(["test", "Hi"]);
+    assert!(!args.saves_history(&disabled));
+    assert!(args.saves_history(&enabled));
+
+    let args = Args::parse_from(["test", "--history", "Hi"]);
+    assert!(args.saves_history(&disabled));
+
+    let args = Args::parse_from(["test", "--no-history", "Hi"]);
+    assert!(!args.saves_history(&enabled));
+
+    assert!(Args::try_parse_from(["test", "--history", "--no-history", "Hi"]).is_err());
+}
+
+#[test]
+fn test_stored_prompt_flags() {
+    let args = Args::parse_from([
+        "test",
+        "--prompt-id",
+        "pmpt_123",
+        "--prompt-version",
+        "4",
+        "--var",
+        "city=Paris",
+        "--var",
+        "photo=image:file-abc",
+    ]);
+    assert_eq!(args.prompt, None);
+
+    let template = args.get_prompt_template().unwrap();
+    assert_eq!(template.id, "pmpt_123");
+    assert_eq!(template.version.as_deref(), Some("4"));
+    assert_eq!(
+        template.variables["city"],
+        PromptVariable::Text("Paris".to_string())
+    );
+    assert!(matches!(
+        template.variables["photo"],
+        PromptVariable::Input(PromptInput::Image { .. })
+    ));
+
+    assert!(Args::parse_from(["test", "Hi"]).get_prompt_template().is_none());
+    assert!(Args::try_parse_from(["test", "--var", "city=Paris", "Hi"]).is_err());
+    assert!(Args::try_parse_from(["test", "--prompt-version", "4", "Hi"]).is_err());
+}
+
+#[test]
+fn test_prompts_subcommand() {
+    let args = Args::parse_from(["test", "prompts", "render", "summary", "--var", "length=two"]);
+
+    assert_eq!(
+        args.command,
+        Some(Command::Prompts(PromptsArgs {
+            command: PromptsCommand::Render {
+                name: "summary".to_string(),
+                vars: vec![("length".to_string(), "two".to_string())],
+            }
+        }))
+    );
+
+    let args = Args::parse_from(["test", "prompts", "list"]);
+    assert_eq!(
+        args.command,
+        Some(Command::Prompts(PromptsArgs {
+            command: PromptsCommand::List
+        }))
+    );
+}
+
+#[test]
+fn test_context_check_and_tokens_subcommand() {
+    assert_eq!(
+        Args::parse_from(["test", "Hi"]).context_check,
+        ContextCheck::Warn
+    );
+    assert_eq!(
+        Args::parse_from(["test", "--context-check", "fail", "Hi"]).context_check,
+        ContextCheck::Fail
+    );
+
+    let args = Args::parse_from(["test", "tokens", "--encoding", "cl100k_base"]);
+    assert_eq!(
+        args.command,
+        Some(Command::Tokens(TokensArgs {
+            model: "gpt-4o-mini".to_string(),
+            encoding: Some(Encoding::Cl100kBase),
+        }))
+    );
+}
+
+#[test]
+fn test_max_cost_flag() {
+    let args = Args::parse_from(["test", "--max-cost", "0.05", "Hi"]);
+    assert_eq!(args.max_cost, Some(0.05));
+    assert!(args.validate_max_cost().is_ok());
+
+    let args = Args::parse_from(["test", "--max-cost", "0", "Hi"]);
+    assert!(args.validate_max_cost().is_err());
+}
+
+#[test]
+fn test_usage_report_subcommand() {
+    let args = Args::parse_from([
+        "test",
+        "usage",
+        "report",
+        "--by",
+        "metadata:team",
+        "--format",
+        "csv",
+        "--since",
+        "2025-03-01",
+    ]);
+
+    assert_eq!(
+        args.command,
+        Some(Command::Usage(UsageArgs {
+            command: UsageCommand::Report(ReportArgs {
+                by: GroupBy::Metadata("team".to_string()),
+                format: ReportFormat::Csv,
+                since: Some("2025-03-01".to_string()),
+                until: None,
+            })
+        }))
+    );
+
+    let args = Args::parse_from(["test", "usage", "report"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::Usage(UsageArgs {
+            command: UsageCommand::Report(ReportArgs {
+                by: GroupBy::Day,
+                format: ReportFormat::Table,
+                ..
+            })
+        }))
+    ));
+
+    assert!(Args::try_parse_from(["test", "usage", "report", "--since", "yesterday"]).is_err());
+}
+
+#[test]
+fn test_profile_flag() {
+    let args = Args::parse_from(["test", "--profile", "research", "Hi"]);
+    assert_eq!(args.get_profile(), "research");
+}
+
+#[test]
+fn test_cache_flags() {
+    let config = CacheConfig::default();
+
+    let args = Args::parse_from(["test", "--cache", "Hi"]);
+    assert_eq!(args.cache, Some(CacheMode::On));
+    assert_eq!(args.prompt, Some("Hi".to_string()));
+    let cache = args.resolve_cache(&config);
+    assert!(cache.enabled && !cache.force);
+
+    let args = Args::parse_from(["test", "--cache=force", "Hi"]);
+    assert!(args.resolve_cache(&config).force);
+
+    let enabled = CacheConfig {
+        enabled: true,
+        ..Default::default()
+    };
+    let args = Args::parse_from(["test", "--no-cache", "Hi"]);
+    assert!(!args.resolve_cache(&enabled).enabled);
+    assert!(Args::parse_from(["test", "Hi"]).resolve_cache(&enabled).enabled);
+
+    assert!(Args::try_parse_from(["test", "--cache", "--no-cache", "Hi"]).is_err());
+
+    let args = Args::parse_from(["test", "cache", "clear"]);
+    assert_eq!(
+        args.command,
+        Some(Command::Cache(CacheArgs {
+            command: CacheCommand::Clear
+        }))
+    );
+}
+
+#[test]
+fn test_log_flags() {
+    let args = Args::parse_from(["test", "Hi"]);
+    assert_eq!(args.log_level, None);
+    assert_eq!(args.log_format, LogFormat::Text);
+    assert!(!args.log_bodies && !args.log_genai);
+
+    let args = Args::parse_from([
+        "test",
+        "--log-level",
+        "debug",
+        "--log-format",
+        "json",
+        "--log-file",
+        "/tmp/openai.log",
+        "--log-bodies",
+        "Hi",
+    ]);
+    assert_eq!(args.log_level, Some(LogLevel::Debug));
+    assert_eq!(args.log_format, LogFormat::Json);
+    assert_eq!(args.log_file, Some("/tmp/openai.log".into()));
+    assert!(args.log_bodies);
+    assert_eq!(logging::filter(&args).to_string(), "openai_api=debug");
+
+    assert!(Args::try_parse_from(["test", "--log-level", "loud", "Hi"]).is_err());
+}
@@ -0,0 +1,241 @@
+use openai_api::api::errors::{OpenAIError, TransportError, ValidationError};
+use openai_api::api::models::ResponseApiResponse;
+use openai_api::cli::exit_codes::{self, completion_notice, for_response, hint};
+use openai_api::mcp::client::McpError;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::json;
+
+fn response(status: &str, reason: Option<&str>, content: serde_json::Value) -> ResponseApiResponse {
+    serde_json::from_value(json!({
+        "id": "resp_1",
+        "object": "response",
+        "created_at": 1741476542,
+        "status": status,
+        "error": null,
+        "incomplete_details": reason.map(|reason| json!({"reason": reason})),
+        "instructions": null,
+        "max_output_tokens": null,
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": status,
+            "role": "assistant",
+            "content": content
+        }],
+        "parallel_tool_calls": true,
+        "previous_response_id": null,
+        "reasoning": {"effort": null, "summary": null},
+        "store": true,
+        "temperature": 1.0,
+        "text": {"format": {"type": "text"}},
+        "tool_choice": "auto",
+        "tools": [],
+        "top_p": 1.0,
+        "truncation": "disabled",
+        "usage": {
+            "input_tokens": 1,
+            "output_tokens": 1,
+            "total_tokens": 2,
+            "input_tokens_details": {"cached_tokens": 0},
+            "output_tokens_details": {"reasoning_tokens": 0}
+        },
+        "user": null,
+        "metadata": {}
+    }))
+    .unwrap()
+}
+
+fn text(text: &str) -> serde_json::Value {
+    json!([{"type": "output_text", "text": text, "annotations": []}])
+}
+
+#[test]
+fn test_exit_code_for_response() {
+    assert_eq!(
+        for_response(&response("completed", None, text("Hi"))),
+        exit_codes::SUCCESS
+    );
+    assert_eq!(
+        for_response(&response(
+            "incomplete",
+            Some("max_output_tokens"),
+            text("Hi")
+        )),
+        exit_codes::TRUNCATED
+    );
+    assert_eq!(
+        for_response(&response("incomplete", Some("content_filter"), text("Hi"))),
+        exit_codes::INCOMPLETE
+    );
+    assert_eq!(
+        for_response(&response(
+            "completed",
+            None,
+            json!([{"type": "refusal", "refusal": "No."}])
+        )),
+        exit_codes::REFUSED
+    );
+}
+
+#[test]
+fn test_completion_notice() {
+    assert_eq!(
+        completion_notice(&response("completed", None, text("Hi"))),
+        None
+    );
+
+    let truncated = completion_notice(&response(
+        "incomplete",
+        Some("max_output_tokens"),
+        text("Hi"),
+    ));
+    assert!(truncated.unwrap().contains("max_output_tokens"));
+
+    let filtered = completion_notice(&response("incomplete", Some("content_filter"), text("Hi")));
+    assert!(filtered.unwrap().contains("content filter"));
+
+    let refused = completion_notice(&response(
+        "completed",
+        None,
+        json!([{"type": "refusal", "refusal": "No."}]),
+    ));
+    assert!(refused.unwrap().contains("refused"));
+}
+
+#[test]
+fn test_exit_code_for_error() {
+    let error = |status| anyhow::Error::new(OpenAIError::parse_api_error("", status));
+
+    assert_eq!(
+        exit_codes::for_error(&error(StatusCode::UNAUTHORIZED)),
+        exit_codes::AUTHENTICATION
+    );
+    assert_eq!(
+        exit_codes::for_error(&error(StatusCode::TOO_MANY_REQUESTS)),
+        exit_codes::RATE_LIMITED
+    );
+    assert_eq!(
+        exit_codes::for_error(&error(StatusCode::BAD_GATEWAY)),
+        exit_codes::SERVER_ERROR
+    );
+    assert_eq!(
+        exit_codes::for_error(&anyhow::anyhow!("something else")),
+        exit_codes::ERROR
+    );
+
+    // API errors raised while running local MCP tools keep their class
+    let wrapped = anyhow::Error::new(McpError::Api(OpenAIError::parse_api_error(
+        "",
+        StatusCode::NOT_FOUND,
+    )));
+    assert_eq!(exit_codes::for_error(&wrapped), exit_codes::NOT_FOUND);
+
+    // The local context window check fails like the API would
+    let overflow = anyhow::Error::new(ValidationError::ContextWindowExceeded {
+        model: "gpt-4".to_string(),
+        tokens: 9000,
+        window: 8192,
+    });
+    assert_eq!(
+        exit_codes::for_error(&overflow),
+        exit_codes::CONTEXT_LENGTH_EXCEEDED
+    );
+    let budget = anyhow::Error::new(ValidationError::BudgetExceeded {
+        period: "daily",
+        spent: "$5.000000".to_string(),
+        limit: "$5.000000".to_string(),
+    });
+    assert_eq!(exit_codes::for_error(&budget), exit_codes::SPENDING_LIMIT);
+
+    // Failures inside a custom transport are classified by their kind
+    let transport = |error: TransportError| anyhow::Error::new(OpenAIError::from(error));
+    assert_eq!(
+        exit_codes::for_error(&transport(TransportError::connection("connection reset"))),
+        exit_codes::NETWORK
+    );
+    assert_eq!(
+        exit_codes::for_error(&transport(TransportError::timeout("deadline elapsed"))),
+        exit_codes::TIMEOUT
+    );
+    assert_eq!(
+        exit_codes::for_error(&transport(TransportError::other("bad header"))),
+        exit_codes::NETWORK
+    );
+    assert_eq!(
+        exit_codes::for_error(&anyhow::Error::new(ValidationError::Conflict("x"))),
+        exit_codes::INVALID_REQUEST
+    );
+}
+
+#[test]
+fn test_error_hints() {
+    let auth = OpenAIError::parse_api_error("", StatusCode::UNAUTHORIZED);
+    assert!(hint(&auth).unwrap().contains("OPENAI_API_KEY"));
+
+    let mut headers = HeaderMap::new();
+    headers.insert("retry-after", HeaderValue::from_static("2"));
+    let limited = OpenAIError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, "");
+    assert_eq!(hint(&limited).unwrap(), "Wait 2.0s and try again");
+
+    let invalid = OpenAIError::parse_api_error(
+        r#"{"error": {"message": "bad", "type": "invalid_request_error", "param": "top_p"}}"#,
+        StatusCode::BAD_REQUEST,
+    );
+    assert_eq!(hint(&invalid).unwrap(), "Check the value given for 'top_p'");
+
+    let no_param = OpenAIError::parse_api_error("", StatusCode::BAD_REQUEST);
+    assert_eq!(hint(&no_param), None);
+}
+
+#[test]
+fn test_spend_limit_hints_name_the_flags() {
+    // The library's errors speak of its own settings, the CLI hints of flags
+    let over = ValidationError::CostLimitExceeded {
+        estimate: "$0.020000".to_string(),
+        limit: "$0.010000".to_string(),
+    };
+    assert!(!over.to_string().contains("--"));
+    let hint = exit_codes::error_hint(&anyhow::Error::new(over.clone())).unwrap();
+    assert!(hint.contains("--max-cost"));
+    let wrapped = anyhow::Error::new(OpenAIError::Validation(over));
+    assert_eq!(exit_codes::error_hint(&wrapped).unwrap(), hint);
+
+    let unbounded = ValidationError::UnboundedCost("ft:custom".to_string());
+    assert!(!unbounded.to_string().contains("--"));
+    assert!(exit_codes::validation_hint(&unbounded)
+        .unwrap()
+        .contains("--max-output-tokens"));
+    assert_eq!(
+        exit_codes::validation_hint(&ValidationError::Conflict("x")),
+        None
+    );
+}
+
+#[test]
+fn test_cli_exits_with_error_class_codes() {
+    let data_dir = std::env::temp_dir().join(format!("openai-cli-exit-{}", std::process::id()));
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_openai-api"))
+            .args(args)
+            .env("OPENAI_API_KEY", "sk-test")
+            .env("OPENAI_CLI_CONFIG", data_dir.join("missing.toml"))
+            .env("OPENAI_CLI_DATA_DIR", &data_dir)
+            .env_remove("OPENAI_API_CASSETTE")
+            .output()
+            .unwrap()
+    };
+
+    // Rejected by argument validation before any request is built
+    let output = run(&["--temperature", "5", "Hi"]);
+    assert_eq!(output.status.code(), Some(exit_codes::INVALID_REQUEST));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("❌ Error:"));
+
+    // Rejected while building the request
+    let output = run(&["--local-mcp", "missing", "Hi"]);
+    assert_eq!(output.status.code(), Some(exit_codes::ERROR));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("❌ Error: Unknown local MCP server 'missing'"));
+    let _ = std::fs::remove_dir_all(&data_dir);
+}
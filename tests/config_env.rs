@@ -1,4 +1,5 @@
 use openai_api::config::env::Config;
+use openai_api::config::file::ConfigFile;
 use std::env;
 
 #[test]
@@ -25,6 +26,7 @@ fn test_config_accessors() {
     let config = Config {
         api_key: "test-key".to_string(),
         default_model: "gpt-4o-mini".to_string(),
+        file: ConfigFile::default(),
     };
 
     assert_eq!(config.api_key(), "test-key");
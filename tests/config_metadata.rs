@@ -0,0 +1,115 @@
+use openai_api::config::file::ConfigFile;
+use openai_api::config::metadata::{
+    parse_key_value, render_metadata, resolve_metadata, TemplateContext,
+};
+use std::collections::BTreeMap;
+
+fn context() -> TemplateContext {
+    TemplateContext {
+        hostname: "build-01".to_string(),
+        git_branch: "main".to_string(),
+        session: "abc123".to_string(),
+    }
+}
+
+#[test]
+fn test_render_templates() {
+    let context = context();
+
+    assert_eq!(context.render("{hostname}"), "build-01");
+    assert_eq!(
+        context.render("{git_branch}@{hostname}/{session}"),
+        "main@build-01/abc123"
+    );
+    assert_eq!(context.render("{unknown} stays"), "{unknown} stays");
+    assert_eq!(context.render("plain"), "plain");
+}
+
+#[test]
+fn test_render_metadata_values() {
+    let mut metadata = BTreeMap::from([
+        ("host".to_string(), "{hostname}".to_string()),
+        ("team".to_string(), "search".to_string()),
+    ]);
+    render_metadata(&mut metadata, &context());
+
+    assert_eq!(metadata["host"], "build-01");
+    assert_eq!(metadata["team"], "search");
+}
+
+#[test]
+fn test_cli_pairs_override_config_defaults() {
+    let defaults = BTreeMap::from([
+        ("team".to_string(), "search".to_string()),
+        ("cost_center".to_string(), "cc-42".to_string()),
+    ]);
+    let overrides = vec![
+        ("team".to_string(), "ranking".to_string()),
+        ("ticket".to_string(), "T-7".to_string()),
+    ];
+
+    let metadata = resolve_metadata(&defaults, &overrides);
+
+    assert_eq!(
+        metadata,
+        BTreeMap::from([
+            ("cost_center".to_string(), "cc-42".to_string()),
+            ("team".to_string(), "ranking".to_string()),
+            ("ticket".to_string(), "T-7".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_session_template_uses_environment() {
+    std::env::set_var("OPENAI_CLI_SESSION", "shell-7");
+    let metadata = resolve_metadata(
+        &BTreeMap::new(),
+        &[("session".to_string(), "{session}".to_string())],
+    );
+    std::env::remove_var("OPENAI_CLI_SESSION");
+
+    assert_eq!(metadata["session"], "shell-7");
+}
+
+#[test]
+fn test_parse_key_value() {
+    assert_eq!(
+        parse_key_value("team=search"),
+        Ok(("team".to_string(), "search".to_string()))
+    );
+    assert_eq!(
+        parse_key_value("query=a=b"),
+        Ok(("query".to_string(), "a=b".to_string()))
+    );
+    assert_eq!(
+        parse_key_value("empty="),
+        Ok(("empty".to_string(), String::new()))
+    );
+    assert!(parse_key_value("no-separator").is_err());
+    assert!(parse_key_value("=value").is_err());
+}
+
+#[test]
+fn test_config_file_metadata_and_history() {
+    let config = ConfigFile::parse(
+        r#"
+        [metadata]
+        team = "search"
+        cost_center = "cc-42"
+        host = "{hostname}"
+
+        [history]
+        enabled = true
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.metadata["team"], "search");
+    assert_eq!(config.metadata["host"], "{hostname}");
+    assert!(config.history.enabled);
+
+    // Prompts are only kept on disk when asked for
+    assert!(!ConfigFile::default().history.enabled);
+    assert!(!ConfigFile::parse("").unwrap().history.enabled);
+}
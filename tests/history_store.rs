@@ -0,0 +1,102 @@
+use openai_api::api::models::{ResponseApiResponse, ResponseStatus};
+use openai_api::history::store::{HistoryEntry, HistoryStore};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_history(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("openai-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("history.jsonl")
+}
+
+fn entry(id: &str, metadata: &[(&str, &str)]) -> HistoryEntry {
+    HistoryEntry {
+        id: id.to_string(),
+        created_at: 1741476542,
+        model: "gpt-4.1".to_string(),
+        status: Some(ResponseStatus::Completed),
+        prompt: "Hello".to_string(),
+        response: "Hi there".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+fn filter(key: &str, value: &str) -> (String, String) {
+    (key.to_string(), value.to_string())
+}
+
+#[test]
+fn test_entry_from_response() {
+    let response: ResponseApiResponse = serde_json::from_value(json!({
+        "id": "resp_1",
+        "created_at": 1741476542,
+        "status": "completed",
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{"type": "output_text", "text": "Paris", "annotations": []}]
+        }]
+    }))
+    .unwrap();
+    let metadata = BTreeMap::from([("team".to_string(), "geo".to_string())]);
+
+    let entry = HistoryEntry::new("Capital of France?", &response, metadata.clone());
+
+    assert_eq!(entry.id, "resp_1");
+    assert_eq!(entry.prompt, "Capital of France?");
+    assert_eq!(entry.response, "Paris");
+    assert_eq!(entry.metadata, metadata);
+}
+
+#[test]
+fn test_entry_matches_all_filters() {
+    let entry = entry("resp_1", &[("team", "search"), ("env", "prod")]);
+
+    assert!(entry.matches(&[]));
+    assert!(entry.matches(&[filter("team", "search")]));
+    assert!(entry.matches(&[filter("team", "search"), filter("env", "prod")]));
+    assert!(!entry.matches(&[filter("team", "search"), filter("env", "dev")]));
+    assert!(!entry.matches(&[filter("owner", "search")]));
+}
+
+#[test]
+fn test_append_and_search() {
+    let path = temp_history("search");
+    let store = HistoryStore::new(path.clone());
+
+    assert!(store.entries().unwrap().is_empty());
+
+    store
+        .append(&entry("resp_1", &[("team", "search")]))
+        .unwrap();
+    store.append(&entry("resp_2", &[("team", "ads")])).unwrap();
+    store
+        .append(&entry("resp_3", &[("team", "search")]))
+        .unwrap();
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, b"not json\n"))
+        .unwrap();
+
+    assert_eq!(store.entries().unwrap().len(), 3);
+
+    let ids = |entries: Vec<HistoryEntry>| -> Vec<String> {
+        entries.into_iter().map(|entry| entry.id).collect()
+    };
+    assert_eq!(
+        ids(store.search(&[filter("team", "search")], 10).unwrap()),
+        vec!["resp_1", "resp_3"]
+    );
+    assert_eq!(ids(store.search(&[], 2).unwrap()), vec!["resp_2", "resp_3"]);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,214 @@
+use openai_api::config::file::{ConfigFile, LocalMcpServerConfig};
+use openai_api::mcp::client::{McpError, McpStdioClient};
+use openai_api::mcp::host::LocalMcpHost;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_config_file_mcp_servers() {
+    let config = ConfigFile::parse(
+        r#"
+        [mcp_servers.internal]
+        command = "internal-mcp"
+        args = ["--stdio"]
+        env = { TOKEN = "secret" }
+        "#,
+    )
+    .unwrap();
+
+    let server = &config.mcp_servers["internal"];
+    assert_eq!(server.command, "internal-mcp");
+    assert_eq!(server.args, vec!["--stdio".to_string()]);
+    assert_eq!(server.env["TOKEN"], "secret");
+    assert_eq!(server.timeout_secs, 60);
+}
+
+#[test]
+fn test_function_name_sanitization() {
+    assert_eq!(
+        LocalMcpHost::function_name("docs", "search"),
+        "docs__search"
+    );
+    assert_eq!(
+        LocalMcpHost::function_name("my docs", "files.read"),
+        "my_docs__files_read"
+    );
+    assert_eq!(LocalMcpHost::function_name("s", &"x".repeat(100)).len(), 64);
+}
+
+#[tokio::test]
+async fn test_start_named_rejects_unknown_server() {
+    let configs = BTreeMap::new();
+    let result = LocalMcpHost::start_named(&configs, &["missing".to_string()]).await;
+    assert!(matches!(result, Err(McpError::UnknownServer(_))));
+}
+
+#[tokio::test]
+async fn test_spawn_failure() {
+    let config = LocalMcpServerConfig {
+        command: "/nonexistent/mcp-server".to_string(),
+        args: vec![],
+        env: BTreeMap::new(),
+        timeout_secs: 10,
+    };
+    let result = McpStdioClient::start("broken", &config).await;
+    assert!(matches!(result, Err(McpError::Spawn { .. })));
+}
+
+// Tests against the fake server bin, built with `--features fake-mcp-server`
+#[cfg(feature = "fake-mcp-server")]
+mod fake_server {
+    use super::*;
+    use openai_api::api::models::{FunctionCall, InputItem};
+    use openai_api::api::tools::ToolDefinition;
+    use serde_json::json;
+
+    fn fake_server_config() -> LocalMcpServerConfig {
+        LocalMcpServerConfig {
+            command: env!("CARGO_BIN_EXE_fake-mcp-server").to_string(),
+            args: vec![],
+            env: BTreeMap::new(),
+            timeout_secs: 10,
+        }
+    }
+
+    fn function_call(name: &str, arguments: &str) -> FunctionCall {
+        FunctionCall {
+            id: Some("fc_1".to_string()),
+            call_id: "call_1".to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            status: Some("completed".to_string()),
+        }
+    }
+
+    fn output_of(item: InputItem) -> String {
+        match item {
+            InputItem::FunctionCallOutput { call_id, output } => {
+                assert_eq!(call_id, "call_1");
+                output
+            }
+            other => panic!("Expected function_call_output, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdio_client_lists_all_pages() {
+        let client = McpStdioClient::start("fake", &fake_server_config())
+            .await
+            .unwrap();
+
+        let names: Vec<String> = client
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        assert_eq!(names, vec!["echo", "add", "fail"]);
+
+        let result = client
+            .call_tool("echo", json!({ "text": "hello" }))
+            .await
+            .unwrap();
+        assert_eq!(result.to_output(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_host_maps_tools_to_functions() {
+        let mut configs = BTreeMap::new();
+        configs.insert("fake".to_string(), fake_server_config());
+        let host = LocalMcpHost::start(&configs).await.unwrap();
+
+        let tools = host.tool_definitions();
+        assert_eq!(tools.len(), 3);
+        match &tools[0] {
+            ToolDefinition::Function(function) => {
+                assert_eq!(function.name, "fake__echo");
+                assert_eq!(function.description.as_deref(), Some("Echo the given text"));
+                assert_eq!(function.parameters["required"][0], "text");
+            }
+            other => panic!("Expected function tool, got {:?}", other),
+        }
+        assert!(host.handles("fake__add"));
+        assert!(!host.handles("add"));
+    }
+
+    #[tokio::test]
+    async fn test_host_rejects_colliding_function_names() {
+        let mut configs = BTreeMap::new();
+        configs.insert("fake.1".to_string(), fake_server_config());
+        configs.insert("fake_1".to_string(), fake_server_config());
+
+        match LocalMcpHost::start(&configs).await {
+            Err(McpError::NameCollision {
+                function,
+                first,
+                second,
+            }) => {
+                assert_eq!(function, "fake_1__echo");
+                assert_eq!(first, "fake.1/echo");
+                assert_eq!(second, "fake_1/echo");
+            }
+            other => panic!("Expected a name collision, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_host_dispatches_function_calls() {
+        let mut configs = BTreeMap::new();
+        configs.insert("fake".to_string(), fake_server_config());
+        let host = LocalMcpHost::start(&configs).await.unwrap();
+
+        let sum = host
+            .call(&function_call("fake__add", r#"{"a": 2, "b": 3}"#))
+            .await
+            .unwrap();
+        assert_eq!(output_of(sum), "5");
+
+        let failed = host.call(&function_call("fake__fail", "{}")).await.unwrap();
+        assert_eq!(output_of(failed), "Error: something broke");
+
+        let bad_json = host
+            .call(&function_call("fake__echo", "{not json"))
+            .await
+            .unwrap();
+        assert!(output_of(bad_json).starts_with("Error: invalid JSON arguments"));
+
+        let unknown = host.call(&function_call("other__tool", "{}")).await;
+        assert!(matches!(unknown, Err(McpError::UnknownTool(_))));
+    }
+
+    #[cfg(feature = "mock-server")]
+    #[tokio::test]
+    async fn test_host_stops_after_max_turns() {
+        use openai_api::api::errors::OpenAIError;
+        use openai_api::api::models::ResponseRequest;
+        use openai_api::mock::{MockReply, MockServer};
+
+        let mut configs = BTreeMap::new();
+        configs.insert("fake".to_string(), fake_server_config());
+        let host = LocalMcpHost::start(&configs).await.unwrap();
+        let server = MockServer::start().await.unwrap();
+        for _ in 0..2 {
+            server.push(MockReply::function_call(
+                "fake__echo",
+                json!({ "text": "again" }),
+            ));
+        }
+        let client = server.client();
+
+        let request = ResponseRequest::builder()
+            .model("gpt-4.1".to_string())
+            .input_text("Loop".to_string())
+            .build();
+        let first = client.send_request(request.clone()).await.unwrap();
+        let result = host
+            .resolve_function_calls(&client, &request, first, 1)
+            .await;
+        assert!(matches!(
+            result,
+            Err(McpError::Api(OpenAIError::TooManyTurns(1)))
+        ));
+        server.assert_request_count(2);
+    }
+}
@@ -0,0 +1,319 @@
+#![cfg(feature = "mock-server")]
+
+use futures_util::TryStreamExt;
+use openai_api::api::errors::OpenAIError;
+use openai_api::api::middleware::ExtraHeaders;
+use openai_api::api::models::*;
+use openai_api::api::streaming::StreamEvent;
+use openai_api::mock::{MockReply, MockServer};
+use openai_api::utils::content::extract_response_content;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn request(prompt: &str) -> ResponseRequest {
+    ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text(prompt.to_string())
+        .build()
+}
+
+#[tokio::test]
+async fn test_scripted_text_reply() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text("Paris is the capital."));
+
+    let response = server
+        .client()
+        .send_request(request("Capital of France?"))
+        .await
+        .unwrap();
+    assert_eq!(extract_response_content(&response), "Paris is the capital.");
+    assert_eq!(response.model, "gpt-4.1");
+    assert!(response.id.starts_with("resp_mock_"));
+
+    server.assert_request_count(1);
+    server.assert_replies_used();
+    let received = server.last_request().unwrap();
+    assert_eq!(received.path, "/v1/responses");
+    assert_eq!(received.bearer_token(), Some("sk-mock"));
+    assert_eq!(received.field("/input"), Some(&json!("Capital of France?")));
+}
+
+#[tokio::test]
+async fn test_tool_call_turns() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .push(MockReply::function_call(
+            "get_weather",
+            json!({ "city": "Boston" }),
+        ))
+        .push(MockReply::text("It is sunny in Boston."));
+    let client = server.client();
+
+    let first = client.send_request(request("Weather?")).await.unwrap();
+    let call = first.function_calls().next().unwrap().clone();
+    assert_eq!(call.name, "get_weather");
+    assert_eq!(call.arguments, r#"{"city":"Boston"}"#);
+
+    let mut follow_up = request("Weather?");
+    follow_up.input = Some(InputType::Items(vec![InputItem::function_call_output(
+        &call,
+        "sunny".to_string(),
+    )]));
+    follow_up.previous_response_id = Some(first.id.clone());
+    let second = client.send_request(follow_up).await.unwrap();
+    assert_eq!(extract_response_content(&second), "It is sunny in Boston.");
+
+    let received = server.last_request().unwrap();
+    assert_eq!(
+        received.field("/previous_response_id"),
+        Some(&json!(first.id))
+    );
+    assert_eq!(
+        received.field("/input/0/call_id"),
+        Some(&json!(call.call_id))
+    );
+    assert_eq!(second.previous_response_id, Some(first.id));
+}
+
+#[tokio::test]
+async fn test_errors_carry_rate_limit_headers() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::rate_limited(2));
+    server.push(MockReply::error(
+        400,
+        "invalid_request_error",
+        None,
+        "Bad input",
+    ));
+    let client = server.client();
+
+    let error = client.send_request(request("Hi")).await.unwrap_err();
+    match &error {
+        OpenAIError::RateLimited { retry_after, .. } => {
+            assert_eq!(*retry_after, Some(Duration::from_secs(2)))
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(error.request_id(), Some("req_mock"));
+
+    let error = client.send_request(request("Hi")).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::InvalidRequest(_)));
+
+    // Running out of scripted replies is a server error
+    let error = client.send_request(request("Hi")).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::ServerError(_)));
+}
+
+#[tokio::test]
+async fn test_follow_ups_stop_after_max_turns() {
+    let approval = MockReply::Response(json!({
+        "status": "completed",
+        "output": [{
+            "type": "mcp_approval_request",
+            "id": "mcpr_1",
+            "server_label": "deepwiki",
+            "name": "ask_question",
+            "arguments": "{}"
+        }]
+    }));
+    let server = MockServer::start().await.unwrap();
+    for _ in 0..3 {
+        server.push(approval.clone());
+    }
+    let client = server.client();
+
+    let first = client.send_request(request("Ask")).await.unwrap();
+    let error = client
+        .resolve_mcp_approvals(&request("Ask"), first, |_| true, 2)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, OpenAIError::TooManyTurns(2)));
+    server.assert_request_count(3);
+
+    // Within the limit the final response comes back
+    server.push(approval).push(MockReply::text("Answered."));
+    let first = client.send_request(request("Ask")).await.unwrap();
+    let response = client
+        .resolve_mcp_approvals(&request("Ask"), first, |_| true, 2)
+        .await
+        .unwrap();
+    assert_eq!(extract_response_content(&response), "Answered.");
+}
+
+#[tokio::test]
+async fn test_background_status_transitions() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::background("Done thinking.", 1));
+    server.push(MockReply::background("Never finishes.", 5));
+    let client = server.client();
+
+    let mut background = request("Think hard");
+    background.background = Some(true);
+    let queued = client.send_request(background.clone()).await.unwrap();
+    assert_eq!(queued.status, Some(ResponseStatus::Queued));
+    assert!(queued.output.is_empty());
+
+    let polled = client.retrieve_response(&queued.id).await.unwrap();
+    assert_eq!(polled.status, Some(ResponseStatus::InProgress));
+    let polled = client.retrieve_response(&queued.id).await.unwrap();
+    assert_eq!(polled.status, Some(ResponseStatus::Completed));
+    assert_eq!(extract_response_content(&polled), "Done thinking.");
+
+    let other = client.send_request(background).await.unwrap();
+    let cancelled = client.cancel_response(&other.id).await.unwrap();
+    assert_eq!(cancelled.status, Some(ResponseStatus::Cancelled));
+    let polled = client.retrieve_response(&other.id).await.unwrap();
+    assert_eq!(polled.status, Some(ResponseStatus::Cancelled));
+}
+
+#[tokio::test]
+async fn test_stored_response_endpoints() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text("Stored."));
+    let client = server.client();
+
+    let response = client.send_request(request("Keep this")).await.unwrap();
+
+    let url = format!("{}/responses/{}/input_items", server.url(), response.id);
+    let items: Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+    assert_eq!(items["data"][0]["content"][0]["text"], "Keep this");
+
+    client.delete_response(&response.id).await.unwrap();
+    let error = client.retrieve_response(&response.id).await.unwrap_err();
+    assert!(matches!(error, OpenAIError::NotFound(_)));
+
+    // store: false responses are not kept
+    server.push(MockReply::text("Forgotten."));
+    let mut unstored = request("Forget this");
+    unstored.store = Some(false);
+    let response = client.send_request(unstored).await.unwrap();
+    assert!(client.retrieve_response(&response.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_middleware_headers_reach_every_endpoint() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text("Stored."));
+    let headers = ExtraHeaders::new().header("x-team", "search").unwrap();
+    let client = server.client().with_middleware(headers);
+
+    let response = client.send_request(request("Keep this")).await.unwrap();
+    client.retrieve_response(&response.id).await.unwrap();
+    client.cancel_response(&response.id).await.unwrap();
+    client.delete_response(&response.id).await.unwrap();
+
+    let received = server.requests();
+    let methods: Vec<&str> = received.iter().map(|r| r.method.as_str()).collect();
+    assert_eq!(methods, ["POST", "GET", "POST", "DELETE"]);
+    for request in &received {
+        assert_eq!(request.headers["x-team"], "search", "{}", request.path);
+    }
+}
+
+#[tokio::test]
+async fn test_sse_event_sequence() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text_stream("Hello there world"));
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/responses", server.url()))
+        .json(&json!({ "model": "gpt-4.1", "input": "Hi", "stream": true }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let body = response.text().await.unwrap();
+
+    let events: Vec<&str> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("event: "))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            "response.created",
+            "response.output_text.delta",
+            "response.output_text.delta",
+            "response.output_text.delta",
+            "response.output_text.done",
+            "response.completed",
+        ]
+    );
+
+    let completed: Value = serde_json::from_str(
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .next_back()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(completed["sequence_number"], 5);
+    assert_eq!(completed["response"]["model"], "gpt-4.1");
+}
+
+#[tokio::test]
+async fn test_client_streams_text() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text_stream("Hello there world"));
+    server.push(MockReply::text_stream("Collected."));
+    let client = server.client();
+
+    let events: Vec<StreamEvent> = client
+        .stream_request(request("Hi"))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let deltas: String = events.iter().filter_map(StreamEvent::text_delta).collect();
+    assert_eq!(deltas, "Hello there world");
+    let response = events.last().unwrap().response().unwrap();
+    assert_eq!(extract_response_content(&response), "Hello there world");
+    assert_eq!(
+        server.last_request().unwrap().field("/stream"),
+        Some(&json!(true))
+    );
+
+    // The streamed response is stored like any other
+    let stored = client.retrieve_response(&response.id).await.unwrap();
+    assert_eq!(stored.status, Some(ResponseStatus::Completed));
+
+    let response = client
+        .get_streaming_response("gpt-4.1", "Hi", None)
+        .await
+        .unwrap();
+    assert_eq!(extract_response_content(&response), "Collected.");
+    server.assert_replies_used();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cli_end_to_end() {
+    let server = MockServer::start().await.unwrap();
+    server.push(MockReply::text("Hello from the mock."));
+    let data_dir = std::env::temp_dir().join(format!("openai-cli-mock-{}", std::process::id()));
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_openai-api"))
+        .args(["--model", "gpt-4.1", "--metadata", "team=qa", "Say hello"])
+        .env("OPENAI_API_KEY", "sk-cli")
+        .env("OPENAI_BASE_URL", server.url())
+        .env("OPENAI_CLI_CONFIG", data_dir.join("missing.toml"))
+        .env("OPENAI_CLI_DATA_DIR", &data_dir)
+        .env_remove("OPENAI_API_CASSETTE")
+        .output()
+        .await
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Hello from the mock."));
+
+    let received = server.last_request().unwrap();
+    assert_eq!(received.bearer_token(), Some("sk-cli"));
+    assert_eq!(received.field("/model"), Some(&json!("gpt-4.1")));
+    assert_eq!(received.field("/metadata/team"), Some(&json!("qa")));
+    let _ = std::fs::remove_dir_all(&data_dir);
+}
@@ -0,0 +1,146 @@
+use openai_api::prompts::library::{LocalPrompt, PromptLibrary};
+use std::fs;
+use std::path::PathBuf;
+
+const SUMMARY_TOML: &str = r#"
+description = "Summarize a document"
+version = "3"
+template = "Summarize {{document}} in {{ length }} sentences."
+
+[variables.document]
+description = "Text to summarize"
+
+[variables.length]
+default = "three"
+"#;
+
+const REVIEW_MD: &str = "+++
+description = \"Review code\"
+
+[variables.language]
+default = \"Rust\"
++++
+
+Review this {{language}} code:
+
+{{code}}
+";
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn temp_library(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "openai-cli-prompts-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_toml_prompt() {
+    let prompt = LocalPrompt::from_toml("summary", SUMMARY_TOML).unwrap();
+
+    assert_eq!(prompt.meta.version.as_deref(), Some("3"));
+    assert_eq!(prompt.placeholders(), vec!["document", "length"]);
+    assert_eq!(
+        prompt.render(&vars(&[("document", "the report")])).unwrap(),
+        "Summarize the report in three sentences."
+    );
+    assert_eq!(
+        prompt
+            .render(&vars(&[("document", "it"), ("length", "two")]))
+            .unwrap(),
+        "Summarize it in two sentences."
+    );
+}
+
+#[test]
+fn test_markdown_front_matter() {
+    let prompt = LocalPrompt::from_markdown("review", REVIEW_MD).unwrap();
+
+    assert_eq!(prompt.meta.description.as_deref(), Some("Review code"));
+    assert_eq!(
+        prompt.template,
+        "Review this {{language}} code:\n\n{{code}}"
+    );
+    assert_eq!(
+        prompt.render(&vars(&[("code", "fn main() {}")])).unwrap(),
+        "Review this Rust code:\n\nfn main() {}"
+    );
+
+    // Front matter is TOML; YAML between `---` lines gets a clear error
+    let yaml = LocalPrompt::from_markdown("yaml", "---\nversion: 1\n---\nHi {{name}}")
+        .unwrap_err()
+        .to_string();
+    assert!(yaml.contains("`---` (YAML) front matter"));
+    let invalid = LocalPrompt::from_markdown("broken", "+++\nversion: 1\n+++\nHi")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(invalid, "Invalid TOML front matter in prompt 'broken'");
+
+    let bare = LocalPrompt::from_markdown("bare", "Just {{thing}}\n").unwrap();
+    assert_eq!(bare.meta, Default::default());
+    assert_eq!(bare.template, "Just {{thing}}");
+}
+
+#[test]
+fn test_render_errors() {
+    let prompt = LocalPrompt::from_toml("summary", SUMMARY_TOML).unwrap();
+
+    let missing = prompt.render(&[]).unwrap_err().to_string();
+    assert!(missing.contains("Missing value for document"));
+
+    let unknown = prompt
+        .render(&vars(&[("document", "x"), ("lenght", "two")]))
+        .unwrap_err()
+        .to_string();
+    assert!(unknown.contains("no variable 'lenght'"));
+}
+
+#[test]
+fn test_non_placeholder_braces_are_kept() {
+    let prompt =
+        LocalPrompt::from_markdown("json", "Return {{ \"a\": 1 }} for {{name}}, not {{}}").unwrap();
+
+    assert_eq!(prompt.placeholders(), vec!["name"]);
+    assert_eq!(
+        prompt.render(&vars(&[("name", "x")])).unwrap(),
+        "Return {{ \"a\": 1 }} for x, not {{}}"
+    );
+}
+
+#[test]
+fn test_library_list_and_get() {
+    let dir = temp_library("list");
+    let library = PromptLibrary::new(dir.clone());
+    assert!(library.list().unwrap().is_empty());
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("summary.toml"), SUMMARY_TOML).unwrap();
+    fs::write(dir.join("review.md"), REVIEW_MD).unwrap();
+    fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+    let names: Vec<String> = library
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|prompt| prompt.name)
+        .collect();
+    assert_eq!(names, vec!["review", "summary"]);
+
+    assert_eq!(library.get("review").unwrap().name, "review");
+    assert!(library
+        .get("missing")
+        .unwrap_err()
+        .to_string()
+        .contains("No prompt named 'missing'"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,116 @@
+//! Minimal MCP server speaking newline-delimited JSON-RPC over stdio.
+//!
+//! Tools: `echo` returns its `text` argument, `add` sums `a` and `b`, and
+//! `fail` reports a tool error. `tools/list` is split over two pages to
+//! exercise cursor handling.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+
+        // Notifications carry no id and get no reply
+        let Some(id) = message.get("id").cloned() else {
+            continue;
+        };
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let reply = match method {
+            "initialize" => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "protocolVersion": params["protocolVersion"],
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "fake-mcp-server", "version": "0.1.0" }
+                }
+            }),
+            "tools/list" => json!({ "jsonrpc": "2.0", "id": id, "result": list_tools(params) }),
+            "tools/call" => match call_tool(params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(message) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32602, "message": message }
+                }),
+            },
+            _ => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("Method not found: {}", method) }
+            }),
+        };
+
+        // Interleave a notification to make sure clients skip it
+        writeln!(
+            stdout,
+            "{}",
+            json!({ "jsonrpc": "2.0", "method": "notifications/message", "params": {} })
+        )
+        .unwrap();
+        writeln!(stdout, "{}", reply).unwrap();
+        stdout.flush().unwrap();
+    }
+}
+
+fn list_tools(params: &Value) -> Value {
+    if params.get("cursor").and_then(Value::as_str) == Some("page-2") {
+        return json!({
+            "tools": [{
+                "name": "fail",
+                "description": "Always fails",
+                "inputSchema": { "type": "object", "properties": {} }
+            }]
+        });
+    }
+
+    json!({
+        "tools": [
+            {
+                "name": "echo",
+                "description": "Echo the given text",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": { "text": { "type": "string" } },
+                    "required": ["text"]
+                }
+            },
+            {
+                "name": "add",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": { "a": { "type": "number" }, "b": { "type": "number" } }
+                }
+            }
+        ],
+        "nextCursor": "page-2"
+    })
+}
+
+fn call_tool(params: &Value) -> Result<Value, String> {
+    let arguments = &params["arguments"];
+    match params["name"].as_str() {
+        Some("echo") => Ok(json!({
+            "content": [{ "type": "text", "text": arguments["text"] }]
+        })),
+        Some("add") => {
+            let sum =
+                arguments["a"].as_f64().unwrap_or(0.0) + arguments["b"].as_f64().unwrap_or(0.0);
+            Ok(json!({ "content": [{ "type": "text", "text": sum.to_string() }] }))
+        }
+        Some("fail") => Ok(json!({
+            "content": [{ "type": "text", "text": "something broke" }],
+            "isError": true
+        })),
+        other => Err(format!("Unknown tool: {:?}", other)),
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use openai_api::api::errors::OpenAIError;
+use openai_api::api::models::{ResponseApiResponse, ResponseRequest};
+use openai_api::api::pricing::PricingTable;
+use openai_api::api::transport::{HttpRequest, HttpResponse, Transport};
+use openai_api::usage::ledger::{utc_date, Ledger, LedgerEntry, UsageRecorder};
+use openai_api::usage::report::{aggregate, parse_day, total, GroupBy};
+use openai_api::OpenAIClient;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// 2025-03-09T00:29:02Z
+const MARCH_9: u64 = 1741480142;
+const SECONDS_PER_DAY: u64 = 86_400;
+
+fn temp_ledger(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("openai-cli-usage-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join("usage.jsonl")
+}
+
+fn entry(timestamp: u64, model: &str, profile: &str, team: Option<&str>, cost: f64) -> LedgerEntry {
+    LedgerEntry {
+        timestamp,
+        response_id: format!("resp_{}", timestamp),
+        model: model.to_string(),
+        service_tier: None,
+        input_tokens: 100,
+        cached_tokens: 20,
+        output_tokens: 50,
+        reasoning_tokens: 0,
+        cost: Some(cost),
+        metadata: team
+            .map(|team| BTreeMap::from([("team".to_string(), team.to_string())]))
+            .unwrap_or_default(),
+        profile: profile.to_string(),
+    }
+}
+
+#[test]
+fn test_utc_date() {
+    assert_eq!(utc_date(0), "1970-01-01");
+    assert_eq!(utc_date(MARCH_9), "2025-03-09");
+    assert_eq!(utc_date(951_782_400), "2000-02-29");
+    assert_eq!(utc_date(4_107_542_399), "2100-02-28");
+}
+
+#[test]
+fn test_entry_from_response() {
+    let mut response: ResponseApiResponse = serde_json::from_value(json!({
+        "id": "resp_1",
+        "created_at": MARCH_9,
+        "model": "gpt-4.1",
+        "usage": {
+            "input_tokens": 1000,
+            "input_tokens_details": { "cached_tokens": 200 },
+            "output_tokens": 300,
+            "output_tokens_details": { "reasoning_tokens": 100 },
+            "total_tokens": 1300
+        }
+    }))
+    .unwrap();
+    response.cost = PricingTable::default().cost(&response);
+
+    let metadata = BTreeMap::from([("team".to_string(), "search".to_string())]);
+    let entry = LedgerEntry::new(&response, "work", metadata.clone());
+
+    assert_eq!(entry.timestamp, MARCH_9);
+    assert_eq!(entry.day(), "2025-03-09");
+    assert_eq!(entry.month(), "2025-03");
+    assert_eq!(entry.cached_tokens, 200);
+    assert_eq!(entry.reasoning_tokens, 100);
+    assert_eq!(entry.cost, Some(response.cost.unwrap().total));
+    assert_eq!(entry.metadata, metadata);
+    assert_eq!(entry.profile, "work");
+}
+
+// Answers with the given bodies in turn
+struct Replies(Mutex<VecDeque<Value>>);
+
+#[async_trait]
+impl Transport for Replies {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, OpenAIError> {
+        let body = self.0.lock().unwrap().pop_front().unwrap();
+        Ok(HttpResponse::new(
+            StatusCode::OK,
+            HeaderMap::new(),
+            body.to_string(),
+        ))
+    }
+}
+
+fn reply(id: &str, status: &str, output_tokens: u32) -> Value {
+    json!({
+        "id": id,
+        "status": status,
+        "incomplete_details": (status == "incomplete").then(|| json!({ "reason": "max_output_tokens" })),
+        "model": "gpt-4.1",
+        "output": [],
+        "usage": { "input_tokens": 100, "output_tokens": output_tokens, "total_tokens": 100 + output_tokens }
+    })
+}
+
+#[tokio::test]
+async fn test_recorder_records_every_call() {
+    let path = temp_ledger("recorder");
+    let recorder = Arc::new(UsageRecorder::new(
+        Ledger::new(path.clone()),
+        PricingTable::default(),
+        "work",
+        BTreeMap::new(),
+    ));
+    let replies = [
+        reply("resp_1", "incomplete", 50),
+        reply("resp_2", "completed", 20),
+    ];
+    let client = OpenAIClient::new("sk-test".to_string())
+        .with_transport(Replies(Mutex::new(replies.into())))
+        .with_middleware(recorder.clone());
+    let request = ResponseRequest::builder()
+        .model("gpt-4.1".to_string())
+        .input_text("Hi".to_string())
+        .build();
+
+    // The continuation is merged into one response but made two calls
+    let response = client.send_request(request.clone()).await.unwrap();
+    let response = client
+        .continue_truncated(&request, response, 3)
+        .await
+        .unwrap();
+    assert_eq!(response.usage.output_tokens, 70);
+
+    let entries = Ledger::new(path.clone()).entries().unwrap();
+    let ids: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.response_id.as_str())
+        .collect();
+    assert_eq!(ids, ["resp_1", "resp_2"]);
+    assert_eq!(entries[1].output_tokens, 20);
+    assert_eq!(entries[1].profile, "work");
+    // gpt-4.1 list prices: $2 in, $8 out per million tokens
+    let cost = entries[0].cost.unwrap();
+    assert!((cost - (100.0 * 2.0 + 50.0 * 8.0) / 1e6).abs() < 1e-12);
+    assert!(recorder.take_failures().is_empty());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_spending_by_day_and_month() {
+    let path = temp_ledger("spending");
+    let ledger = Ledger::new(path.clone());
+    assert_eq!(ledger.spending(MARCH_9).unwrap().month, 0.0);
+
+    ledger
+        .append(&entry(
+            MARCH_9 - 40 * SECONDS_PER_DAY,
+            "gpt-4.1",
+            "default",
+            None,
+            5.0,
+        ))
+        .unwrap();
+    ledger
+        .append(&entry(
+            MARCH_9 - 2 * SECONDS_PER_DAY,
+            "gpt-4.1",
+            "default",
+            None,
+            1.5,
+        ))
+        .unwrap();
+    ledger
+        .append(&entry(MARCH_9, "gpt-4.1", "default", None, 0.25))
+        .unwrap();
+
+    let spending = ledger.spending(MARCH_9 + 60).unwrap();
+    assert_eq!(spending.day, 0.25);
+    assert_eq!(spending.month, 1.75);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_aggregate() {
+    let entries = vec![
+        entry(MARCH_9, "gpt-4.1", "work", Some("search"), 1.0),
+        entry(MARCH_9 + 60, "gpt-4o-mini", "work", Some("ads"), 0.5),
+        entry(MARCH_9 + SECONDS_PER_DAY, "gpt-4.1", "home", None, 2.0),
+    ];
+
+    let by_day = aggregate(&entries, &GroupBy::Day, None, None);
+    assert_eq!(by_day.len(), 2);
+    assert_eq!(by_day[0].key, "2025-03-09");
+    assert_eq!(by_day[0].calls, 2);
+    assert_eq!(by_day[0].input_tokens, 200);
+    assert_eq!(by_day[0].cost, 1.5);
+
+    let by_model = aggregate(&entries, &GroupBy::Model, None, None);
+    let keys: Vec<&str> = by_model.iter().map(|row| row.key.as_str()).collect();
+    assert_eq!(keys, vec!["gpt-4.1", "gpt-4o-mini"]);
+    assert_eq!(by_model[0].cost, 3.0);
+
+    let by_team = aggregate(&entries, &GroupBy::Metadata("team".to_string()), None, None);
+    let keys: Vec<&str> = by_team.iter().map(|row| row.key.as_str()).collect();
+    assert_eq!(keys, vec!["-", "ads", "search"]);
+
+    let by_profile = aggregate(&entries, &GroupBy::Profile, Some("2025-03-10"), None);
+    assert_eq!(by_profile.len(), 1);
+    assert_eq!(by_profile[0].key, "home");
+    assert!(aggregate(&entries, &GroupBy::Day, None, Some("2025-03-08")).is_empty());
+
+    let total = total(&by_day);
+    assert_eq!(total.key, "total");
+    assert_eq!(total.calls, 3);
+    assert_eq!(total.cached_tokens, 60);
+    assert_eq!(total.cost, 3.5);
+}
+
+#[test]
+fn test_parse_group_by_and_day() {
+    assert_eq!("model".parse::<GroupBy>(), Ok(GroupBy::Model));
+    assert_eq!(
+        "metadata:team".parse::<GroupBy>(),
+        Ok(GroupBy::Metadata("team".to_string()))
+    );
+    assert!("metadata:".parse::<GroupBy>().is_err());
+    assert!("week".parse::<GroupBy>().is_err());
+
+    assert_eq!(parse_day("2025-03-09"), Ok("2025-03-09".to_string()));
+    assert!(parse_day("2025-3-9").is_err());
+    assert!(parse_day("March 9").is_err());
+}
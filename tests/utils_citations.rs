@@ -0,0 +1,149 @@
+use openai_api::api::models::{Annotation, ResponseApiResponse};
+use openai_api::utils::citations::{cite_text, extract_cited_content, format_sources, hyperlink};
+use serde_json::json;
+
+fn url_citation(url: &str, title: Option<&str>, start: usize, end: usize) -> Annotation {
+    Annotation::UrlCitation {
+        url: url.to_string(),
+        title: title.map(str::to_string),
+        start_index: start,
+        end_index: end,
+    }
+}
+
+#[test]
+fn test_annotation_deserialization() {
+    let annotations: Vec<Annotation> = serde_json::from_value(json!([
+        {
+            "type": "url_citation",
+            "url": "https://www.rust-lang.org",
+            "title": "Rust",
+            "start_index": 0,
+            "end_index": 4
+        },
+        { "type": "file_citation", "file_id": "file_1", "filename": "notes.pdf", "index": 10 },
+        {
+            "type": "container_file_citation",
+            "container_id": "cntr_1",
+            "file_id": "cfile_1",
+            "filename": "plot.png",
+            "start_index": 5,
+            "end_index": 9
+        },
+        { "type": "file_path", "file_id": "file_2", "index": 3 },
+        { "type": "brand_new_annotation", "anything": true }
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        annotations[0],
+        url_citation("https://www.rust-lang.org", Some("Rust"), 0, 4)
+    );
+    assert_eq!(annotations[1].marker_index(), Some(10));
+    assert_eq!(annotations[2].marker_index(), Some(9));
+    assert_eq!(annotations[3].marker_index(), Some(3));
+    // Unknown annotations keep their payload and serialize back unchanged
+    let unknown = json!({ "type": "brand_new_annotation", "anything": true });
+    assert_eq!(annotations[4], Annotation::Other(unknown.clone()));
+    assert_eq!(serde_json::to_value(&annotations[4]).unwrap(), unknown);
+}
+
+#[test]
+fn test_cite_text_inserts_markers() {
+    let text = "Rust is fast. Rust is safe.";
+    let annotations = vec![
+        url_citation("https://a.example", Some("A"), 0, 13),
+        url_citation("https://b.example", Some("B"), 14, 27),
+        url_citation("https://a.example", Some("A"), 14, 27),
+    ];
+
+    let mut sources = Vec::new();
+    let cited = cite_text(text, &annotations, &mut sources);
+
+    assert_eq!(cited, "Rust is fast.[1] Rust is safe.[1][2]");
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0].title, "A");
+    assert_eq!(sources[1].url.as_deref(), Some("https://b.example"));
+}
+
+#[test]
+fn test_cite_text_uses_character_offsets() {
+    let text = "Café ☕ is open.";
+    let annotations = vec![url_citation("https://cafe.example", None, 0, 6)];
+
+    let mut sources = Vec::new();
+    assert_eq!(
+        cite_text(text, &annotations, &mut sources),
+        "Café ☕[1] is open."
+    );
+    assert_eq!(sources[0].title, "https://cafe.example");
+}
+
+#[test]
+fn test_extract_cited_content_numbers_across_parts() {
+    let response: ResponseApiResponse = serde_json::from_value(json!({
+        "id": "resp_1",
+        "object": "response",
+        "created_at": 1741476542,
+        "status": "completed",
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [
+                {
+                    "type": "output_text",
+                    "text": "First.",
+                    "annotations": [
+                        { "type": "url_citation", "url": "https://a.example", "title": "A", "start_index": 0, "end_index": 6 }
+                    ]
+                },
+                {
+                    "type": "output_text",
+                    "text": "Second.",
+                    "annotations": [
+                        { "type": "file_citation", "file_id": "file_1", "filename": "notes.pdf", "index": 7 }
+                    ]
+                }
+            ]
+        }],
+        "parallel_tool_calls": true,
+        "store": true,
+        "temperature": 1.0,
+        "text": { "format": { "type": "text" } },
+        "tool_choice": "auto",
+        "tools": [],
+        "top_p": 1.0,
+        "truncation": "disabled",
+        "usage": { "input_tokens": 1, "output_tokens": 2, "total_tokens": 3 }
+    }))
+    .unwrap();
+
+    let (content, sources) = extract_cited_content(&response);
+    assert_eq!(content, "First.[1] Second.[2]");
+    assert_eq!(
+        format_sources(&sources, false),
+        vec!["[1] A (https://a.example)", "[2] notes.pdf"]
+    );
+}
+
+#[test]
+fn test_format_sources_with_hyperlinks() {
+    let mut sources = Vec::new();
+    cite_text(
+        "Docs",
+        &[url_citation("https://docs.rs", Some("docs.rs"), 0, 4)],
+        &mut sources,
+    );
+
+    assert_eq!(
+        format_sources(&sources, true),
+        vec![format!("[1] {}", hyperlink("docs.rs", "https://docs.rs"))]
+    );
+    assert_eq!(
+        hyperlink("docs.rs", "https://docs.rs"),
+        "\x1b]8;;https://docs.rs\x1b\\docs.rs\x1b]8;;\x1b\\"
+    );
+}
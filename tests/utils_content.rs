@@ -1,5 +1,6 @@
 use openai_api::api::models::{
-    ContentType, OutputMessage, ResponseApiResponse, TextConfig, TextFormat, Usage,
+    ContentType, ItemStatus, OutputItem, OutputMessage, ResponseApiResponse, ResponseStatus, Role,
+    TextConfig, TextFormat, TextFormatType, ToolChoice, Truncation, Usage,
 };
 use openai_api::utils::content::{extract_content_from_array, extract_response_content};
 
@@ -9,35 +10,38 @@ fn test_extract_response_content() {
         id: "test-123".to_string(),
         object: "response".to_string(),
         created_at: 1741476542,
-        status: "completed".to_string(),
+        status: Some(ResponseStatus::Completed),
         error: None,
         incomplete_details: None,
         instructions: None,
         max_output_tokens: None,
         model: "gpt-4.1".to_string(),
-        output: vec![OutputMessage {
-            message_type: "message".to_string(),
+        output: vec![OutputItem::Message(OutputMessage {
             id: "msg-123".to_string(),
-            status: "completed".to_string(),
-            role: "assistant".to_string(),
+            status: ItemStatus::Completed,
+            role: Role::Assistant,
             content: vec![ContentType::Text {
                 text: "Simple message".to_string(),
             }],
-        }],
-        parallel_tool_calls: true,
+        })],
+        parallel_tool_calls: Some(true),
         previous_response_id: None,
+        prompt: None,
         reasoning: None,
-        store: true,
-        temperature: 1.0,
-        text: TextConfig {
+        service_tier: None,
+        store: Some(true),
+        temperature: Some(1.0),
+        text: Some(TextConfig {
             format: TextFormat {
-                format_type: "text".to_string(),
+                format_type: TextFormatType::Text,
+                extra: Default::default(),
             },
-        },
-        tool_choice: "auto".to_string(),
+            extra: Default::default(),
+        }),
+        tool_choice: Some(ToolChoice::auto()),
         tools: vec![],
-        top_p: 1.0,
-        truncation: "disabled".to_string(),
+        top_p: Some(1.0),
+        truncation: Some(Truncation::Disabled),
         usage: Usage {
             input_tokens: 10,
             output_tokens: 20,
@@ -47,6 +51,9 @@ fn test_extract_response_content() {
         },
         user: None,
         metadata: None,
+        cost: None,
+        cached: false,
+        extra: Default::default(),
     };
 
     assert_eq!(extract_response_content(&response), "Simple message");
@@ -61,6 +68,7 @@ fn test_extract_complex_content() {
         ContentType::OutputText {
             text: "World".to_string(),
             annotations: vec![],
+            logprobs: vec![],
         },
     ];
 
@@ -73,27 +81,31 @@ fn test_extract_no_content() {
         id: "test-123".to_string(),
         object: "response".to_string(),
         created_at: 1741476542,
-        status: "completed".to_string(),
+        status: Some(ResponseStatus::Completed),
         error: None,
         incomplete_details: None,
         instructions: None,
         max_output_tokens: None,
         model: "gpt-4.1".to_string(),
         output: vec![],
-        parallel_tool_calls: true,
+        parallel_tool_calls: Some(true),
         previous_response_id: None,
+        prompt: None,
         reasoning: None,
-        store: true,
-        temperature: 1.0,
-        text: TextConfig {
+        service_tier: None,
+        store: Some(true),
+        temperature: Some(1.0),
+        text: Some(TextConfig {
             format: TextFormat {
-                format_type: "text".to_string(),
+                format_type: TextFormatType::Text,
+                extra: Default::default(),
             },
-        },
-        tool_choice: "auto".to_string(),
+            extra: Default::default(),
+        }),
+        tool_choice: Some(ToolChoice::auto()),
         tools: vec![],
-        top_p: 1.0,
-        truncation: "disabled".to_string(),
+        top_p: Some(1.0),
+        truncation: Some(Truncation::Disabled),
         usage: Usage {
             input_tokens: 10,
             output_tokens: 20,
@@ -103,6 +115,9 @@ fn test_extract_no_content() {
         },
         user: None,
         metadata: None,
+        cost: None,
+        cached: false,
+        extra: Default::default(),
     };
 
     assert_eq!(extract_response_content(&response), "No content available");
@@ -129,6 +144,7 @@ fn test_extract_mixed_content() {
         ContentType::OutputText {
             text: "Third".to_string(),
             annotations: vec![],
+            logprobs: vec![],
         },
     ];
 
@@ -0,0 +1,116 @@
+use openai_api::api::models::{ResponseApiResponse, TokenLogprob};
+use openai_api::cli::output::OutputFormatter;
+use openai_api::utils::logprobs::{collect_logprobs, perplexity, Confidence};
+use serde_json::json;
+
+fn response_with_logprobs() -> ResponseApiResponse {
+    serde_json::from_value(json!({
+        "id": "resp_1",
+        "object": "response",
+        "created_at": 1741476542,
+        "status": "completed",
+        "model": "gpt-4.1",
+        "output": [{
+            "type": "message",
+            "id": "msg_1",
+            "status": "completed",
+            "role": "assistant",
+            "content": [{
+                "type": "output_text",
+                "text": "Hello world",
+                "annotations": [],
+                "logprobs": [
+                    {
+                        "token": "Hello",
+                        "logprob": -0.01,
+                        "bytes": [72, 101, 108, 108, 111],
+                        "top_logprobs": [
+                            { "token": "Hello", "logprob": -0.01 },
+                            { "token": "Hi", "logprob": -4.6 }
+                        ]
+                    },
+                    {
+                        "token": " world",
+                        "logprob": -1.2,
+                        "top_logprobs": [
+                            { "token": " there", "logprob": -0.6 },
+                            { "token": " world", "logprob": -1.2 }
+                        ]
+                    }
+                ]
+            }]
+        }],
+        "parallel_tool_calls": true,
+        "store": true,
+        "temperature": 1.0,
+        "text": { "format": { "type": "text" } },
+        "tool_choice": "auto",
+        "tools": [],
+        "top_p": 1.0,
+        "truncation": "disabled",
+        "usage": { "input_tokens": 1, "output_tokens": 2, "total_tokens": 3 }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_collect_logprobs() {
+    let response = response_with_logprobs();
+    let tokens = collect_logprobs(&response);
+
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].token, "Hello");
+    assert_eq!(tokens[0].bytes, Some(vec![72, 101, 108, 108, 111]));
+    assert_eq!(tokens[1].top_logprobs[0].token, " there");
+}
+
+#[test]
+fn test_perplexity() {
+    let response = response_with_logprobs();
+    let tokens = collect_logprobs(&response);
+
+    let expected = ((0.01 + 1.2) / 2.0_f64).exp();
+    assert!((perplexity(&tokens).unwrap() - expected).abs() < 1e-9);
+    assert_eq!(perplexity(&[]), None);
+}
+
+#[test]
+fn test_confidence_levels() {
+    assert_eq!(Confidence::from_probability(0.95), Confidence::High);
+    assert_eq!(Confidence::from_probability(0.9), Confidence::High);
+    assert_eq!(Confidence::from_probability(0.6), Confidence::Medium);
+    assert_eq!(Confidence::from_probability(0.1), Confidence::Low);
+}
+
+#[test]
+fn test_alternatives_table() {
+    let response = response_with_logprobs();
+    let tokens = collect_logprobs(&response);
+
+    let table = OutputFormatter::format_alternatives_table(&tokens);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[0], "Token       Prob  Alternatives");
+    assert_eq!(lines[1], "\"Hello\"    99.0%  \"Hi\" 1.0%");
+    assert_eq!(lines[2], "\" world\"   30.1%  \" there\" 54.9%");
+}
+
+#[test]
+fn test_colorize_tokens() {
+    let high = TokenLogprob {
+        token: "sure".to_string(),
+        logprob: -0.001,
+        bytes: None,
+        top_logprobs: vec![],
+    };
+    let low = TokenLogprob {
+        token: "maybe".to_string(),
+        logprob: -3.0,
+        bytes: None,
+        top_logprobs: vec![],
+    };
+
+    let colored = OutputFormatter::colorize_tokens(&[&high, &low]);
+    assert!(colored.contains("sure"));
+    assert!(colored.contains("maybe"));
+    assert!(colored.contains('\x1b'));
+}
@@ -0,0 +1,103 @@
+use openai_api::api::models::*;
+use openai_api::utils::tokens::{
+    context_window, count_input_tokens, count_item_tokens, count_request_tokens, ContextUsage,
+    Encoding,
+};
+use serde_json::json;
+
+#[test]
+fn test_count_tokens() {
+    assert_eq!(Encoding::O200kBase.count_tokens(""), 0);
+    assert_eq!(Encoding::O200kBase.count_tokens("hello world"), 2);
+    assert_eq!(Encoding::Cl100kBase.count_tokens("hello world"), 2);
+
+    // Special-token markers in user text are ordinary text
+    assert!(Encoding::O200kBase.count_tokens("<|endoftext|>") > 1);
+}
+
+#[test]
+fn test_encoding_for_model() {
+    assert_eq!(Encoding::for_model("gpt-4o-mini"), Encoding::O200kBase);
+    assert_eq!(
+        Encoding::for_model("gpt-4.1-2025-04-14"),
+        Encoding::O200kBase
+    );
+    assert_eq!(Encoding::for_model("o4-mini"), Encoding::O200kBase);
+    assert_eq!(
+        Encoding::for_model("some-future-model"),
+        Encoding::O200kBase
+    );
+    assert_eq!(Encoding::for_model("gpt-4"), Encoding::Cl100kBase);
+    assert_eq!(Encoding::for_model("gpt-4-turbo"), Encoding::Cl100kBase);
+    assert_eq!(Encoding::for_model("gpt-3.5-turbo"), Encoding::Cl100kBase);
+}
+
+#[test]
+fn test_context_window() {
+    assert_eq!(context_window("gpt-4o-mini"), Some(128_000));
+    assert_eq!(context_window("gpt-4.1-nano"), Some(1_047_576));
+    assert_eq!(context_window("gpt-4-turbo-preview"), Some(128_000));
+    assert_eq!(context_window("gpt-4-0613"), Some(8_192));
+    // 128k snapshots of gpt-4 are not held to its 8k window
+    assert_eq!(context_window("gpt-4-0125-preview"), Some(128_000));
+    assert_eq!(context_window("gpt-4-1106-preview"), Some(128_000));
+    // Families end at a `-`, as for prices
+    assert_eq!(context_window("gpt-4.1"), Some(1_047_576));
+    assert_eq!(context_window("gpt-4o-2024-08-06"), Some(128_000));
+    assert_eq!(context_window("o3x"), None);
+    assert_eq!(context_window("o1-mini"), Some(128_000));
+    assert_eq!(context_window("o3-pro"), Some(200_000));
+    assert_eq!(context_window("ft:custom"), None);
+}
+
+#[test]
+fn test_count_typed_input() {
+    let encoding = Encoding::O200kBase;
+    let text = count_input_tokens(&InputType::Text("hello world".to_string()), encoding);
+    assert_eq!(text, 2 + 4);
+
+    let output = InputItem::FunctionCallOutput {
+        call_id: "call_1".to_string(),
+        output: "hello world".to_string(),
+    };
+    assert_eq!(count_item_tokens(&output, encoding), 2);
+
+    let messages = InputType::Array(vec![json!({
+        "role": "user",
+        "content": [
+            { "type": "input_text", "text": "hello world" },
+            { "type": "input_image", "image_url": "https://example.com/a.png" }
+        ]
+    })]);
+    let with_image = count_input_tokens(&messages, encoding);
+    assert!(with_image > text + 100);
+}
+
+#[test]
+fn test_context_usage() {
+    let request = ResponseRequest::builder()
+        .model("gpt-4".to_string())
+        .instructions("Be brief.".to_string())
+        .input_text("hello world".to_string())
+        .max_output_tokens(8_000)
+        .build();
+
+    let usage = ContextUsage::of(&request).unwrap();
+    assert_eq!(usage.window, 8_192);
+    assert_eq!(usage.input_tokens, count_request_tokens(&request));
+    assert_eq!(usage.total(), usage.input_tokens + 8_000);
+    assert!(!usage.exceeds_window());
+
+    let long = ResponseRequest::builder()
+        .model("gpt-4".to_string())
+        .input_text("hello ".repeat(300))
+        .max_output_tokens(8_000)
+        .build();
+    assert!(ContextUsage::of(&long).unwrap().exceeds_window());
+
+    let unknown = ResponseRequest::builder()
+        .model("ft:custom".to_string())
+        .input_text("hello".to_string())
+        .build();
+    assert!(ContextUsage::of(&unknown).is_none());
+}